@@ -0,0 +1,997 @@
+use crate::case::*;
+use crate::loadflow::solve_sparse;
+use rsparse::data::Trpl;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/// Newton-Raphson iterations allowed before the AC load flow gives up.
+const MAX_ITERATIONS: usize = 30;
+/// Largest bus mismatch in pu accepted as converged.
+const TOLERANCE: f64 = 1e-6;
+/// Newton solves allowed for the HVDC converter injections to settle.
+const MAX_DC_PASSES: usize = 20;
+/// Passes of the LCC control sequence allowed for the DC current to settle.
+const MAX_CONTROL_PASSES: usize = 50;
+/// No-load DC voltage of a six-pulse bridge per kV of AC line voltage, 3√2/π.
+const BRIDGE_VOLTAGE: f32 = 1.350_474_2;
+
+/// Bus admittance matrix in pu as rows of (column, G, B).
+type YBus = Vec<Vec<(usize, f64, f64)>>;
+
+/// Reactive limits in MVAR, as (min, max).
+type QLimits = (f32, f32);
+
+/// Voltage-dependent load at a bus in pu: constant power, current and
+/// admittance parts of P and of Q.
+#[derive(Default, Clone, Copy)]
+struct ZipLoad {
+    p: [f64; 3],
+    q: [f64; 3],
+}
+
+/// A converged AC load flow over the in-service buses.
+struct AcSolution {
+    index: HashMap<usize, usize>,
+    system: AcSystem,
+    v: Vec<f64>,
+    theta: Vec<f64>,
+    /// HVDC, flow-controlled FACTS and regulating source P and Q injected at
+    /// each bus in pu
+    fixed_p: Vec<f64>,
+    fixed_q: Vec<f64>,
+    dc_lines: Vec<Option<DcLineSolution>>,
+    /// MVAR of each VSC converter and FACTS shunt link, and of each
+    /// generator holding a PV bus
+    vsc_q: Vec<f32>,
+    shunt_q: Vec<f32>,
+    gen_q: Vec<Option<f32>>,
+}
+
+/// A reactive source holding a bus voltage in the AC load flow, by index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    Vsc(usize),
+    Shunt(usize),
+    Generator(usize),
+}
+
+/// Solved operating point of one LCC converter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConverterSolution {
+    /// Firing angle alpha at the rectifier or extinction angle gamma at the
+    /// inverter, in degrees
+    pub angle: f32,
+    pub tap: f32,
+    /// DC voltage in kV
+    pub dc_voltage: f32,
+    /// MW and MVAR taken from the AC bus; the inverter's MW is negative
+    pub p: f32,
+    pub q: f32,
+}
+
+/// Solved operating point of a two-terminal LCC DC line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DcLineSolution {
+    /// DC current in kA
+    pub current: f32,
+    pub rectifier: ConverterSolution,
+    pub inverter: ConverterSolution,
+}
+
+/// Newton-Raphson system over the in-service buses: the Y-bus, the loads and
+/// the unknowns, θ at every non-slack bus and then V at the PQ buses.
+struct AcSystem {
+    y_bus: YBus,
+    loads: Vec<ZipLoad>,
+    kind: Vec<BusType>,
+    angle_var: Vec<Option<usize>>,
+    voltage_var: Vec<Option<usize>>,
+    unknowns: usize,
+}
+
+impl ZipLoad {
+    /// (P, Q) drawn at voltage magnitude `v`.
+    fn power(&self, v: f64) -> (f64, f64) {
+        let at = |k: &[f64; 3]| k[0] + k[1] * v + k[2] * v * v;
+        (at(&self.p), at(&self.q))
+    }
+
+    /// (dP/dV, dQ/dV) at voltage magnitude `v`.
+    fn slope(&self, v: f64) -> (f64, f64) {
+        let at = |k: &[f64; 3]| k[1] + 2.0 * k[2] * v;
+        (at(&self.p), at(&self.q))
+    }
+}
+
+/// P and Q injected at bus `i` by the network at magnitudes `v` and angles `theta`.
+fn injection(y_bus: &YBus, v: &[f64], theta: &[f64], i: usize) -> (f64, f64) {
+    y_bus[i].iter().fold((0.0, 0.0), |(p, q), &(k, g, b)| {
+        let (sin, cos) = (theta[i] - theta[k]).sin_cos();
+        (
+            p + v[i] * v[k] * (g * cos + b * sin),
+            q + v[i] * v[k] * (g * sin - b * cos),
+        )
+    })
+}
+
+impl AcSystem {
+    fn new(y_bus: YBus, loads: Vec<ZipLoad>, kind: Vec<BusType>) -> Self {
+        let mut system = Self {
+            y_bus,
+            loads,
+            kind: Vec::new(),
+            angle_var: Vec::new(),
+            voltage_var: Vec::new(),
+            unknowns: 0,
+        };
+        system.set_kind(kind);
+        system
+    }
+
+    /// Sets the bus types and numbers the unknowns they leave.
+    fn set_kind(&mut self, kind: Vec<BusType>) {
+        let n = kind.len();
+        self.angle_var = vec![None; n];
+        self.voltage_var = vec![None; n];
+        self.unknowns = 0;
+        for i in (0..n).filter(|&i| kind[i] != BusType::Slack) {
+            self.angle_var[i] = Some(self.unknowns);
+            self.unknowns += 1;
+        }
+        for i in (0..n).filter(|&i| kind[i] == BusType::PQ) {
+            self.voltage_var[i] = Some(self.unknowns);
+            self.unknowns += 1;
+        }
+        self.kind = kind;
+    }
+
+    /// Solves for `v` and `theta` with `p_spec` and `q_spec` injected at the
+    /// buses, starting from their present values. Returns the iterations taken.
+    fn newton(
+        &self,
+        p_spec: &[f64],
+        q_spec: &[f64],
+        v: &mut [f64],
+        theta: &mut [f64],
+    ) -> Result<usize, String> {
+        let n = self.kind.len();
+        let (y_bus, loads) = (&self.y_bus, &self.loads);
+        let (angle_var, voltage_var) = (&self.angle_var, &self.voltage_var);
+        let mut iterations = 0;
+        loop {
+            let calc: Vec<(f64, f64)> = (0..n).map(|i| injection(y_bus, v, theta, i)).collect();
+            let mut mismatch = vec![0.0f64; self.unknowns];
+            for i in 0..n {
+                let (p_load, q_load) = loads[i].power(v[i]);
+                if let Some(row) = angle_var[i] {
+                    mismatch[row] = p_spec[i] - p_load - calc[i].0;
+                }
+                if let Some(row) = voltage_var[i] {
+                    mismatch[row] = q_spec[i] - q_load - calc[i].1;
+                }
+            }
+            if mismatch.iter().all(|f| f.abs() < TOLERANCE) {
+                return Ok(iterations);
+            }
+            if iterations == MAX_ITERATIONS {
+                return Err(format!(
+                    "AC load flow did not converge in {} iterations",
+                    MAX_ITERATIONS
+                ));
+            }
+            iterations += 1;
+
+            // Jacobian of the calculated injection plus the load
+            let mut jacobian = Trpl::<f64>::new();
+            jacobian.m = self.unknowns;
+            jacobian.n = self.unknowns;
+            for i in 0..n {
+                let (p_i, q_i) = calc[i];
+                let (dp_load, dq_load) = loads[i].slope(v[i]);
+                for &(k, g, b) in &y_bus[i] {
+                    let (sin, cos) = (theta[i] - theta[k]).sin_cos();
+                    let (dp_dtheta, dp_dv, dq_dtheta, dq_dv) = if k == i {
+                        (
+                            -q_i - b * v[i] * v[i],
+                            p_i / v[i] + g * v[i] + dp_load,
+                            p_i - g * v[i] * v[i],
+                            q_i / v[i] - b * v[i] + dq_load,
+                        )
+                    } else {
+                        (
+                            v[i] * v[k] * (g * sin - b * cos),
+                            v[i] * (g * cos + b * sin),
+                            -v[i] * v[k] * (g * cos + b * sin),
+                            v[i] * (g * sin - b * cos),
+                        )
+                    };
+                    for (row, col, value) in [
+                        (angle_var[i], angle_var[k], dp_dtheta),
+                        (angle_var[i], voltage_var[k], dp_dv),
+                        (voltage_var[i], angle_var[k], dq_dtheta),
+                        (voltage_var[i], voltage_var[k], dq_dv),
+                    ] {
+                        if let (Some(row), Some(col)) = (row, col) {
+                            jacobian.append(row, col, value);
+                        }
+                    }
+                }
+            }
+            if !solve_sparse(&jacobian, &mut mismatch, 1.0) {
+                return Err("AC load flow Jacobian is singular".to_string());
+            }
+            for i in 0..n {
+                if let Some(col) = angle_var[i] {
+                    theta[i] += mismatch[col];
+                }
+                if let Some(col) = voltage_var[i] {
+                    v[i] += mismatch[col];
+                }
+            }
+        }
+    }
+}
+
+impl DcConverter {
+    /// No-load DC voltage in kV of one bridge at AC voltage `v` pu on `tap`.
+    fn bridge_voltage(&self, v: f32, tap: f32) -> f32 {
+        BRIDGE_VOLTAGE * v * self.base_kv * self.transformer_ratio / tap
+    }
+
+    /// Commutation voltage drop in kV of one bridge carrying `current` kA.
+    fn commutation_drop(&self, current: f32) -> f32 {
+        (3.0 / PI * self.commutating_reactance + 2.0 * self.commutating_resistance) * current
+    }
+
+    /// Tap and angle cosine that give `dc_voltage` kV at `current` kA and AC
+    /// voltage `v` pu. The tap stays put while the angle is within its limits
+    /// and otherwise moves on its steps to bring the angle back; at a tap
+    /// limit the angle may still be outside them.
+    fn control(&self, v: f32, dc_voltage: f32, current: f32) -> (f32, f32) {
+        let needed = dc_voltage / self.bridges.max(1) as f32 + self.commutation_drop(current);
+        // The cosine needed grows in proportion to the tap
+        let per_tap = needed / self.bridge_voltage(v, 1.0);
+        let cos_at_min = self.angle_min.to_radians().cos();
+        let cos_at_max = self.angle_max.to_radians().cos();
+        let cos = per_tap * self.tap;
+        let tap = if cos > cos_at_min {
+            self.step_tap(cos_at_min / per_tap, |steps| (steps + 1e-4).floor())
+        } else if cos < cos_at_max && per_tap > 0.0 {
+            self.step_tap(cos_at_max / per_tap, |steps| (steps - 1e-4).ceil())
+        } else {
+            self.tap
+        };
+        (tap, per_tap * tap)
+    }
+
+    /// `tap` rounded by `round` onto the steps up from `tap_min`, within the
+    /// tap limits.
+    fn step_tap(&self, tap: f32, round: fn(f32) -> f32) -> f32 {
+        let tap = if self.tap_step > 0.0 {
+            self.tap_min + round((tap - self.tap_min) / self.tap_step) * self.tap_step
+        } else {
+            tap
+        };
+        tap.min(self.tap_max).max(self.tap_min)
+    }
+
+    /// Solved state at AC voltage `v` pu carrying `current` kA at
+    /// `dc_voltage` kV with angle cosine `cos`; `sign` is 1 at the rectifier
+    /// and -1 at the inverter. The power factor is the ratio of the DC
+    /// voltage to its no-load value.
+    fn solution(
+        &self,
+        v: f32,
+        tap: f32,
+        cos: f32,
+        dc_voltage: f32,
+        current: f32,
+        sign: f32,
+    ) -> ConverterSolution {
+        let p = dc_voltage * current;
+        let power_factor = (dc_voltage
+            / (self.bridges.max(1) as f32 * self.bridge_voltage(v, tap)))
+        .clamp(1e-3, 1.0);
+        ConverterSolution {
+            angle: cos.clamp(-1.0, 1.0).acos().to_degrees(),
+            tap,
+            dc_voltage,
+            p: sign * p,
+            q: p * (1.0 - power_factor * power_factor).sqrt() / power_factor,
+        }
+    }
+}
+
+impl VscConverter {
+    /// Reactive limits in MVAR at `p` MW and a terminal voltage of `kv`:
+    /// `q_min` and `q_max`, narrowed to what the MVA and current ratings
+    /// leave beside `p`. The current rating needs the bus base kV.
+    fn q_limits(&self, p: f32, kv: f32) -> (f32, f32) {
+        let mut rating = f32::INFINITY;
+        if self.s_max > 0.0 {
+            rating = self.s_max;
+        }
+        if self.i_max > 0.0 && kv > 0.0 {
+            rating = rating.min(3f32.sqrt() * kv * self.i_max / 1000.0);
+        }
+        let room = (rating * rating - p * p).max(0.0).sqrt();
+        let q_max = self.q_max.min(room);
+        (self.q_min.max(-room).min(q_max), q_max)
+    }
+
+    /// MVAR scheduled at `p` MW under reactive power or power factor
+    /// control; a negative power factor absorbs.
+    fn scheduled_q(&self, p: f32) -> f32 {
+        match self.ac_control {
+            VscAcControl::ReactivePower => self.ac_setpoint,
+            VscAcControl::PowerFactor if self.ac_setpoint != 0.0 => {
+                let pf = self.ac_setpoint.clamp(-1.0, 1.0);
+                p.abs() * (1.0 - pf * pf).sqrt() / pf
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+impl SeriesLink {
+    /// Series admittance in pu and the from side ratio 1 + Vd + jVq the
+    /// series voltage gives the link, or None under flow control.
+    fn ac_model(&self) -> Option<((f64, f64), (f64, f64))> {
+        let (r, x) = self.impedance()?;
+        let (r, x) = (r as f64, x as f64);
+        let z2 = r * r + x * x;
+        let (d, q) = self.series_voltage();
+        Some(((r / z2, -x / z2), (1.0 + d as f64, q as f64)))
+    }
+}
+
+impl DcLine {
+    /// Solves the converter equations with the rectifier bus at `v_rect` pu
+    /// and the inverter bus at `v_inv` pu. The inverter holds the
+    /// compounding point at the scheduled voltage by its tap, with gamma no
+    /// lower than its minimum; the rectifier holds the current order by alpha,
+    /// moving its tap when alpha leaves its limits. Power control falls back
+    /// to the scheduled current below `switch_voltage`. A rectifier held at
+    /// its minimum alpha sets the DC voltage, and the inverter takes over the
+    /// current, less `current_margin`. None when blocked or unsolvable.
+    pub fn ac_solution(&self, v_rect: f32, v_inv: f32) -> Option<DcLineSolution> {
+        let (rect, inv) = (&self.rectifier, &self.inverter);
+        let scheduled = self.scheduled_voltage;
+        if self.control == DcControl::Blocked
+            || scheduled <= 0.0
+            || v_rect <= 0.0
+            || v_inv <= 0.0
+            || rect.base_kv <= 0.0
+            || inv.base_kv <= 0.0
+        {
+            return None;
+        }
+        let (r, rc) = (self.resistance, self.compounding_resistance);
+        let (nr, ni) = (rect.bridges.max(1) as f32, inv.bridges.max(1) as f32);
+        let cos_alpha_min = rect.angle_min.to_radians().cos();
+        let cos_gamma_min = inv.angle_min.to_radians().cos();
+
+        let mut current = match self.control {
+            DcControl::Current => self.setpoint / 1000.0,
+            _ => self.setpoint.abs() / scheduled,
+        };
+        for _ in 0..MAX_CONTROL_PASSES {
+            let (tap_i, cos_i) = inv.control(v_inv, scheduled - rc * current, current);
+            let mut cos_i = cos_i.min(cos_gamma_min);
+            let mut v_dc =
+                ni * (inv.bridge_voltage(v_inv, tap_i) * cos_i - inv.commutation_drop(current));
+            if v_dc <= 0.0 {
+                return None;
+            }
+            // kV times kA gives MW
+            let mut order = match self.control {
+                DcControl::Blocked => return None,
+                DcControl::Current => self.setpoint / 1000.0,
+                DcControl::Power if v_dc < self.switch_voltage => self.setpoint.abs() / scheduled,
+                DcControl::Power if self.setpoint < 0.0 => -self.setpoint / v_dc,
+                // Rectifier power: r I² + v I - P = 0
+                DcControl::Power if r > 0.0 => {
+                    ((v_dc * v_dc + 4.0 * r * self.setpoint).sqrt() - v_dc) / (2.0 * r)
+                }
+                DcControl::Power => self.setpoint / v_dc,
+            };
+            let (tap_r, mut cos_r) = rect.control(v_rect, v_dc + r * order, order);
+            if cos_r > cos_alpha_min {
+                order *= 1.0 - self.current_margin;
+                cos_r = cos_alpha_min;
+                let v_rect_dc = nr
+                    * (rect.bridge_voltage(v_rect, tap_r) * cos_r - rect.commutation_drop(order));
+                v_dc = v_rect_dc - r * order;
+                if v_dc <= 0.0 {
+                    return None;
+                }
+                cos_i =
+                    (v_dc / ni + inv.commutation_drop(order)) / inv.bridge_voltage(v_inv, tap_i);
+            }
+            let settled = (order - current).abs() < 1e-7 * current.abs().max(1.0);
+            current = order;
+            if settled {
+                return Some(DcLineSolution {
+                    current,
+                    rectifier: rect.solution(
+                        v_rect,
+                        tap_r,
+                        cos_r,
+                        v_dc + r * current,
+                        current,
+                        1.0,
+                    ),
+                    inverter: inv.solution(v_inv, tap_i, cos_i, v_dc, current, -1.0),
+                });
+            }
+        }
+        None
+    }
+}
+
+impl Network {
+    /// Bus admittance matrix over the buses in `index`. Branch shunts sit at
+    /// their end buses, the tap and phase shift on the from side; FACTS series
+    /// links enter at their DC load flow reactance.
+    fn y_bus(&self, index: &HashMap<usize, usize>) -> YBus {
+        let mut entries: HashMap<(usize, usize), (f64, f64)> = HashMap::new();
+        let mut add = |i: usize, j: usize, g: f64, b: f64| {
+            let entry = entries.entry((i, j)).or_default();
+            entry.0 += g;
+            entry.1 += b;
+        };
+        let s_base = self.s_base as f64;
+        for bus in &self.buses {
+            // Every bus gets a diagonal entry, even with no shunt
+            if let Some(&i) = index.get(&bus.bus_id) {
+                add(
+                    i,
+                    i,
+                    bus.real_shunt as f64 / s_base,
+                    bus.imag_shunt as f64 / s_base,
+                );
+            }
+        }
+
+        let impedances = self.effective_impedances();
+        for (br, (r, x)) in self.branches.iter().zip(impedances) {
+            let (Some(&i), Some(&j)) = (index.get(&br.from_bus), index.get(&br.to_bus)) else {
+                continue;
+            };
+            let (r, x) = (r as f64, x as f64);
+            if !br.branch_status || (r == 0.0 && x == 0.0) {
+                continue;
+            }
+            let z2 = r * r + x * x;
+            let (gs, bs) = (r / z2, -x / z2);
+            let t = if br.tap_ratio > 0.0 {
+                br.tap_ratio as f64
+            } else {
+                1.0
+            };
+            let (sin, cos) = (br.phase_shift as f64).to_radians().sin_cos();
+            add(
+                i,
+                i,
+                gs / (t * t) + br.from_shunt_conductance as f64,
+                bs / (t * t) + br.from_shunt_susceptance as f64,
+            );
+            add(
+                j,
+                j,
+                gs + br.to_shunt_conductance as f64,
+                bs + br.to_shunt_susceptance as f64,
+            );
+            // Yft = -ys / (t e^-jφ), Ytf = -ys / (t e^jφ)
+            add(i, j, -(gs * cos - bs * sin) / t, -(bs * cos + gs * sin) / t);
+            add(j, i, -(gs * cos + bs * sin) / t, -(bs * cos - gs * sin) / t);
+        }
+
+        // FACTS series links: the impedance behind an ideal transformer of
+        // ratio k = 1 + Vd + jVq for the series voltage, so Yff = ys |k|²,
+        // Yft = -ys conj(k), Ytf = -ys k and Ytt = ys
+        for device in self.facts_devices.iter().filter(|d| d.status) {
+            let Some(link) = &device.series else {
+                continue;
+            };
+            let (Some(&i), Some(&j)) = (index.get(&device.bus_id), index.get(&link.to_bus)) else {
+                continue;
+            };
+            if let Some(((g, b), (kr, ki))) = link.ac_model() {
+                let k2 = kr * kr + ki * ki;
+                add(i, i, g * k2, b * k2);
+                add(j, j, g, b);
+                add(i, j, -(g * kr + b * ki), -(b * kr - g * ki));
+                add(j, i, -(g * kr - b * ki), -(b * kr + g * ki));
+            }
+        }
+
+        let mut y_bus: YBus = vec![Vec::new(); index.len()];
+        for ((i, j), (g, b)) in entries {
+            y_bus[i].push((j, g, b));
+        }
+        for row in &mut y_bus {
+            row.sort_by_key(|&(j, _, _)| j);
+        }
+        y_bus
+    }
+
+    /// Runs a Newton-Raphson AC load flow in polar form and writes bus
+    /// voltages and angles, branch flows, and the P of slack generators and
+    /// Q of voltage-controlling ones into the network. ZIP loads are evaluated
+    /// at the bus voltage each iteration, and their dP/dV and dQ/dV enter the
+    /// Jacobian. FACTS series links are an impedance behind their series
+    /// voltage, which draws what it adds from the device bus, or under flow
+    /// control move their P to the to bus and deliver their Q there, scaled
+    /// back to the rating at the to bus voltage.
+    ///
+    /// HVDC is solved sequentially: two-terminal DC lines take the P and Q of
+    /// their converter equations at the solved voltages, and the DC grids are
+    /// re-solved with VSC losses at the solved converter currents. Generators
+    /// at PV buses hold the voltage within `Generator::q_limits` at their
+    /// dispatched P. VSC converters follow their AC control: a fixed MVAR or
+    /// power factor, or the voltage of a bus no generator holds, within their
+    /// reactive limits at the dispatched P. FACTS shunt links hold such a bus
+    /// too, within limits that scale with its voltage. A bus whose sources
+    /// reach a limit is held at it as PQ. The passes repeat until the injections settle.
+    /// LCC taps and VSC and FACTS shunt MVAR are written back. Returns the
+    /// Newton iterations taken.
+    pub fn ac_load_flow(&mut self) -> Result<usize, String> {
+        let isolated = self.isolated_dummy_buses();
+        let in_service: Vec<&Bus> = self
+            .buses
+            .iter()
+            .filter(|b| b.bus_status && b.bus_type != BusType::OUT)
+            .filter(|b| !isolated.contains(&b.bus_id))
+            .collect();
+        let index: HashMap<usize, usize> = in_service
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.bus_id, i))
+            .collect();
+        let n = in_service.len();
+        let s_base = self.s_base as f64;
+        let base_kv: Vec<f32> = in_service.iter().map(|b| b.nom_voltage).collect();
+
+        // Scheduled injections and loads in pu
+        let mut p_gen = vec![0.0f64; n];
+        let mut q_gen = vec![0.0f64; n];
+        let mut loads = vec![ZipLoad::default(); n];
+        let mut setpoint: Vec<Option<f64>> = vec![None; n];
+        for g in self.generators.iter().filter(|g| g.gen_status) {
+            if let Some(&i) = index.get(&g.gen_bus_id) {
+                p_gen[i] += g.p_gen as f64 / s_base;
+                q_gen[i] += g.q_gen as f64 / s_base;
+                setpoint[i].get_or_insert(g.v_setpoint as f64);
+            }
+        }
+        for l in self.loads.iter().filter(|l| l.load_status) {
+            if let Some(&i) = index.get(&l.bus_id) {
+                let zip = &mut loads[i];
+                for (k, mw) in [l.real_load, l.real_current, l.real_admittance]
+                    .into_iter()
+                    .enumerate()
+                {
+                    zip.p[k] += mw as f64 / s_base;
+                }
+                for (k, mvar) in [l.imag_load, l.imag_current, l.imag_admittance]
+                    .into_iter()
+                    .enumerate()
+                {
+                    zip.q[k] += mvar as f64 / s_base;
+                }
+            }
+        }
+        // Slack buses hold V and θ, PV buses with a generator V; PV buses
+        // without one are solved as PQ unless a VSC converter or FACTS shunt
+        // link holds them
+        let mut kind: Vec<BusType> = in_service
+            .iter()
+            .zip(&setpoint)
+            .map(|(b, set)| match b.bus_type {
+                BusType::PV if set.is_none() => BusType::PQ,
+                other => other,
+            })
+            .collect();
+        if !kind.contains(&BusType::Slack) {
+            return Err("AC load flow needs a slack bus".to_string());
+        }
+        let vsc_bus: Vec<Option<usize>> = self
+            .vsc_converters
+            .iter()
+            .map(|c| c.status.then(|| index.get(&c.ac_bus_id).copied()).flatten())
+            .collect();
+        let candidates = self
+            .vsc_converters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.ac_control == VscAcControl::Voltage)
+            .filter_map(|(k, c)| Some((Source::Vsc(k), vsc_bus[k]?, c.ac_setpoint)))
+            .chain(
+                self.facts_devices
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, d)| d.status && d.q_max > d.q_min)
+                    .filter_map(|(k, d)| {
+                        Some((Source::Shunt(k), *index.get(&d.bus_id)?, d.v_setpoint))
+                    }),
+            );
+        let mut sources: Vec<(Source, usize)> = Vec::new();
+        let mut regulated = vec![false; n];
+        for (source, i, set) in candidates {
+            if kind[i] == BusType::PQ || regulated[i] {
+                if !regulated[i] {
+                    kind[i] = BusType::PV;
+                    setpoint[i] = Some(set as f64);
+                    regulated[i] = true;
+                }
+                sources.push((source, i));
+            }
+        }
+        // Generators at PV buses hold them within their limits at the
+        // dispatched P; the slack's are not limited
+        for (g, machine) in self.generators.iter().enumerate() {
+            if let Some(&i) = index.get(&machine.gen_bus_id)
+                && machine.gen_status
+                && kind[i] == BusType::PV
+            {
+                sources.push((Source::Generator(g), i));
+                regulated[i] = true;
+                q_gen[i] = 0.0;
+            }
+        }
+        let regulating: Vec<bool> = (0..self.vsc_converters.len())
+            .map(|k| sources.iter().any(|&(s, _)| s == Source::Vsc(k)))
+            .collect();
+
+        let mut v: Vec<f64> = in_service
+            .iter()
+            .enumerate()
+            .map(|(i, b)| match (kind[i], setpoint[i]) {
+                (BusType::Slack | BusType::PV, Some(vs)) => vs,
+                _ if (0.5..1.5).contains(&b.voltage) => b.voltage as f64,
+                _ => 1.0,
+            })
+            .collect();
+        let mut theta: Vec<f64> = in_service
+            .iter()
+            .map(|b| (b.angle as f64).to_radians())
+            .collect();
+
+        let mut system = AcSystem::new(self.y_bus(&index), loads, kind);
+        let mut iterations = 0;
+        let mut passes = 0;
+        // Converter currents in kA from the last pass, MVAR of each source and
+        // whether a bus's sources are held at their upper or lower limit
+        let mut ac_current: Option<Vec<f32>> = None;
+        let mut vsc_q = vec![0.0f32; self.vsc_converters.len()];
+        let mut source_q = vec![0.0f32; sources.len()];
+        let mut at_limit: Vec<Option<bool>> = vec![None; n];
+        let mut previous: Option<(Vec<f64>, Vec<f64>)> = None;
+        let (fixed_p, fixed_q, dc_lines) = loop {
+            if !self.solve_dc_grid_at(ac_current.as_deref()) {
+                return Err("DC grid solve failed".to_string());
+            }
+            let (mut fixed_p, mut fixed_q, dc_lines) = self.dc_line_injections(&index, &v);
+            for (k, conv) in self.vsc_converters.iter().enumerate() {
+                let Some(i) = vsc_bus[k] else {
+                    continue;
+                };
+                fixed_p[i] += conv.p_ac as f64 / s_base;
+                if !regulating[k] {
+                    let (q_min, q_max) = conv.q_limits(conv.p_ac, v[i] as f32 * base_kv[i]);
+                    vsc_q[k] = conv.scheduled_q(conv.p_ac).clamp(q_min, q_max);
+                    fixed_q[i] += vsc_q[k] as f64 / s_base;
+                }
+            }
+            // Flow-controlled FACTS links move P from their bus and deliver
+            // P and Q at the to bus, within their rating there
+            for device in self.facts_devices.iter().filter(|d| d.status) {
+                let Some(link) = &device.series else {
+                    continue;
+                };
+                if let (None, Some(&i), Some(&j)) = (
+                    link.impedance(),
+                    index.get(&device.bus_id),
+                    index.get(&link.to_bus),
+                ) {
+                    let (p, q) = link.scheduled_power(v[j] as f32);
+                    fixed_p[i] -= p as f64 / s_base;
+                    fixed_p[j] += p as f64 / s_base;
+                    fixed_q[j] += q as f64 / s_base;
+                }
+            }
+            // Held buses take their limit at the present voltage and P
+            let (_, limits) = self.regulation_limits(&sources, &v, &base_kv);
+            let held_q = |i: usize| match at_limit[i] {
+                Some(true) => limits[i].1 as f64 / s_base,
+                Some(false) => limits[i].0 as f64 / s_base,
+                None => 0.0,
+            };
+            let p_spec: Vec<f64> = (0..n).map(|i| p_gen[i] + fixed_p[i]).collect();
+            let q_spec: Vec<f64> = (0..n).map(|i| q_gen[i] + fixed_q[i] + held_q(i)).collect();
+            if let Some((last_p, last_q)) = &previous {
+                let close =
+                    |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < TOLERANCE);
+                if close(last_p, &p_spec) && close(last_q, &q_spec) {
+                    for (&(source, i), &q) in sources.iter().zip(&source_q) {
+                        if !matches!(source, Source::Generator(_)) {
+                            fixed_q[i] += q as f64 / s_base;
+                        }
+                    }
+                    break (fixed_p, fixed_q, dc_lines);
+                }
+            }
+            if passes == MAX_DC_PASSES {
+                return Err(format!(
+                    "HVDC and FACTS injections did not settle in {} passes",
+                    MAX_DC_PASSES
+                ));
+            }
+            passes += 1;
+            iterations += system.newton(&p_spec, &q_spec, &mut v, &mut theta)?;
+
+            // Regulated buses: the sources share what the bus needs, in
+            // proportion to their range, and a bus past the sum of their
+            // limits is held there
+            let (ranges, limits) = self.regulation_limits(&sources, &v, &base_kv);
+            let mut kind = system.kind.clone();
+            let mut needed = vec![0.0f32; n];
+            for i in (0..n).filter(|&i| regulated[i]) {
+                let (q_min, q_max) = limits[i];
+                if let Some(upper) = at_limit[i] {
+                    needed[i] = if upper { q_max } else { q_min };
+                    continue;
+                }
+                let (_, q_calc) = injection(&system.y_bus, &v, &theta, i);
+                let (_, q_load) = system.loads[i].power(v[i]);
+                let q = ((q_calc + q_load - q_spec[i]) * s_base) as f32;
+                needed[i] = q.clamp(q_min, q_max);
+                if q < q_min || q > q_max {
+                    at_limit[i] = Some(q > q_max);
+                    kind[i] = BusType::PQ;
+                }
+            }
+            for (k, (&(source, i), &(q_min, q_max))) in sources.iter().zip(&ranges).enumerate() {
+                let (low, high) = limits[i];
+                let share = if high > low {
+                    (needed[i] - low) / (high - low)
+                } else {
+                    0.0
+                };
+                source_q[k] = q_min + (q_max - q_min) * share;
+                if let Source::Vsc(c) = source {
+                    vsc_q[c] = source_q[k];
+                }
+            }
+            if kind != system.kind {
+                system.set_kind(kind);
+                previous = None;
+            } else {
+                previous = Some((p_spec, q_spec));
+            }
+
+            ac_current = Some(
+                self.vsc_converters
+                    .iter()
+                    .enumerate()
+                    .map(|(k, conv)| match vsc_bus[k] {
+                        Some(i) if v[i] > 0.0 && base_kv[i] > 0.0 => {
+                            conv.p_ac.hypot(vsc_q[k]) / (3f32.sqrt() * v[i] as f32 * base_kv[i])
+                        }
+                        _ => 0.0,
+                    })
+                    .collect(),
+            );
+        };
+
+        let mut shunt_q = vec![0.0f32; self.facts_devices.len()];
+        let mut gen_q = vec![None; self.generators.len()];
+        for (&(source, _), &q) in sources.iter().zip(&source_q) {
+            match source {
+                Source::Shunt(d) => shunt_q[d] = q,
+                Source::Generator(g) => gen_q[g] = Some(q),
+                Source::Vsc(_) => {}
+            }
+        }
+        let solution = AcSolution {
+            index,
+            system,
+            v,
+            theta,
+            fixed_p,
+            fixed_q,
+            dc_lines,
+            vsc_q,
+            shunt_q,
+            gen_q,
+        };
+        self.write_ac_solution(&solution);
+        Ok(iterations)
+    }
+
+    /// Reactive limits in MVAR of each of `sources` at voltages `v`, and
+    /// their sums at each bus. A VSC converter's and a generator's depend on
+    /// its P; a FACTS shunt link is limited in current, so its limits at
+    /// unity voltage scale with the voltage.
+    fn regulation_limits(
+        &self,
+        sources: &[(Source, usize)],
+        v: &[f64],
+        base_kv: &[f32],
+    ) -> (Vec<QLimits>, Vec<QLimits>) {
+        let ranges: Vec<QLimits> = sources
+            .iter()
+            .map(|&(source, i)| match source {
+                Source::Vsc(k) => {
+                    let conv = &self.vsc_converters[k];
+                    conv.q_limits(conv.p_ac, v[i] as f32 * base_kv[i])
+                }
+                Source::Shunt(k) => {
+                    let device = &self.facts_devices[k];
+                    (device.q_min * v[i] as f32, device.q_max * v[i] as f32)
+                }
+                Source::Generator(k) => {
+                    let (q_min, q_max) = self.generators[k].q_limits();
+                    (q_min.min(q_max), q_max)
+                }
+            })
+            .collect();
+        let mut limits: Vec<QLimits> = vec![(0.0, 0.0); v.len()];
+        for (&(_, i), &(q_min, q_max)) in sources.iter().zip(&ranges) {
+            limits[i].0 += q_min;
+            limits[i].1 += q_max;
+        }
+        (ranges, limits)
+    }
+
+    /// P and Q in pu injected at each bus in `index` by two-terminal DC lines
+    /// at voltages `v`, with each line's solution. A line with a converter
+    /// off the buses in `index` is blocked.
+    fn dc_line_injections(
+        &self,
+        index: &HashMap<usize, usize>,
+        v: &[f64],
+    ) -> (Vec<f64>, Vec<f64>, Vec<Option<DcLineSolution>>) {
+        let s_base = self.s_base as f64;
+        let mut p = vec![0.0f64; v.len()];
+        let mut q = vec![0.0f64; v.len()];
+        let solutions = self
+            .dc_lines
+            .iter()
+            .map(|line| {
+                let i = *index.get(&line.rectifier.bus_id)?;
+                let j = *index.get(&line.inverter.bus_id)?;
+                let solution = line.ac_solution(v[i] as f32, v[j] as f32)?;
+                for (k, converter) in [(i, &solution.rectifier), (j, &solution.inverter)] {
+                    p[k] -= converter.p as f64 / s_base;
+                    q[k] -= converter.q as f64 / s_base;
+                }
+                Some(solution)
+            })
+            .collect();
+        (p, q, solutions)
+    }
+
+    /// Writes a converged AC solution back into buses, branches and generators.
+    fn write_ac_solution(&mut self, solution: &AcSolution) {
+        let AcSolution {
+            index,
+            system,
+            v,
+            theta,
+            fixed_p,
+            fixed_q,
+            dc_lines,
+            vsc_q,
+            shunt_q,
+            gen_q,
+        } = solution;
+        let s_base = self.s_base as f64;
+        for bus in &mut self.buses {
+            match index.get(&bus.bus_id) {
+                Some(&i) => {
+                    bus.voltage = v[i] as f32;
+                    bus.angle = theta[i].to_degrees() as f32;
+                }
+                None => {
+                    bus.voltage = 0.0;
+                    bus.angle = 0.0;
+                }
+            }
+        }
+
+        // MW leaving the from bus through the series and from-side shunt
+        let impedances = self.effective_impedances();
+        for (br, (r, x)) in self.branches.iter_mut().zip(impedances) {
+            br.flow = match (index.get(&br.from_bus), index.get(&br.to_bus)) {
+                (Some(&i), Some(&j)) if br.branch_status => {
+                    let (r, x) = (r as f64, x as f64);
+                    let z2 = r * r + x * x;
+                    if z2 == 0.0 {
+                        0.0
+                    } else {
+                        let (gs, bs) = (r / z2, -x / z2);
+                        let t = if br.tap_ratio > 0.0 {
+                            br.tap_ratio as f64
+                        } else {
+                            1.0
+                        };
+                        let angle = theta[i] - theta[j] - (br.phase_shift as f64).to_radians();
+                        let (sin, cos) = angle.sin_cos();
+                        let p = v[i] * v[i] * (gs / (t * t) + br.from_shunt_conductance as f64)
+                            - v[i] * v[j] / t * (gs * cos + bs * sin);
+                        (p * s_base) as f32
+                    }
+                }
+                _ => 0.0,
+            };
+        }
+
+        for (device, &q) in self.facts_devices.iter_mut().zip(shunt_q) {
+            device.q_shunt = q;
+            let Some(link) = &mut device.series else {
+                continue;
+            };
+            link.flow = match (index.get(&device.bus_id), index.get(&link.to_bus)) {
+                (Some(&i), Some(&j)) if device.status => match link.ac_model() {
+                    Some(((g, b), (kr, ki))) => {
+                        // P_i = Vi² Re(Yff) + Vi Vj (a cos δ + c sin δ) for Yft = a + jc
+                        let (a, c) = (-(g * kr + b * ki), -(b * kr - g * ki));
+                        let (sin, cos) = (theta[i] - theta[j]).sin_cos();
+                        let p = v[i] * v[i] * g * (kr * kr + ki * ki)
+                            + v[i] * v[j] * (a * cos + c * sin);
+                        (p * s_base) as f32
+                    }
+                    None => link.scheduled_power(v[j] as f32).0,
+                },
+                _ => 0.0,
+            };
+        }
+
+        for (conv, &q) in self.vsc_converters.iter_mut().zip(vsc_q) {
+            conv.q_ac = q;
+        }
+
+        for (line, solved) in self.dc_lines.iter_mut().zip(dc_lines) {
+            if let Some(solved) = solved {
+                line.rectifier.tap = solved.rectifier.tap;
+                line.inverter.tap = solved.inverter.tap;
+            }
+        }
+
+        // Generators holding a PV bus take their share of its Q, at their
+        // limit when the bus is held there
+        for (g, &q) in self.generators.iter_mut().zip(gen_q) {
+            if let Some(q) = q {
+                g.q_gen = q;
+            }
+        }
+
+        // The first in-service generator at the slack takes up what the
+        // solution needs beyond the others
+        for (&bus_id, &i) in index {
+            if system.kind[i] != BusType::Slack {
+                continue;
+            }
+            let (p_calc, q_calc) = injection(&system.y_bus, v, theta, i);
+            let (p_load, q_load) = system.loads[i].power(v[i]);
+            let at_bus: Vec<usize> = (0..self.generators.len())
+                .filter(|&g| {
+                    self.generators[g].gen_bus_id == bus_id && self.generators[g].gen_status
+                })
+                .collect();
+            let Some((&first, others)) = at_bus.split_first() else {
+                continue;
+            };
+            let (other_p, other_q) = others.iter().fold((0.0, 0.0), |(p, q), &g| {
+                let g = &self.generators[g];
+                (p + g.p_gen as f64, q + g.q_gen as f64)
+            });
+            let p = (p_calc + p_load - fixed_p[i]) * s_base - other_p;
+            self.generators[first].p_gen = p as f32;
+            let q = (q_calc + q_load - fixed_q[i]) * s_base - other_q;
+            self.generators[first].q_gen = q as f32;
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::Path;
+
+use crate::case::*;
+use crate::contingency::tokenize;
+use crate::pypsa::parse_csv;
+
+/// Replaces the capability curve of the machine `id` at `bus_id`, sorting the
+/// points by MW.
+pub(crate) fn set_curve(
+    network: &mut Network,
+    bus_id: usize,
+    id: &str,
+    points: Vec<CapabilityPoint>,
+) -> Result<(), String> {
+    let index = machine_index(network, bus_id, id)?;
+    replace_curves(network, vec![(index, points)]);
+    Ok(())
+}
+
+fn machine_index(network: &Network, bus_id: usize, id: &str) -> Result<usize, String> {
+    network
+        .find_generator(bus_id, id)
+        .ok_or_else(|| format!("no machine {} at bus {}", id, bus_id))
+}
+
+/// Replaces the curves of the generators at the given indices, sorting the
+/// points by MW. Readers check every record first, so a bad file leaves
+/// the network as it was.
+fn replace_curves(network: &mut Network, curves: Vec<(usize, Vec<CapabilityPoint>)>) {
+    for (index, mut points) in curves {
+        points.sort_by(|a, b| a.p.total_cmp(&b.p));
+        network.generators[index].capability = points;
+    }
+}
+
+fn check_point(point: &CapabilityPoint) -> Result<(), String> {
+    if point.q_min > point.q_max {
+        return Err(format!(
+            "Qmin {} is above Qmax {} at {} MW",
+            point.q_min, point.q_max, point.p
+        ));
+    }
+    Ok(())
+}
+
+/// Applies capability curves from CSV with the columns bus, id, p, q_min and
+/// q_max, one row per point. Returns the number of machines given a curve.
+pub fn parse_capability_csv(network: &mut Network, content: &str) -> Result<usize, String> {
+    let table = parse_csv(content);
+    let mut curves: Vec<(usize, Vec<CapabilityPoint>)> = Vec::new();
+    for (i, row) in table.rows.iter().enumerate() {
+        let line = i + 2;
+        let number = |column: &str| {
+            table
+                .num(row, column)
+                .ok_or_else(|| format!("Line {}: missing or invalid {}", line, column))
+        };
+        let bus_id = table
+            .get(row, "bus")
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("Line {}: missing or invalid bus", line))?;
+        let index = machine_index(network, bus_id, table.get(row, "id").unwrap_or("1"))
+            .map_err(|e| format!("Line {}: {}", line, e))?;
+        let point = CapabilityPoint {
+            p: number("p")?,
+            q_min: number("q_min")?,
+            q_max: number("q_max")?,
+        };
+        check_point(&point).map_err(|e| format!("Line {}: {}", line, e))?;
+        match curves.iter_mut().find(|(k, _)| *k == index) {
+            Some((_, points)) => points.push(point),
+            None => curves.push((index, vec![point])),
+        }
+    }
+    let count = curves.len();
+    replace_curves(network, curves);
+    Ok(count)
+}
+
+/// Applies capability curves from a PSS/E .gcp file: one record per machine,
+/// `BUSI, 'ID', P1, QT1, QB1, P2, QT2, QB2, ...`, ended by a bus number of
+/// zero. Returns the number of machines given a curve.
+pub fn parse_gcp_str(network: &mut Network, content: &str) -> Result<usize, String> {
+    let mut curves: Vec<(usize, Vec<CapabilityPoint>)> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let tokens = tokenize(line.trim());
+        let Some(first) = tokens.first() else {
+            continue;
+        };
+        let bus_id: usize = first
+            .parse()
+            .map_err(|_| format!("Line {}: invalid bus number '{}'", i + 1, first))?;
+        if bus_id == 0 {
+            break;
+        }
+        let id = tokens.get(1).map_or("1", String::as_str);
+        let values: Vec<f32> = tokens
+            .iter()
+            .skip(2)
+            .map(|v| v.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Line {}: invalid capability value", i + 1))?;
+        let points: Vec<CapabilityPoint> = values
+            .chunks_exact(3)
+            .map(|v| CapabilityPoint {
+                p: v[0],
+                q_max: v[1],
+                q_min: v[2],
+            })
+            .collect();
+        let index = machine_index(network, bus_id, id)
+            .and_then(|index| {
+                points.iter().try_for_each(check_point)?;
+                Ok(index)
+            })
+            .map_err(|e| format!("Line {}: {}", i + 1, e))?;
+        curves.push((index, points));
+    }
+    let count = curves.len();
+    replace_curves(network, curves);
+    Ok(count)
+}
+
+/// Reads capability curves from a .csv or PSS/E .gcp file into a network.
+pub fn read_capability(network: &mut Network, path: &str) -> Result<usize, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let is_csv = Path::new(path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    if is_csv {
+        parse_capability_csv(network, &content)
+    } else {
+        parse_gcp_str(network, &content)
+    }
+}
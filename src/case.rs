@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
 /// Bus type enum.
@@ -23,13 +23,35 @@ impl fmt::Display for BusType {
     }
 }
 
+/// Area, zone and owner number assumed for buses saved before they were tracked.
+fn default_one() -> usize {
+    1
+}
+
+/// Recover the RAW id from a "Bus{n}-{id}" element name.
+pub(crate) fn raw_id(name: &str, bus_id: usize) -> String {
+    let prefix = format!("Bus{}-", bus_id);
+    name.strip_prefix(&prefix).unwrap_or(name).to_string()
+}
+
+/// Status assumed for loads saved before they carried one.
+fn default_true() -> bool {
+    true
+}
+
 /// Bus struct with the necessary fields for a power system bus.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bus {
     pub bus_id: usize,
     pub bus_name: String,
     pub bus_type: BusType,
     pub nom_voltage: f32,
+    #[serde(default = "default_one")]
+    pub area: usize,
+    #[serde(default = "default_one")]
+    pub zone: usize,
+    #[serde(default = "default_one")]
+    pub owner: usize,
     pub bus_status: bool,
     pub voltage: f32,
     pub angle: f32,
@@ -50,6 +72,9 @@ impl Bus {
             bus_status: bus_type != BusType::OUT,
             bus_type,
             nom_voltage: 0.0,
+            area: 1,
+            zone: 1,
+            owner: 1,
             voltage: 1.0,
             angle: 0.0,
             real_shunt: 0.0,
@@ -74,14 +99,31 @@ impl fmt::Display for Bus {
 }
 
 /// Load struct constructor.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Load {
     pub load_id: usize,
     pub bus_id: usize,
+    /// Load identifier at its bus, as in the RAW 'ID' field
+    #[serde(default)]
+    pub load_ident: String,
     pub load_name: String,
+    #[serde(default = "default_true")]
+    pub load_status: bool,
 
+    /// Constant power part in MW / MVAR
     pub real_load: f32,
     pub imag_load: f32,
+    /// Constant current part in MW / MVAR at 1 pu, as RAW IP / IQ
+    #[serde(default)]
+    pub real_current: f32,
+    #[serde(default)]
+    pub imag_current: f32,
+    /// Constant admittance part in MW / MVAR at 1 pu, as RAW YP and -YQ:
+    /// RAW counts the admittance, so an inductive YQ is negative
+    #[serde(default)]
+    pub real_admittance: f32,
+    #[serde(default)]
+    pub imag_admittance: f32,
 }
 
 /// Load struct display implementation.
@@ -96,11 +138,36 @@ impl Load {
         Self {
             load_id,
             bus_id,
+            load_ident: raw_id(&load_name, bus_id),
             load_name,
+            load_status: true,
             real_load,
             imag_load,
+            real_current: 0.0,
+            imag_current: 0.0,
+            real_admittance: 0.0,
+            imag_admittance: 0.0,
         }
     }
+
+    /// (P, Q) in MW / MVAR drawn at voltage magnitude `v` pu: constant current
+    /// terms scale with v and constant admittance terms with v².
+    pub fn power_at(&self, v: f32) -> (f32, f32) {
+        (
+            self.real_load + self.real_current * v + self.real_admittance * v * v,
+            self.imag_load + self.imag_current * v + self.imag_admittance * v * v,
+        )
+    }
+
+    /// Scales all three load parts by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        self.real_load *= factor;
+        self.imag_load *= factor;
+        self.real_current *= factor;
+        self.imag_current *= factor;
+        self.real_admittance *= factor;
+        self.imag_admittance *= factor;
+    }
 }
 
 /// Load struct display implementation.
@@ -114,6 +181,51 @@ impl fmt::Display for Load {
     }
 }
 
+/// Transformer impedance correction table: complex factors on R + jX
+/// against the off-nominal ratio or the phase shift in degrees.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImpedanceTable {
+    pub number: usize,
+    /// (ratio or angle, real factor, imaginary factor) in increasing ratio
+    /// or angle
+    pub points: Vec<(f32, f32, f32)>,
+}
+
+impl ImpedanceTable {
+    /// Factor at `x` as (real, imaginary), interpolated linearly and held at
+    /// the end points.
+    pub fn factor(&self, x: f32) -> (f32, f32) {
+        let Some(&(first_x, first_re, first_im)) = self.points.first() else {
+            return (1.0, 0.0);
+        };
+        if x <= first_x {
+            return (first_re, first_im);
+        }
+        for pair in self.points.windows(2) {
+            let ((x0, re0, im0), (x1, re1, im1)) = (pair[0], pair[1]);
+            if x <= x1 {
+                return if x1 > x0 {
+                    let w = (x - x0) / (x1 - x0);
+                    (re0 + (re1 - re0) * w, im0 + (im1 - im0) * w)
+                } else {
+                    (re1, im1)
+                };
+            }
+        }
+        self.points
+            .last()
+            .map_or((1.0, 0.0), |&(_, re, im)| (re, im))
+    }
+}
+
+/// A transformer's reference to an impedance correction table.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TableLink {
+    pub table: usize,
+    /// Whether the table is against the phase shift rather than the ratio
+    pub by_angle: bool,
+}
+
 /// BranchType enum display implementation.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BranchType {
@@ -131,12 +243,15 @@ impl fmt::Display for BranchType {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Branch {
     pub branch_type: BranchType,
     pub id: usize,
     pub from_bus: usize,
     pub to_bus: usize,
+    /// Circuit identifier between the bus pair, as in the RAW 'CKT' field
+    #[serde(default)]
+    pub ckt: String,
     pub branch_name: String,
     pub branch_status: bool,
     pub resistance: f32,
@@ -147,6 +262,9 @@ pub struct Branch {
     pub to_shunt_susceptance: f32,
     pub tap_ratio: f32,
     pub phase_shift: f32,
+    /// Impedance correction table scaling R and X with the tap or angle
+    #[serde(default)]
+    pub impedance_table: Option<TableLink>,
     pub operating_limit: f32,
     pub contingency_limit: f32,
     pub flow: f32,
@@ -166,6 +284,7 @@ impl Branch {
             from_bus,
             to_bus,
             branch_type,
+            ckt: String::new(),
             branch_name: String::new(),
             branch_status: true,
             resistance,
@@ -176,6 +295,7 @@ impl Branch {
             to_shunt_susceptance: 0.0,
             tap_ratio: 1.0,
             phase_shift: 0.0,
+            impedance_table: None,
             operating_limit: 0.0,
             contingency_limit: 0.0,
             flow: 0.0,
@@ -202,10 +322,13 @@ impl fmt::Display for Branch {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Generator {
     pub gen_id: usize,
     pub gen_bus_id: usize,
+    /// Machine identifier at its bus, as in the RAW 'ID' field
+    #[serde(default)]
+    pub machine_id: String,
     pub gen_name: String,
     pub gen_status: bool,
     pub p_gen: f32,
@@ -215,6 +338,38 @@ pub struct Generator {
     pub p_max: f32,
     pub q_min: f32,
     pub q_max: f32,
+    #[serde(default)]
+    pub cost: Option<GenCost>,
+    /// Reactive capability curve in increasing MW; empty means the fixed
+    /// `q_min` and `q_max` apply at every output
+    #[serde(default)]
+    pub capability: Vec<CapabilityPoint>,
+}
+
+/// One point of a reactive capability (D-)curve: the MVAR limits at an
+/// output in MW.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityPoint {
+    pub p: f32,
+    pub q_min: f32,
+    pub q_max: f32,
+}
+
+/// Generator cost curve in $/h, as in MATPOWER gencost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CostCurve {
+    /// (MW, $/h) breakpoints in increasing MW
+    PiecewiseLinear(Vec<(f32, f32)>),
+    /// Polynomial coefficients from the highest order down
+    Polynomial(Vec<f32>),
+}
+
+/// Generator operating cost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenCost {
+    pub startup: f32,
+    pub shutdown: f32,
+    pub curve: CostCurve,
 }
 
 impl Generator {
@@ -222,6 +377,7 @@ impl Generator {
         Self {
             gen_id,
             gen_bus_id,
+            machine_id: raw_id(&gen_name, gen_bus_id),
             gen_name,
             gen_status: true,
             p_gen: 0.0,
@@ -231,7 +387,38 @@ impl Generator {
             p_max: 0.0,
             q_min: 0.0,
             q_max: 0.0,
+            cost: None,
+            capability: Vec::new(),
+        }
+    }
+
+    /// (Qmin, Qmax) in MVAR at the dispatched `p_gen`, interpolated linearly
+    /// on the capability curve and held at its end points. The AC load flow
+    /// holds a PV bus's generators within these; the DC load flow does not
+    /// use reactive power.
+    pub fn q_limits(&self) -> (f32, f32) {
+        let Some(first) = self.capability.first() else {
+            return (self.q_min, self.q_max);
+        };
+        if self.p_gen <= first.p {
+            return (first.q_min, first.q_max);
         }
+        for pair in self.capability.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if self.p_gen <= b.p {
+                if b.p <= a.p {
+                    return (b.q_min, b.q_max);
+                }
+                let t = (self.p_gen - a.p) / (b.p - a.p);
+                return (
+                    a.q_min + (b.q_min - a.q_min) * t,
+                    a.q_max + (b.q_max - a.q_max) * t,
+                );
+            }
+        }
+        self.capability
+            .last()
+            .map_or((self.q_min, self.q_max), |last| (last.q_min, last.q_max))
     }
 }
 
@@ -245,7 +432,299 @@ impl fmt::Display for Generator {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Two-terminal DC line control mode, as RAW MDC.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DcControl {
+    Blocked,
+    Power,
+    Current,
+}
+
+/// One converter of a two-terminal LCC DC line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcConverter {
+    pub bus_id: usize,
+    /// Bridges in series
+    pub bridges: u32,
+    /// Firing angle limits in degrees: alpha at the rectifier, gamma at the inverter
+    pub angle_max: f32,
+    pub angle_min: f32,
+    /// Commutating resistance and reactance in ohms per bridge
+    pub commutating_resistance: f32,
+    pub commutating_reactance: f32,
+    /// Primary base AC voltage in kV
+    pub base_kv: f32,
+    /// Converter transformer ratio, then its tap with limits and step
+    pub transformer_ratio: f32,
+    pub tap: f32,
+    pub tap_max: f32,
+    pub tap_min: f32,
+    pub tap_step: f32,
+}
+
+/// Two-terminal LCC HVDC line. The DC load flow uses it as a fixed
+/// transfer; the AC load flow solves its converter equations with firing
+/// angle and tap control.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcLine {
+    pub name: String,
+    pub control: DcControl,
+    /// DC line resistance in ohms
+    pub resistance: f32,
+    /// MW in power control (positive at the rectifier, negative at the
+    /// inverter) or amps in current control
+    pub setpoint: f32,
+    /// Scheduled DC voltage in kV, held at the inverter
+    pub scheduled_voltage: f32,
+    /// Voltage in kV below which power control drops to current control
+    pub switch_voltage: f32,
+    /// Compounding resistance in ohms
+    pub compounding_resistance: f32,
+    /// Current margin in pu of the current order
+    pub current_margin: f32,
+    pub rectifier: DcConverter,
+    pub inverter: DcConverter,
+}
+
+impl DcLine {
+    /// (MW drawn at the rectifier, MW delivered at the inverter), with the
+    /// compounding point held at the scheduled voltage and I²R loss on the
+    /// line. The inverter sits `compounding_resistance` below that point.
+    pub fn transfer(&self) -> (f32, f32) {
+        let v = self.scheduled_voltage;
+        let (r, rc) = (self.resistance, self.compounding_resistance);
+        // kV times kA gives MW; power at either end is a I² + v I with the
+        // resistance between the compounding point and that end
+        let (a, p) = match self.control {
+            DcControl::Blocked => return (0.0, 0.0),
+            _ if v <= 0.0 => return (0.0, 0.0),
+            DcControl::Current => (0.0, self.setpoint / 1000.0 * v),
+            DcControl::Power if self.setpoint < 0.0 => (-rc, -self.setpoint),
+            DcControl::Power => (r - rc, self.setpoint),
+        };
+        let current = if a == 0.0 {
+            p / v
+        } else {
+            let discriminant = v * v + 4.0 * a * p;
+            if discriminant < 0.0 {
+                return (0.0, 0.0);
+            }
+            (discriminant.sqrt() - v) / (2.0 * a)
+        };
+        let delivered = (v - rc * current) * current;
+        (delivered + r * current * current, delivered)
+    }
+}
+
+/// Bus of a DC grid, joining VSC converters and DC branches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcBus {
+    pub dc_bus_id: usize,
+    pub name: String,
+    /// DC voltage in kV; the starting point of the DC grid solve and its result
+    pub voltage: f32,
+}
+
+/// DC grid branch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcBranch {
+    pub from_bus: usize,
+    pub to_bus: usize,
+    pub name: String,
+    pub status: bool,
+    /// Resistance in ohms
+    pub resistance: f32,
+    /// Solved MW leaving the from bus
+    pub flow: f32,
+}
+
+/// DC side control of a VSC converter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VscDcControl {
+    /// Fixed MW into the AC bus (negative to draw from it)
+    Power,
+    /// Fixed DC voltage in kV; takes up the balance of its DC grid
+    Voltage,
+}
+
+/// AC side control of a VSC converter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VscAcControl {
+    /// AC voltage in pu
+    Voltage,
+    /// MVAR into the AC bus
+    ReactivePower,
+    /// Power factor, as RAW MODE 2
+    PowerFactor,
+}
+
+/// VSC converter between an AC bus and a DC bus. The DC load flow only uses
+/// its active power; the AC load flow also applies its reactive control.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VscConverter {
+    pub name: String,
+    pub ac_bus_id: usize,
+    pub dc_bus_id: usize,
+    pub status: bool,
+    pub dc_control: VscDcControl,
+    /// MW or kV by `dc_control`
+    pub dc_setpoint: f32,
+    pub ac_control: VscAcControl,
+    /// pu voltage, MVAR or power factor by `ac_control`
+    pub ac_setpoint: f32,
+    /// Losses of `loss_a` kW plus `loss_b` kW per amp of converter current, at least `loss_min` kW
+    pub loss_a: f32,
+    pub loss_b: f32,
+    pub loss_min: f32,
+    /// Ratings in MVA and amps, and reactive limits in MVAR
+    pub s_max: f32,
+    pub i_max: f32,
+    pub q_max: f32,
+    pub q_min: f32,
+    /// Solved MW and MVAR into the AC bus
+    pub p_ac: f32,
+    #[serde(default)]
+    pub q_ac: f32,
+}
+
+impl VscConverter {
+    /// Converter loss in MW at a converter current of `current` kA.
+    pub fn loss(&self, current: f32) -> f32 {
+        (self.loss_a + self.loss_b * current.abs() * 1000.0).max(self.loss_min) / 1000.0
+    }
+}
+
+/// Control of a FACTS series link.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SeriesControl {
+    /// Holds the flow setpoints at the to bus
+    Flow,
+    /// Fixed series impedance, as in a TCSC
+    Impedance,
+    /// Fixed series voltage behind the link reactance
+    Voltage,
+    /// Bypassed, a zero impedance link
+    Bypassed,
+}
+
+/// Series link of a FACTS device between its bus and `to_bus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesLink {
+    pub to_bus: usize,
+    pub control: SeriesControl,
+    /// MW and MVAR arriving at the to bus under flow control
+    pub p_setpoint: f32,
+    pub q_setpoint: f32,
+    /// Resistance and reactance in pu under impedance control, or the
+    /// magnitude in pu and angle in degrees of the series voltage, relative
+    /// to the from bus voltage, under voltage control (RAW SET1, SET2)
+    pub setpoint_real: f32,
+    pub setpoint_imag: f32,
+    /// Series current limit in MVA at unity voltage; 0 for none
+    pub rating: f32,
+    /// Reactance in pu behind the series voltage (RAW LINX)
+    #[serde(default)]
+    pub reactance: f32,
+    /// Solved MW from the device bus to `to_bus`
+    pub flow: f32,
+}
+
+/// FACTS device: a shunt link (SVC, STATCOM) holding its bus voltage within
+/// reactive limits, with an optional series link (TCSC, SSSC, UPFC). The DC
+/// load flow only sees the series link.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactsDevice {
+    pub name: String,
+    pub bus_id: usize,
+    pub status: bool,
+    /// Shunt link voltage setpoint in pu and reactive limits in MVAR at
+    /// unity voltage; equal limits for no shunt link
+    pub v_setpoint: f32,
+    pub q_max: f32,
+    pub q_min: f32,
+    pub series: Option<SeriesLink>,
+    /// Solved MVAR of the shunt link into the bus
+    #[serde(default)]
+    pub q_shunt: f32,
+}
+
+/// Smallest series reactance in pu; bypassed links take it so B' stays
+/// finite.
+const MIN_SERIES_REACTANCE: f32 = 1e-4;
+
+impl SeriesLink {
+    /// Resistance and reactance in pu of the link between the buses, or None
+    /// under flow control, where the link is a pair of injections.
+    pub fn impedance(&self) -> Option<(f32, f32)> {
+        let (r, x) = match self.control {
+            SeriesControl::Flow => return None,
+            SeriesControl::Impedance => (self.setpoint_real, self.setpoint_imag),
+            SeriesControl::Voltage => (0.0, self.reactance),
+            SeriesControl::Bypassed => (0.0, 0.0),
+        };
+        if x.abs() < MIN_SERIES_REACTANCE {
+            Some((r, MIN_SERIES_REACTANCE))
+        } else {
+            Some((r, x))
+        }
+    }
+
+    /// Reactance in pu the DC load flow gives the link, or None under flow
+    /// control.
+    pub fn dc_reactance(&self) -> Option<f32> {
+        self.impedance().map(|(_, x)| x)
+    }
+
+    /// Series voltage in pu of the from bus voltage, as (in phase,
+    /// quadrature); zero unless under voltage control.
+    pub fn series_voltage(&self) -> (f32, f32) {
+        if self.control != SeriesControl::Voltage {
+            return (0.0, 0.0);
+        }
+        let (sin, cos) = self.setpoint_imag.to_radians().sin_cos();
+        (self.setpoint_real * cos, self.setpoint_real * sin)
+    }
+
+    /// Angle in radians the series voltage advances the link by.
+    pub fn dc_shift(&self) -> f32 {
+        let (d, q) = self.series_voltage();
+        q.atan2(1.0 + d)
+    }
+
+    /// MW held under flow control, limited to the rating.
+    pub fn scheduled_flow(&self) -> f32 {
+        self.scheduled_power(1.0).0
+    }
+
+    /// MW and MVAR held at the to bus under flow control with it at `v` pu,
+    /// scaled back together to the rating.
+    pub fn scheduled_power(&self, v: f32) -> (f32, f32) {
+        let (p, q) = (self.p_setpoint, self.q_setpoint);
+        let s = p.hypot(q);
+        let limit = self.rating * v;
+        if self.rating > 0.0 && s > limit {
+            (p * limit / s, q * limit / s)
+        } else {
+            (p, q)
+        }
+    }
+}
+
+/// A multi-section line: series branches through dummy buses that are
+/// switched and reported as one circuit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineGroup {
+    pub from_bus: usize,
+    pub to_bus: usize,
+    /// Circuit id of the group, conventionally starting with '&'
+    pub id: String,
+    /// Whether flows are metered at `from_bus` rather than `to_bus`
+    pub metered_from: bool,
+    /// Dummy buses in order from `from_bus` to `to_bus`
+    pub dummy_buses: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Network {
     pub case_name: String,
     pub s_base: f32,
@@ -255,6 +734,23 @@ pub struct Network {
     pub branches: Vec<Branch>,
     pub loads: Vec<Load>,
     pub generators: Vec<Generator>,
+    /// RAWX record tables mantis does not model, as JSON text keyed by record type
+    #[serde(default)]
+    pub passthrough: BTreeMap<String, String>,
+    #[serde(default)]
+    pub dc_lines: Vec<DcLine>,
+    #[serde(default)]
+    pub dc_buses: Vec<DcBus>,
+    #[serde(default)]
+    pub dc_branches: Vec<DcBranch>,
+    #[serde(default)]
+    pub vsc_converters: Vec<VscConverter>,
+    #[serde(default)]
+    pub facts_devices: Vec<FactsDevice>,
+    #[serde(default)]
+    pub impedance_tables: Vec<ImpedanceTable>,
+    #[serde(default)]
+    pub line_groups: Vec<LineGroup>,
     #[serde(skip)]
     pub bus_map: HashMap<usize, usize>, // bus_id -> matrix index (slack excluded)
 }
@@ -310,14 +806,126 @@ impl Network {
             branches: Vec::new(),
             loads: Vec::new(),
             generators: Vec::new(),
+            passthrough: BTreeMap::new(),
+            dc_lines: Vec::new(),
+            dc_buses: Vec::new(),
+            dc_branches: Vec::new(),
+            vsc_converters: Vec::new(),
+            facts_devices: Vec::new(),
+            impedance_tables: Vec::new(),
+            line_groups: Vec::new(),
             bus_map: HashMap::new(),
         }
     }
 
+    /// Whether `bus_id` exists and is in service.
+    pub fn bus_in_service(&self, bus_id: usize) -> bool {
+        self.buses
+            .iter()
+            .any(|b| b.bus_id == bus_id && b.bus_status && b.bus_type != BusType::OUT)
+    }
+
+    /// Net MW injected at each AC bus by HVDC: two-terminal lines draw their
+    /// `transfer` at the rectifier and deliver it at the inverter, and VSC
+    /// converters inject their solved `p_ac`. A converter at an out-of-service
+    /// bus blocks its line.
+    pub fn hvdc_injections(&self) -> HashMap<usize, f32> {
+        let mut injections = self.vsc_injections();
+        for line in &self.dc_lines {
+            if !self.bus_in_service(line.rectifier.bus_id)
+                || !self.bus_in_service(line.inverter.bus_id)
+            {
+                continue;
+            }
+            let (drawn, delivered) = line.transfer();
+            *injections.entry(line.rectifier.bus_id).or_default() -= drawn;
+            *injections.entry(line.inverter.bus_id).or_default() += delivered;
+        }
+        injections
+    }
+
+    /// MW injected at each AC bus by in-service VSC converters.
+    pub(crate) fn vsc_injections(&self) -> HashMap<usize, f32> {
+        let mut injections = HashMap::new();
+        for conv in self
+            .vsc_converters
+            .iter()
+            .filter(|c| c.status && self.bus_in_service(c.ac_bus_id))
+        {
+            *injections.entry(conv.ac_bus_id).or_default() += conv.p_ac;
+        }
+        injections
+    }
+
+    /// Series impedance (R, X) of each branch at its present tap and angle,
+    /// indexed like `branches`: R + jX times the complex factor of its
+    /// impedance correction table, if the table exists.
+    pub fn effective_impedances(&self) -> Vec<(f32, f32)> {
+        let tables: HashMap<usize, &ImpedanceTable> = self
+            .impedance_tables
+            .iter()
+            .map(|t| (t.number, t))
+            .collect();
+        self.branches
+            .iter()
+            .map(|br| {
+                let (re, im) = br
+                    .impedance_table
+                    .and_then(|link| {
+                        let x = if link.by_angle {
+                            br.phase_shift
+                        } else {
+                            br.tap_ratio
+                        };
+                        tables.get(&link.table).map(|t| t.factor(x))
+                    })
+                    .unwrap_or((1.0, 0.0));
+                (
+                    br.resistance * re - br.reactance * im,
+                    br.resistance * im + br.reactance * re,
+                )
+            })
+            .collect()
+    }
+
+    /// Reactance part of `effective_impedances`, for the lossless DC models.
+    pub fn effective_reactances(&self) -> Vec<f32> {
+        self.effective_impedances()
+            .into_iter()
+            .map(|(_, x)| x)
+            .collect()
+    }
+
+    /// MW leaving `bus_id` through in-service FACTS series links.
+    pub fn facts_flow_out(&self, bus_id: usize) -> f32 {
+        self.facts_devices
+            .iter()
+            .filter(|d| d.status)
+            .filter_map(|d| d.series.as_ref().map(|link| (d.bus_id, link)))
+            .map(|(from, link)| {
+                if from == bus_id {
+                    link.flow
+                } else if link.to_bus == bus_id {
+                    -link.flow
+                } else {
+                    0.0
+                }
+            })
+            .sum()
+    }
+
     /// Compute (P_mismatch, Q_mismatch) for a given bus.
-    /// P_mis = P_gen - P_load - P_flow_out
+    /// P_mis = P_gen + P_dc - P_load - P_flow_out
+    /// P_flow_out covers branches and FACTS series links.
     /// Q_mis = Q_gen - Q_load
+    /// Loads are evaluated at the bus voltage magnitude.
     pub fn bus_mismatch(&self, bus_id: usize) -> (f32, f32) {
+        let v = self
+            .buses
+            .iter()
+            .find(|b| b.bus_id == bus_id)
+            .map(|b| b.voltage)
+            .unwrap_or(1.0);
         let p_gen: f32 = self
             .generators
             .iter()
@@ -330,18 +938,12 @@ impl Network {
             .filter(|g| g.gen_bus_id == bus_id && g.gen_status)
             .map(|g| g.q_gen)
             .sum();
-        let p_load: f32 = self
-            .loads
-            .iter()
-            .filter(|l| l.bus_id == bus_id)
-            .map(|l| l.real_load)
-            .sum();
-        let q_load: f32 = self
+        let (p_load, q_load) = self
             .loads
             .iter()
-            .filter(|l| l.bus_id == bus_id)
-            .map(|l| l.imag_load)
-            .sum();
+            .filter(|l| l.bus_id == bus_id && l.load_status)
+            .map(|l| l.power_at(v))
+            .fold((0.0, 0.0), |(p, q), (lp, lq)| (p + lp, q + lq));
         let p_flow_out: f32 = self
             .branches
             .iter()
@@ -355,19 +957,152 @@ impl Network {
                     0.0
                 }
             })
-            .sum();
-        (p_gen - p_load - p_flow_out, q_gen - q_load)
+            .sum::<f32>()
+            + self.facts_flow_out(bus_id);
+        let p_dc = self.hvdc_injections().get(&bus_id).copied().unwrap_or(0.0);
+        (p_gen + p_dc - p_load - p_flow_out, q_gen - q_load)
     }
 
     /// Rebuild bus_map from current buses list (must be called after any bus change)
     pub fn rebuild_bus_map(&mut self) {
         self.bus_map.clear();
+        let isolated = self.isolated_dummy_buses();
         let mut matrix_idx: usize = 0;
         for bus in &self.buses {
-            if bus.bus_type != BusType::Slack && bus.bus_type != BusType::OUT {
+            if bus.bus_type != BusType::Slack
+                && bus.bus_type != BusType::OUT
+                && !isolated.contains(&bus.bus_id)
+            {
                 self.bus_map.insert(bus.bus_id, matrix_idx);
                 matrix_idx += 1;
             }
         }
     }
+
+    /// Index into `buses` of each bus id.
+    pub fn bus_indices(&self) -> HashMap<usize, usize> {
+        self.buses
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.bus_id, i))
+            .collect()
+    }
+
+    /// Circuit id of each branch: its own `ckt`, or for a blank one the lowest
+    /// number not already used between its bus pair, lines ahead of transformers.
+    pub fn circuit_ids(&self) -> Vec<String> {
+        let pair = |br: &Branch| (br.from_bus.min(br.to_bus), br.from_bus.max(br.to_bus));
+        let mut used: HashMap<(usize, usize), HashSet<String>> = HashMap::new();
+        for br in self.branches.iter().filter(|br| !br.ckt.trim().is_empty()) {
+            used.entry(pair(br))
+                .or_default()
+                .insert(br.ckt.trim().to_uppercase());
+        }
+        let mut ids: Vec<String> = self.branches.iter().map(|br| br.ckt.clone()).collect();
+        let blank_lines_first = self
+            .branches
+            .iter()
+            .enumerate()
+            .filter(|(_, br)| br.branch_type == BranchType::Line)
+            .chain(
+                self.branches
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, br)| br.branch_type == BranchType::TwoWinding),
+            )
+            .filter(|(_, br)| br.ckt.trim().is_empty());
+        for (i, br) in blank_lines_first {
+            let taken = used.entry(pair(br)).or_default();
+            let ckt = (1..)
+                .map(|n: usize| n.to_string())
+                .find(|c| !taken.contains(c))
+                .unwrap_or_default();
+            taken.insert(ckt.clone());
+            ids[i] = ckt;
+        }
+        ids
+    }
+
+    /// Numbers every branch with a blank circuit id; see `circuit_ids`.
+    pub fn number_circuits(&mut self) {
+        let ids = self.circuit_ids();
+        for (br, ckt) in self.branches.iter_mut().zip(ids) {
+            br.ckt = ckt;
+        }
+    }
+
+    /// Index of the branch between `from` and `to` (either direction) with
+    /// circuit id `ckt`.
+    pub fn find_branch(&self, from: usize, to: usize, ckt: &str) -> Option<usize> {
+        self.branches.iter().position(|br| {
+            ((br.from_bus == from && br.to_bus == to) || (br.from_bus == to && br.to_bus == from))
+                && same_id(&br.ckt, ckt)
+        })
+    }
+
+    /// Index of the multi-section line between `from` and `to` (either
+    /// direction) with group id `id`.
+    pub fn find_line_group(&self, from: usize, to: usize, id: &str) -> Option<usize> {
+        self.line_groups.iter().position(|g| {
+            ((g.from_bus == from && g.to_bus == to) || (g.from_bus == to && g.to_bus == from))
+                && same_id(&g.id, id)
+        })
+    }
+
+    /// Indices of a multi-section line's branches in order from its
+    /// `from_bus`, or None if a section is missing. Sections are lines that
+    /// carry the group's id as their circuit id.
+    pub fn group_sections(&self, group: &LineGroup) -> Option<Vec<usize>> {
+        let mut buses = vec![group.from_bus];
+        buses.extend(&group.dummy_buses);
+        buses.push(group.to_bus);
+        buses
+            .windows(2)
+            .map(|pair| {
+                self.branches.iter().position(|br| {
+                    br.branch_type == BranchType::Line
+                        && ((br.from_bus == pair[0] && br.to_bus == pair[1])
+                            || (br.from_bus == pair[1] && br.to_bus == pair[0]))
+                        && same_id(&br.ckt, &group.id)
+                })
+            })
+            .collect()
+    }
+
+    /// Dummy buses of multi-section lines left without an in-service branch,
+    /// as when the line is opened. The solvers leave them out rather than
+    /// treat them as islands.
+    pub fn isolated_dummy_buses(&self) -> HashSet<usize> {
+        let energized: HashSet<usize> = self
+            .branches
+            .iter()
+            .filter(|br| br.branch_status)
+            .flat_map(|br| [br.from_bus, br.to_bus])
+            .collect();
+        self.line_groups
+            .iter()
+            .flat_map(|g| &g.dummy_buses)
+            .filter(|bus_id| !energized.contains(bus_id))
+            .copied()
+            .collect()
+    }
+
+    /// Index of the generator with machine id `id` at `bus_id`.
+    pub fn find_generator(&self, bus_id: usize, id: &str) -> Option<usize> {
+        self.generators
+            .iter()
+            .position(|g| g.gen_bus_id == bus_id && same_id(&g.machine_id, id))
+    }
+
+    /// Index of the load with id `id` at `bus_id`.
+    pub fn find_load(&self, bus_id: usize, id: &str) -> Option<usize> {
+        self.loads
+            .iter()
+            .position(|l| l.bus_id == bus_id && same_id(&l.load_ident, id))
+    }
+}
+
+/// RAW identifiers compare trimmed and without regard to case.
+pub(crate) fn same_id(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
 }
@@ -0,0 +1,183 @@
+use std::fs;
+
+use crate::case::*;
+
+/// Characters `start..end` (0-based) of a fixed-column record, trimmed; empty
+/// when the line is short.
+pub(crate) fn columns(line: &str, start: usize, end: usize) -> &str {
+    let end = end.min(line.len());
+    line.get(start.min(end)..end).unwrap_or("").trim()
+}
+
+/// Reads an IEEE Common Data Format file into a Network.
+pub fn read_cdf(path: &str) -> Result<Network, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_cdf_str(&content)
+}
+
+/// Parses an IEEE Common Data Format case into a Network. Bus demand becomes
+/// a load and bus generation a generator, one per bus; shunts are converted
+/// from per unit to MW / MVAR at 1 pu like the RAW fixed shunts.
+pub fn parse_cdf_str(content: &str) -> Result<Network, String> {
+    let mut lines = content.lines();
+
+    // Title: date, originator, MVA base (cols 32-37), year, season, case id (cols 46-73)
+    let title = lines.next().ok_or("Empty CDF file")?;
+    let s_base: f32 = columns(title, 31, 37).parse().unwrap_or(100.0);
+    let case_name = columns(title, 45, title.len()).to_string();
+    let mut network = Network::new(case_name, s_base, 60.0);
+
+    #[derive(PartialEq)]
+    enum Section {
+        Header,
+        Bus,
+        Branch,
+        Other,
+    }
+    let mut section = Section::Header;
+
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.starts_with("BUS DATA FOLLOWS") {
+            section = Section::Bus;
+            continue;
+        }
+        if trimmed.starts_with("BRANCH DATA FOLLOWS") {
+            section = Section::Branch;
+            continue;
+        }
+        if trimmed.starts_with("-999") || trimmed.starts_with("END OF DATA") {
+            section = Section::Other;
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match section {
+            Section::Bus => {
+                // Number (1-4) and name (6-17) by column, the rest by whitespace:
+                // area, zone, type, V, angle, PL, QL, PG, QG, base kV, desired V,
+                // Qmax, Qmin, G, B, remote bus
+                let bus_id: usize = columns(line, 0, 4)
+                    .parse()
+                    .map_err(|_| format!("Bad CDF bus record: {}", line))?;
+                let bus_name = columns(line, 5, 18).to_string();
+                let values: Vec<f32> = columns(line, 18, line.len())
+                    .split_whitespace()
+                    .map(|v| v.parse().unwrap_or(0.0))
+                    .collect();
+                if values.len() < 15 {
+                    return Err(format!("Bad CDF bus record: {}", line));
+                }
+                let bus_type = match values[2] as u8 {
+                    3 => BusType::Slack,
+                    2 => BusType::PV,
+                    _ => BusType::PQ,
+                };
+
+                let mut bus = Bus::new(bus_id, bus_name, bus_type);
+                bus.area = values[0] as usize;
+                bus.zone = values[1] as usize;
+                bus.voltage = values[3];
+                bus.angle = values[4];
+                bus.nom_voltage = values[9];
+                bus.real_shunt = values[13] * s_base;
+                bus.imag_shunt = values[14] * s_base;
+                network.buses.push(bus);
+
+                let (pl, ql, pg, qg) = (values[5], values[6], values[7], values[8]);
+                if pl != 0.0 || ql != 0.0 {
+                    network.loads.push(Load::new(
+                        network.loads.len(),
+                        bus_id,
+                        format!("Bus{}-1", bus_id),
+                        pl,
+                        ql,
+                    ));
+                }
+                if bus_type != BusType::PQ || pg != 0.0 || qg != 0.0 {
+                    let mut generator = Generator::new(
+                        network.generators.len(),
+                        bus_id,
+                        format!("Bus{}-1", bus_id),
+                    );
+                    generator.p_gen = pg;
+                    generator.q_gen = qg;
+                    // A zero desired voltage means hold the solved voltage
+                    generator.v_setpoint = if values[10] > 0.0 {
+                        values[10]
+                    } else {
+                        values[3]
+                    };
+                    // No real power limits in CDF; use the RAW defaults
+                    generator.p_max = 9999.0;
+                    generator.p_min = -9999.0;
+                    // Reactive limits only apply to PV and swing buses
+                    if bus_type != BusType::PQ {
+                        generator.q_max = values[11];
+                        generator.q_min = values[12];
+                    }
+                    network.generators.push(generator);
+                }
+            }
+
+            Section::Branch => {
+                // Tap bus (1-4), Z bus (6-9), circuit (17) and type (19) by column,
+                // the rest by whitespace: R, X, B, rating 1-3, control bus, side,
+                // final turns ratio, final angle, ...
+                let from_bus: usize = columns(line, 0, 4)
+                    .parse()
+                    .map_err(|_| format!("Bad CDF branch record: {}", line))?;
+                let to_bus: usize = columns(line, 5, 9)
+                    .parse()
+                    .map_err(|_| format!("Bad CDF branch record: {}", line))?;
+                let branch_code: u8 = columns(line, 18, 19).parse().unwrap_or(0);
+                let values: Vec<f32> = columns(line, 19, line.len())
+                    .split_whitespace()
+                    .map(|v| v.parse().unwrap_or(0.0))
+                    .collect();
+                if values.len() < 3 {
+                    return Err(format!("Bad CDF branch record: {}", line));
+                }
+                let value = |i: usize| values.get(i).copied().unwrap_or(0.0);
+                let b = value(2);
+                let ratio = value(8);
+
+                network.branches.push(Branch {
+                    branch_type: if branch_code == 0 {
+                        BranchType::Line
+                    } else {
+                        BranchType::TwoWinding
+                    },
+                    id: network.branches.len(),
+                    from_bus,
+                    to_bus,
+                    ckt: columns(line, 16, 17).to_string(),
+                    branch_name: String::new(),
+                    branch_status: true,
+                    resistance: value(0),
+                    reactance: value(1),
+                    from_shunt_conductance: 0.0,
+                    from_shunt_susceptance: b / 2.0,
+                    to_shunt_conductance: 0.0,
+                    to_shunt_susceptance: b / 2.0,
+                    // A zero ratio means a nominal tap
+                    tap_ratio: if ratio == 0.0 { 1.0 } else { ratio },
+                    phase_shift: value(9),
+                    impedance_table: None,
+                    operating_limit: value(3),
+                    contingency_limit: value(4),
+                    flow: 0.0,
+                });
+            }
+
+            Section::Header | Section::Other => {}
+        }
+    }
+
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
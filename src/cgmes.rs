@@ -0,0 +1,847 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::case::*;
+
+/// One CIM object: its class and properties, with references normalized to
+/// bare ids. Properties are keyed by local name such as "ACLineSegment.r".
+#[derive(Debug, Default)]
+struct CimObject {
+    class: String,
+    properties: HashMap<String, String>,
+}
+
+impl CimObject {
+    fn get(&self, property: &str) -> Option<&str> {
+        self.properties.get(property).map(String::as_str)
+    }
+
+    fn num(&self, property: &str) -> Option<f32> {
+        self.get(property).and_then(|v| v.trim().parse().ok())
+    }
+
+    fn flag(&self, property: &str) -> Option<bool> {
+        self.get(property).map(|v| v.trim() == "true")
+    }
+
+    fn name(&self) -> String {
+        self.get("IdentifiedObject.name").unwrap_or("").to_string()
+    }
+}
+
+/// Objects from every profile, merged by id in first-seen order.
+#[derive(Debug, Default)]
+struct CimModel {
+    objects: HashMap<String, CimObject>,
+    order: Vec<String>,
+}
+
+impl CimModel {
+    fn get(&self, id: &str) -> Option<&CimObject> {
+        self.objects.get(id)
+    }
+
+    /// Objects of any of `classes`, in document order.
+    fn of_class<'a>(
+        &'a self,
+        classes: &'a [&'a str],
+    ) -> impl Iterator<Item = (&'a str, &'a CimObject)> + 'a {
+        self.order
+            .iter()
+            .filter_map(|id| Some((id.as_str(), self.objects.get(id)?)))
+            .filter(|(_, obj)| classes.contains(&obj.class.as_str()))
+    }
+
+    /// Object referenced by `property` of `obj`.
+    fn follow(&self, obj: &CimObject, property: &str) -> Option<&CimObject> {
+        obj.get(property).and_then(|id| self.get(id))
+    }
+
+    /// Adds the top-level objects of one RDF/XML document. SSH, TP and SV
+    /// objects refer to EQ objects by rdf:about and extend them in place.
+    fn add_document(&mut self, xml: &str) {
+        let mut depth = 0;
+        let mut current: Option<(String, CimObject)> = None;
+        let mut property: Option<String> = None;
+        let mut text = String::new();
+        let mut rest = xml;
+
+        while let Some(open) = rest.find('<') {
+            if property.is_some() {
+                text.push_str(&rest[..open]);
+            }
+            rest = &rest[open..];
+
+            // Declarations, comments and doctypes carry no data
+            let skip_to = if rest.starts_with("<?") {
+                Some("?>")
+            } else if rest.starts_with("<!--") {
+                Some("-->")
+            } else if rest.starts_with("<!") {
+                Some(">")
+            } else {
+                None
+            };
+            if let Some(end) = skip_to {
+                match rest.find(end) {
+                    Some(i) => rest = &rest[i + end.len()..],
+                    None => break,
+                }
+                continue;
+            }
+
+            let Some(close) = rest.find('>') else { break };
+            let tag = &rest[1..close];
+            rest = &rest[close + 1..];
+
+            if tag.starts_with('/') {
+                depth -= 1;
+                match depth {
+                    1 => {
+                        if let Some((id, obj)) = current.take() {
+                            self.merge(id, obj);
+                        }
+                    }
+                    2 => {
+                        if let (Some(p), Some((_, obj))) = (property.take(), current.as_mut()) {
+                            obj.properties.insert(p, decode(text.trim()));
+                        }
+                        text.clear();
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let name = local_name(name);
+
+            match depth {
+                1 => {
+                    let id = attribute(attributes, "rdf:ID")
+                        .or_else(|| attribute(attributes, "rdf:about"))
+                        .map(normalize_id)
+                        .unwrap_or_default();
+                    let obj = CimObject {
+                        class: name.to_string(),
+                        properties: HashMap::new(),
+                    };
+                    if self_closing {
+                        self.merge(id, obj);
+                    } else {
+                        current = Some((id, obj));
+                    }
+                }
+                2 => {
+                    if let Some(resource) = attribute(attributes, "rdf:resource") {
+                        if let Some((_, obj)) = current.as_mut() {
+                            obj.properties
+                                .insert(name.to_string(), normalize_id(resource));
+                        }
+                    } else if !self_closing {
+                        property = Some(name.to_string());
+                        text.clear();
+                    }
+                }
+                _ => {}
+            }
+            if !self_closing {
+                depth += 1;
+            }
+        }
+    }
+
+    fn merge(&mut self, id: String, obj: CimObject) {
+        match self.objects.get_mut(&id) {
+            Some(existing) => {
+                if existing.class.is_empty() {
+                    existing.class = obj.class;
+                }
+                existing.properties.extend(obj.properties);
+            }
+            None => {
+                self.order.push(id.clone());
+                self.objects.insert(id, obj);
+            }
+        }
+    }
+}
+
+/// Element name without its namespace prefix.
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Value of `key="..."` in a tag's attribute text.
+fn attribute<'a>(attributes: &'a str, key: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{}=", key))? + key.len() + 1;
+    let quote = attributes[start..].chars().next()?;
+    let value = &attributes[start + 1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// Reduces "#_id", "urn:uuid:id" and enum URIs ending in "#Kind.value" to the bare id.
+fn normalize_id(id: &str) -> String {
+    let id = id.rsplit('#').next().unwrap_or(id);
+    let id = id.strip_prefix("urn:uuid:").unwrap_or(id);
+    id.trim_start_matches('_').to_string()
+}
+
+/// Replaces the predefined XML entities.
+fn decode(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Conducting equipment classes imported as lines.
+const LINE_CLASSES: [&str; 2] = ["ACLineSegment", "SeriesCompensator"];
+const SWITCH_CLASSES: [&str; 4] = ["Switch", "Breaker", "Disconnector", "LoadBreakSwitch"];
+const MACHINE_CLASSES: [&str; 2] = ["SynchronousMachine", "ExternalNetworkInjection"];
+const LOAD_CLASSES: [&str; 4] = [
+    "EnergyConsumer",
+    "ConformLoad",
+    "NonConformLoad",
+    "StationSupply",
+];
+const SHUNT_CLASSES: [&str; 2] = ["LinearShuntCompensator", "NonlinearShuntCompensator"];
+
+/// Reads CGMES profile files (EQ, TP, SSH, SV) into one Network. A directory
+/// reads every .xml file in it.
+pub fn read_cgmes(paths: &[&str]) -> Result<Network, String> {
+    let mut files: Vec<String> = Vec::new();
+    for path in paths {
+        if Path::new(path).is_dir() {
+            let entries =
+                fs::read_dir(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+            let mut xml: Vec<String> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("xml")))
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            xml.sort();
+            files.extend(xml);
+        } else {
+            files.push(path.to_string());
+        }
+    }
+    if files.is_empty() {
+        return Err(String::from("No CGMES profile files found"));
+    }
+
+    let documents = files
+        .iter()
+        .map(|f| fs::read_to_string(f).map_err(|e| format!("Failed to open {}: {}", f, e)))
+        .collect::<Result<Vec<String>, String>>()?;
+    let documents: Vec<&str> = documents.iter().map(String::as_str).collect();
+    parse_cgmes_str(&documents)
+}
+
+/// Terminal ends of one piece of equipment, ordered by sequence number.
+struct Ends {
+    /// (sequence number, terminal id)
+    terminals: Vec<(usize, String)>,
+}
+
+/// Assembles CGMES profile documents into a bus-branch Network. Topological
+/// nodes from the TP profile become buses; without TP, connectivity nodes do.
+/// Impedances are converted from ohms to per unit on a 100 MVA base and SV
+/// voltages become the initial state.
+pub fn parse_cgmes_str(documents: &[&str]) -> Result<Network, String> {
+    let mut model = CimModel::default();
+    for doc in documents {
+        model.add_document(doc);
+    }
+
+    let case_name = model
+        .of_class(&["FullModel"])
+        .find_map(|(_, m)| m.get("Model.description").map(str::to_string))
+        .unwrap_or_default();
+    let mut network = Network::new(case_name, 100.0, 50.0);
+    let s_base = network.s_base;
+
+    // Buses from topological nodes, or connectivity nodes for node-breaker only data
+    let node_class = if model.of_class(&["TopologicalNode"]).next().is_some() {
+        "TopologicalNode"
+    } else {
+        "ConnectivityNode"
+    };
+    let node_property = format!("Terminal.{}", node_class);
+    let node_classes = [node_class];
+    let mut bus_of_node: HashMap<&str, usize> = HashMap::new();
+    for (id, node) in model.of_class(&node_classes) {
+        let base_voltage = model
+            .follow(node, "TopologicalNode.BaseVoltage")
+            .or_else(|| {
+                // Connectivity nodes take the base voltage of their voltage level
+                model
+                    .follow(node, "ConnectivityNode.ConnectivityNodeContainer")
+                    .and_then(|vl| model.follow(vl, "VoltageLevel.BaseVoltage"))
+            })
+            .and_then(|bv| bv.num("BaseVoltage.nominalVoltage"))
+            .unwrap_or(0.0);
+        let bus_id = network.buses.len() + 1;
+        let mut bus = Bus::new(bus_id, node.name(), BusType::PQ);
+        bus.nom_voltage = base_voltage;
+        network.buses.push(bus);
+        bus_of_node.insert(id, bus_id);
+    }
+
+    // SV voltages as the initial state
+    for (_, sv) in model.of_class(&["SvVoltage"]) {
+        let Some(&bus_id) = sv
+            .get("SvVoltage.TopologicalNode")
+            .and_then(|n| bus_of_node.get(n))
+        else {
+            continue;
+        };
+        let bus = &mut network.buses[bus_id - 1];
+        if let Some(v) = sv.num("SvVoltage.v").filter(|_| bus.nom_voltage > 0.0) {
+            bus.voltage = v / bus.nom_voltage;
+        }
+        if let Some(angle) = sv.num("SvVoltage.angle") {
+            bus.angle = angle;
+        }
+    }
+
+    // Terminals of each piece of equipment
+    let mut ends: HashMap<&str, Ends> = HashMap::new();
+    for (id, terminal) in model.of_class(&["Terminal"]) {
+        let Some(equipment) = terminal.get("Terminal.ConductingEquipment") else {
+            continue;
+        };
+        let sequence = terminal
+            .num("ACDCTerminal.sequenceNumber")
+            .map(|s| s as usize)
+            .unwrap_or(usize::MAX);
+        ends.entry(equipment)
+            .or_insert(Ends {
+                terminals: Vec::new(),
+            })
+            .terminals
+            .push((sequence, id.to_string()));
+    }
+    for e in ends.values_mut() {
+        e.terminals.sort();
+    }
+
+    // Bus and connection status of a terminal
+    let terminal_bus = |terminal_id: &str| -> Option<(usize, bool)> {
+        let terminal = model.get(terminal_id)?;
+        let bus_id = *bus_of_node.get(terminal.get(&node_property)?)?;
+        Some((
+            bus_id,
+            terminal.flag("ACDCTerminal.connected").unwrap_or(true),
+        ))
+    };
+    let equipment_buses = |equipment_id: &str| -> Vec<(usize, bool)> {
+        ends.get(equipment_id)
+            .map(|e| {
+                e.terminals
+                    .iter()
+                    .filter_map(|(_, t)| terminal_bus(t))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let in_service = |obj: &CimObject| obj.flag("Equipment.inService").unwrap_or(true);
+    let nom_voltage = |network: &Network, bus_id: usize| network.buses[bus_id - 1].nom_voltage;
+
+    let (operating, contingency) = branch_ratings(&model, &ends, &network, &terminal_bus);
+
+    // Lines and series compensators, impedances in ohms
+    for (id, line) in model.of_class(&LINE_CLASSES) {
+        let buses = equipment_buses(id);
+        let [(from_bus, from_on), (to_bus, to_on)] = buses[..] else {
+            continue;
+        };
+        let prefix = &line.class;
+        let kv = model
+            .follow(line, "ConductingEquipment.BaseVoltage")
+            .and_then(|bv| bv.num("BaseVoltage.nominalVoltage"))
+            .unwrap_or_else(|| nom_voltage(&network, from_bus));
+        let z_base = if kv > 0.0 { kv * kv / s_base } else { 1.0 };
+        let b = line.num(&format!("{}.bch", prefix)).unwrap_or(0.0) * z_base;
+        let g = line.num(&format!("{}.gch", prefix)).unwrap_or(0.0) * z_base;
+        network.branches.push(Branch {
+            branch_type: BranchType::Line,
+            id: network.branches.len(),
+            from_bus,
+            to_bus,
+            ckt: String::new(),
+            branch_name: line.name(),
+            branch_status: from_on && to_on && in_service(line),
+            resistance: line.num(&format!("{}.r", prefix)).unwrap_or(0.0) / z_base,
+            reactance: line.num(&format!("{}.x", prefix)).unwrap_or(0.0) / z_base,
+            from_shunt_conductance: g / 2.0,
+            from_shunt_susceptance: b / 2.0,
+            to_shunt_conductance: g / 2.0,
+            to_shunt_susceptance: b / 2.0,
+            tap_ratio: 1.0,
+            phase_shift: 0.0,
+            impedance_table: None,
+            operating_limit: operating.get(id).copied().unwrap_or(0.0),
+            contingency_limit: contingency
+                .get(id)
+                .or(operating.get(id))
+                .copied()
+                .unwrap_or(0.0),
+            flow: 0.0,
+        });
+    }
+
+    // Switches retained between topological nodes
+    for (id, switch) in model.of_class(&SWITCH_CLASSES) {
+        let buses = equipment_buses(id);
+        let [(from_bus, from_on), (to_bus, to_on)] = buses[..] else {
+            continue;
+        };
+        if from_bus == to_bus {
+            continue;
+        }
+        let open = switch
+            .flag("Switch.open")
+            .or_else(|| switch.flag("Switch.normalOpen"))
+            .unwrap_or(false);
+        // Same zero impedance threshold as the RAW switching devices
+        network.branches.push(Branch {
+            branch_type: BranchType::Line,
+            id: network.branches.len(),
+            from_bus,
+            to_bus,
+            ckt: String::new(),
+            branch_name: switch.name(),
+            branch_status: from_on && to_on && !open,
+            resistance: 0.0,
+            reactance: 1e-4,
+            from_shunt_conductance: 0.0,
+            from_shunt_susceptance: 0.0,
+            to_shunt_conductance: 0.0,
+            to_shunt_susceptance: 0.0,
+            tap_ratio: 1.0,
+            phase_shift: 0.0,
+            impedance_table: None,
+            operating_limit: operating.get(id).copied().unwrap_or(0.0),
+            contingency_limit: contingency.get(id).copied().unwrap_or(0.0),
+            flow: 0.0,
+        });
+    }
+
+    add_transformers(
+        &model,
+        &mut network,
+        &terminal_bus,
+        &operating,
+        &contingency,
+    );
+
+    // Machines and network injections; p and q use the load sign convention
+    let mut slack: Option<(f32, usize)> = None;
+    let mut machine_count: HashMap<usize, usize> = HashMap::new();
+    for (id, machine) in model.of_class(&MACHINE_CLASSES) {
+        let Some(&(bus_id, connected)) = equipment_buses(id).first() else {
+            continue;
+        };
+        let prefix = if machine.class == "ExternalNetworkInjection" {
+            "ExternalNetworkInjection"
+        } else {
+            "RotatingMachine"
+        };
+        let count = machine_count.entry(bus_id).or_insert(0);
+        *count += 1;
+        let mut generator = Generator::new(
+            network.generators.len(),
+            bus_id,
+            format!("Bus{}-{}", bus_id, count),
+        );
+        generator.gen_status = connected && in_service(machine);
+        generator.p_gen = -machine.num(&format!("{}.p", prefix)).unwrap_or(0.0);
+        generator.q_gen = -machine.num(&format!("{}.q", prefix)).unwrap_or(0.0);
+        let (q_min, q_max) = if prefix == "RotatingMachine" {
+            ("SynchronousMachine.minQ", "SynchronousMachine.maxQ")
+        } else {
+            (
+                "ExternalNetworkInjection.minQ",
+                "ExternalNetworkInjection.maxQ",
+            )
+        };
+        generator.q_min = machine.num(q_min).unwrap_or(-9999.0);
+        generator.q_max = machine.num(q_max).unwrap_or(9999.0);
+        match model.follow(machine, "RotatingMachine.GeneratingUnit") {
+            Some(unit) => {
+                generator.p_min = unit.num("GeneratingUnit.minOperatingP").unwrap_or(0.0);
+                generator.p_max = unit.num("GeneratingUnit.maxOperatingP").unwrap_or(9999.0);
+            }
+            None => {
+                generator.p_min = machine
+                    .num("ExternalNetworkInjection.minP")
+                    .unwrap_or(-9999.0);
+                generator.p_max = machine
+                    .num("ExternalNetworkInjection.maxP")
+                    .unwrap_or(9999.0);
+            }
+        }
+        let regulating = machine
+            .flag("RegulatingCondEq.controlEnabled")
+            .unwrap_or(true);
+        let target_kv = model
+            .follow(machine, "RegulatingCondEq.RegulatingControl")
+            .and_then(|rc| rc.num("RegulatingControl.targetValue"));
+        let bus_kv = nom_voltage(&network, bus_id);
+        if let Some(kv) = target_kv.filter(|_| bus_kv > 0.0) {
+            generator.v_setpoint = kv / bus_kv;
+        }
+
+        if generator.gen_status {
+            if regulating {
+                network.buses[bus_id - 1].bus_type = BusType::PV;
+            }
+            // Lowest positive reference priority is the angle reference
+            let priority = machine
+                .num("SynchronousMachine.referencePriority")
+                .or_else(|| machine.num("ExternalNetworkInjection.referencePriority"))
+                .filter(|&p| p > 0.0);
+            let rank = priority.unwrap_or(f32::MAX);
+            if slack.is_none_or(|(best, _)| rank < best) {
+                slack = Some((rank, bus_id));
+            }
+        }
+        network.generators.push(generator);
+    }
+    if let Some((rank, bus_id)) = slack {
+        // Without reference priorities the largest in-service unit is the slack
+        let bus_id = if rank == f32::MAX {
+            network
+                .generators
+                .iter()
+                .filter(|g| g.gen_status)
+                .max_by(|a, b| a.p_max.total_cmp(&b.p_max))
+                .map(|g| g.gen_bus_id)
+                .unwrap_or(bus_id)
+        } else {
+            bus_id
+        };
+        network.buses[bus_id - 1].bus_type = BusType::Slack;
+    }
+
+    // Energy consumers, p and q in MW and MVAR
+    let mut load_count: HashMap<usize, usize> = HashMap::new();
+    for (id, consumer) in model.of_class(&LOAD_CLASSES) {
+        let Some(&(bus_id, connected)) = equipment_buses(id).first() else {
+            continue;
+        };
+        let count = load_count.entry(bus_id).or_insert(0);
+        *count += 1;
+        let mut load = Load::new(
+            network.loads.len(),
+            bus_id,
+            format!("Bus{}-{}", bus_id, count),
+            consumer.num("EnergyConsumer.p").unwrap_or(0.0),
+            consumer.num("EnergyConsumer.q").unwrap_or(0.0),
+        );
+        load.load_status = connected && in_service(consumer);
+        network.loads.push(load);
+    }
+
+    // Shunt compensators, lumped onto the bus as MW / MVAR at 1 pu
+    for (id, shunt) in model.of_class(&SHUNT_CLASSES) {
+        let Some(&(bus_id, connected)) = equipment_buses(id).first() else {
+            continue;
+        };
+        if !connected || !in_service(shunt) {
+            continue;
+        }
+        let sections = shunt
+            .num("ShuntCompensator.sections")
+            .or_else(|| shunt.num("ShuntCompensator.normalSections"))
+            .unwrap_or(0.0);
+        let (g, b) = if shunt.class == "LinearShuntCompensator" {
+            (
+                shunt
+                    .num("LinearShuntCompensator.gPerSection")
+                    .unwrap_or(0.0)
+                    * sections,
+                shunt
+                    .num("LinearShuntCompensator.bPerSection")
+                    .unwrap_or(0.0)
+                    * sections,
+            )
+        } else {
+            model
+                .of_class(&["NonlinearShuntCompensatorPoint"])
+                .filter(|(_, p)| {
+                    p.get("NonlinearShuntCompensatorPoint.NonlinearShuntCompensator") == Some(id)
+                        && p.num("NonlinearShuntCompensatorPoint.sectionNumber")
+                            .is_some_and(|n| n <= sections)
+                })
+                .fold((0.0, 0.0), |(g, b), (_, p)| {
+                    (
+                        g + p.num("NonlinearShuntCompensatorPoint.g").unwrap_or(0.0),
+                        b + p.num("NonlinearShuntCompensatorPoint.b").unwrap_or(0.0),
+                    )
+                })
+        };
+        // Susceptance in siemens; MVAR at 1 pu is b times the nominal kV squared
+        let kv = Some(nom_voltage(&network, bus_id))
+            .filter(|&kv| kv > 0.0)
+            .or_else(|| shunt.num("ShuntCompensator.nomU"))
+            .unwrap_or(0.0);
+        let bus = &mut network.buses[bus_id - 1];
+        bus.real_shunt += g * kv * kv;
+        bus.imag_shunt += b * kv * kv;
+    }
+
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// Operating (PATL) and contingency (TATL) ratings in MVA by equipment id,
+/// taking the lowest limit of each type found on the equipment's terminals.
+fn branch_ratings(
+    model: &CimModel,
+    ends: &HashMap<&str, Ends>,
+    network: &Network,
+    terminal_bus: &dyn Fn(&str) -> Option<(usize, bool)>,
+) -> (HashMap<String, f32>, HashMap<String, f32>) {
+    let mut equipment_of_terminal: HashMap<&str, &str> = HashMap::new();
+    for (equipment, e) in ends {
+        for (_, terminal) in &e.terminals {
+            equipment_of_terminal.insert(terminal, equipment);
+        }
+    }
+
+    let mut operating: HashMap<String, f32> = HashMap::new();
+    let mut contingency: HashMap<String, f32> = HashMap::new();
+    for (_, limit) in model.of_class(&["CurrentLimit", "ApparentPowerLimit"]) {
+        let Some(set) = model.follow(limit, "OperationalLimit.OperationalLimitSet") else {
+            continue;
+        };
+        let terminal = set.get("OperationalLimitSet.Terminal");
+        let equipment = terminal
+            .and_then(|t| equipment_of_terminal.get(t).copied())
+            .or_else(|| set.get("OperationalLimitSet.Equipment"));
+        let Some(equipment) = equipment else {
+            continue;
+        };
+
+        let is_current = limit.class == "CurrentLimit";
+        let prefix = if is_current {
+            "CurrentLimit"
+        } else {
+            "ApparentPowerLimit"
+        };
+        let Some(value) = limit
+            .num(&format!("{}.value", prefix))
+            .or_else(|| limit.num(&format!("{}.normalValue", prefix)))
+        else {
+            continue;
+        };
+        let mva = if is_current {
+            // Amps to MVA at the terminal's nominal voltage
+            let kv = terminal
+                .or_else(|| {
+                    ends.get(equipment)
+                        .and_then(|e| e.terminals.first().map(|(_, t)| t.as_str()))
+                })
+                .and_then(terminal_bus)
+                .map(|(bus_id, _)| network.buses[bus_id - 1].nom_voltage)
+                .unwrap_or(0.0);
+            3f32.sqrt() * kv * value / 1000.0
+        } else {
+            value
+        };
+
+        let kind = model
+            .follow(limit, "OperationalLimit.OperationalLimitType")
+            .and_then(|t| {
+                t.get("OperationalLimitType.limitType")
+                    .map(str::to_lowercase)
+            })
+            .unwrap_or_default();
+        let target = if kind.ends_with("tatl") {
+            &mut contingency
+        } else if kind.ends_with("patl") || kind.is_empty() {
+            &mut operating
+        } else {
+            continue;
+        };
+        let entry = target.entry(equipment.to_string()).or_insert(mva);
+        *entry = entry.min(mva);
+    }
+    (operating, contingency)
+}
+
+/// Adds two-winding transformers as one branch and three-winding transformers
+/// as three branches to a new star point bus.
+fn add_transformers(
+    model: &CimModel,
+    network: &mut Network,
+    terminal_bus: &dyn Fn(&str) -> Option<(usize, bool)>,
+    operating: &HashMap<String, f32>,
+    contingency: &HashMap<String, f32>,
+) {
+    let s_base = network.s_base;
+
+    // Ends of each transformer ordered by end number
+    let mut windings: HashMap<&str, Vec<(usize, &str, &CimObject)>> = HashMap::new();
+    for (id, end) in model.of_class(&["PowerTransformerEnd"]) {
+        if let Some(transformer) = end.get("PowerTransformerEnd.PowerTransformer") {
+            let number = end.num("TransformerEnd.endNumber").unwrap_or(0.0) as usize;
+            windings
+                .entry(transformer)
+                .or_default()
+                .push((number, id, end));
+        }
+    }
+
+    // Off-nominal ratio factor and phase shift from tap changers, by end id
+    let mut taps: HashMap<&str, (f32, f32)> = HashMap::new();
+    let step = |tc: &CimObject| {
+        let position = tc
+            .num("TapChanger.step")
+            .or_else(|| tc.num("TapChanger.normalStep"))
+            .unwrap_or(0.0);
+        position - tc.num("TapChanger.neutralStep").unwrap_or(0.0)
+    };
+    for (_, tc) in model.of_class(&["RatioTapChanger"]) {
+        if let Some(end) = tc.get("RatioTapChanger.TransformerEnd") {
+            let increment = tc
+                .num("RatioTapChanger.stepVoltageIncrement")
+                .unwrap_or(0.0);
+            taps.entry(end).or_insert((1.0, 0.0)).0 *= 1.0 + step(tc) * increment / 100.0;
+        }
+    }
+    for (_, tc) in model.of_class(&["PhaseTapChangerLinear", "PhaseTapChangerSymmetrical"]) {
+        if let Some(end) = tc.get("PhaseTapChanger.TransformerEnd") {
+            let angle = if tc.class == "PhaseTapChangerLinear" {
+                step(tc)
+                    * tc.num("PhaseTapChangerLinear.stepPhaseShiftIncrement")
+                        .unwrap_or(0.0)
+            } else {
+                let du = tc
+                    .num("PhaseTapChangerNonLinear.voltageStepIncrement")
+                    .unwrap_or(0.0)
+                    / 100.0;
+                (2.0 * (step(tc) * du / 2.0).atan()).to_degrees()
+            };
+            taps.entry(end).or_insert((1.0, 0.0)).1 += angle;
+        }
+    }
+
+    for (id, transformer) in model.of_class(&["PowerTransformer"]) {
+        let Some(ends) = windings.get_mut(id) else {
+            continue;
+        };
+        ends.sort_by_key(|(number, _, _)| *number);
+
+        // Per unit impedance, voltage ratio and phase shift of each end
+        struct Winding {
+            bus_id: usize,
+            connected: bool,
+            r: f32,
+            x: f32,
+            g: f32,
+            b: f32,
+            ratio: f32,
+            shift: f32,
+            rated_s: f32,
+        }
+        let mut wound: Vec<Winding> = Vec::new();
+        for (_, end_id, end) in ends.iter() {
+            let Some((bus_id, connected)) =
+                end.get("TransformerEnd.Terminal").and_then(terminal_bus)
+            else {
+                continue;
+            };
+            let rated_u = end.num("PowerTransformerEnd.ratedU").unwrap_or(0.0);
+            let z_base = if rated_u > 0.0 {
+                rated_u * rated_u / s_base
+            } else {
+                1.0
+            };
+            let bus_kv = network.buses[bus_id - 1].nom_voltage;
+            let (factor, shift) = taps.get(end_id).copied().unwrap_or((1.0, 0.0));
+            wound.push(Winding {
+                bus_id,
+                connected,
+                r: end.num("PowerTransformerEnd.r").unwrap_or(0.0) / z_base,
+                x: end.num("PowerTransformerEnd.x").unwrap_or(0.0) / z_base,
+                g: end.num("PowerTransformerEnd.g").unwrap_or(0.0) * z_base,
+                b: end.num("PowerTransformerEnd.b").unwrap_or(0.0) * z_base,
+                ratio: if rated_u > 0.0 && bus_kv > 0.0 {
+                    rated_u * factor / bus_kv
+                } else {
+                    factor
+                },
+                shift,
+                rated_s: end.num("PowerTransformerEnd.ratedS").unwrap_or(0.0),
+            });
+        }
+
+        let status = transformer.flag("Equipment.inService").unwrap_or(true);
+        let name = transformer.name();
+        let operating_limit = operating.get(id).copied();
+        let contingency_limit = contingency.get(id).copied().or(operating_limit);
+        let branch = |network: &mut Network,
+                      from: &Winding,
+                      to_bus: usize,
+                      connected: bool,
+                      r: f32,
+                      x: f32,
+                      ratio: f32,
+                      shift: f32| {
+            network.branches.push(Branch {
+                branch_type: BranchType::TwoWinding,
+                id: network.branches.len(),
+                from_bus: from.bus_id,
+                to_bus,
+                ckt: String::new(),
+                branch_name: name.clone(),
+                branch_status: status && connected,
+                resistance: r,
+                reactance: x,
+                from_shunt_conductance: from.g,
+                from_shunt_susceptance: from.b,
+                to_shunt_conductance: 0.0,
+                to_shunt_susceptance: 0.0,
+                tap_ratio: ratio,
+                phase_shift: shift,
+                impedance_table: None,
+                operating_limit: operating_limit.unwrap_or(from.rated_s),
+                contingency_limit: contingency_limit.unwrap_or(from.rated_s),
+                flow: 0.0,
+            });
+        };
+
+        match &wound[..] {
+            [w1, w2] => branch(
+                network,
+                w1,
+                w2.bus_id,
+                w1.connected && w2.connected,
+                w1.r + w2.r,
+                w1.x + w2.x,
+                w1.ratio / w2.ratio,
+                w1.shift - w2.shift,
+            ),
+            [w1, _, _] => {
+                // Star point bus at the winding 1 voltage
+                let star_id = network.buses.iter().map(|b| b.bus_id).max().unwrap_or(0) + 1;
+                let mut star = Bus::new(star_id, name.clone(), BusType::PQ);
+                star.nom_voltage = network.buses[w1.bus_id - 1].nom_voltage;
+                network.buses.push(star);
+                for w in &wound {
+                    branch(network, w, star_id, w.connected, w.r, w.x, w.ratio, w.shift);
+                }
+            }
+            _ => {}
+        }
+    }
+}
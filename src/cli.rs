@@ -1,10 +1,77 @@
+use crate::capability::read_capability;
 use crate::case::Network;
-use crate::parse::read_case_v33;
+use crate::cdf::read_cdf;
+use crate::cgmes::read_cgmes;
+use crate::epc::read_epc;
+use crate::export::write_case_v33;
+use crate::matpower::{read_matpower, write_matpower};
+use crate::monitor::{RatingSet, ViolationFilter};
+use crate::native::{from_json, read_native, write_json_schema, write_native};
+use crate::pandapower::{is_pandapower, parse_pandapower_str, write_pandapower};
+use crate::parse::read_case;
+use crate::powerworld::{apply_aux, read_aux, read_aux_case};
+use crate::pypsa::{read_pypsa, write_pypsa};
+use crate::rawx::{read_rawx, write_rawx};
+use crate::subsystem::{MonitorRecord, MonitorSet, Subsystem, read_mon, read_sub};
+use crate::ucte::read_ucte;
+use std::collections::HashSet;
 use std::io::{self, Write};
+use std::path::Path;
+
+/// Lower-case extension of a case file `open` can read, if it is one.
+fn case_extension(path: &str) -> Option<String> {
+    let extension = Path::new(path)
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase();
+    ["raw", "rawx", "m", "cdf", "uct", "epc"]
+        .contains(&extension.as_str())
+        .then_some(extension)
+}
+
+/// Reads a case file with the reader for its extension; a directory is read
+/// as a set of CGMES profiles.
+fn read_case_file(path: &str) -> Result<Network, String> {
+    if Path::new(path).is_dir() {
+        return read_cgmes(&[path]);
+    }
+    match case_extension(path).as_deref() {
+        Some("raw") => read_case(path),
+        Some("rawx") => read_rawx(path),
+        Some("m") => read_matpower(path),
+        Some("cdf") => read_cdf(path),
+        Some("uct") => read_ucte(path),
+        Some("epc") => read_epc(path),
+        _ => Err(format!("Unknown case file type: {}", path)),
+    }
+}
+
+/// Bus ids of the subsystem named by an optional table argument; None selects every bus.
+fn subsystem_buses(
+    n: &Network,
+    subsystems: &[Subsystem],
+    name: Option<&&str>,
+) -> Result<Option<HashSet<usize>>, String> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    subsystems
+        .iter()
+        .find(|s| s.name.eq_ignore_ascii_case(name))
+        .map(|s| Some(s.bus_ids(n)))
+        .ok_or_else(|| {
+            format!(
+                "Unknown subsystem '{}'. Load one with 'subsystems <file>'.",
+                name
+            )
+        })
+}
 
 /// Runs the interactive command-line interface
 pub fn run_cli() {
     let mut net: Option<Network> = None;
+    let mut subsystems: Vec<Subsystem> = Vec::new();
+    let mut monitored: Vec<MonitorRecord> = Vec::new();
 
     println!("mantis - power systems analysis");
     println!("Type 'help' for available commands.\n");
@@ -18,7 +85,7 @@ pub fn run_cli() {
             break 'cli;
         }
 
-        let parts: Vec<&str> = input.trim().split_whitespace().collect();
+        let parts: Vec<&str> = input.split_whitespace().collect();
         if parts.is_empty() {
             continue 'cli;
         }
@@ -27,14 +94,16 @@ pub fn run_cli() {
         match parts[0] {
             "open" => {
                 if parts.len() < 2 {
-                    // List available .raw files
+                    // List available case files
                     println!("Available cases in cases/:");
                     match std::fs::read_dir("cases") {
                         Ok(entries) => {
                             for entry in entries.flatten() {
                                 let name = entry.file_name();
                                 let name = name.to_string_lossy();
-                                if name.to_lowercase().ends_with(".raw") {
+                                if entry.path().is_dir() {
+                                    println!("  {}/", name);
+                                } else if case_extension(&name).is_some() {
                                     println!("  {}", name);
                                 }
                             }
@@ -46,7 +115,13 @@ pub fn run_cli() {
                 }
                 let filename = parts[1];
                 let path = format!("cases/{}", filename);
-                let n = read_case_v33(&path);
+                let n = match read_case_file(&path) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue 'cli;
+                    }
+                };
                 println!(
                     "Loaded: {} ({} buses, {} branches, {} generators, {} loads)",
                     n.case_name,
@@ -63,7 +138,14 @@ pub fn run_cli() {
                     println!("No case loaded. Use 'open <filename>' first.");
                     continue 'cli;
                 };
-                if n.dc_approximation() {
+                if parts.get(1) == Some(&"ac") {
+                    match n.ac_load_flow() {
+                        Ok(iterations) => {
+                            println!("AC load flow converged in {} iterations.", iterations)
+                        }
+                        Err(e) => println!("AC load flow failed: {}", e),
+                    }
+                } else if n.dc_approximation() {
                     println!("DC load flow solved successfully.");
                 } else {
                     println!("DC load flow failed.");
@@ -75,12 +157,21 @@ pub fn run_cli() {
                     println!("No case loaded.");
                     continue 'cli;
                 };
+                let selected = match subsystem_buses(n, &subsystems, parts.get(1)) {
+                    Ok(selected) => selected,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue 'cli;
+                    }
+                };
+                let in_subsystem =
+                    |id: usize| selected.as_ref().is_none_or(|ids| ids.contains(&id));
                 println!(
                     "{:>5}  {:<14}  {:>4}  {:>8}  {:>9}  {:>10}  {:>10}",
                     "ID", "Name", "Type", "Vnom(kV)", "V(pu)", "Angle(deg)", "Pmis(MW)"
                 );
                 println!("{}", "-".repeat(75));
-                for bus in &n.buses {
+                for bus in n.buses.iter().filter(|b| in_subsystem(b.bus_id)) {
                     let (p_mis, _q_mis) = n.bus_mismatch(bus.bus_id);
                     println!(
                         "{:>5}  {:<14}  {:>4}  {:>8.2}  {:>9.6}  {:>10.4}  {:>10.3}",
@@ -100,17 +191,31 @@ pub fn run_cli() {
                     println!("No case loaded.");
                     continue 'cli;
                 };
+                let selected = match subsystem_buses(n, &subsystems, parts.get(1)) {
+                    Ok(selected) => selected,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue 'cli;
+                    }
+                };
+                let in_subsystem =
+                    |id: usize| selected.as_ref().is_none_or(|ids| ids.contains(&id));
                 println!(
-                    "{:>4}  {:>5}  {:>5}  {:>4}  {:>10}  {:>10}  {:>10}  {:>6}",
-                    "ID", "From", "To", "Type", "R(pu)", "X(pu)", "Flow(MW)", "Status"
+                    "{:>4}  {:>5}  {:>5}  {:>3}  {:>4}  {:>10}  {:>10}  {:>10}  {:>6}",
+                    "ID", "From", "To", "Ckt", "Type", "R(pu)", "X(pu)", "Flow(MW)", "Status"
                 );
-                println!("{}", "-".repeat(70));
-                for br in &n.branches {
+                println!("{}", "-".repeat(75));
+                for br in n
+                    .branches
+                    .iter()
+                    .filter(|br| in_subsystem(br.from_bus) || in_subsystem(br.to_bus))
+                {
                     println!(
-                        "{:>4}  {:>5}  {:>5}  {:>4}  {:>10.6}  {:>10.6}  {:>10.2}  {:>6}",
+                        "{:>4}  {:>5}  {:>5}  {:>3}  {:>4}  {:>10.6}  {:>10.6}  {:>10.2}  {:>6}",
                         br.id,
                         br.from_bus,
                         br.to_bus,
+                        br.ckt,
                         br.branch_type,
                         br.resistance,
                         br.reactance,
@@ -125,19 +230,32 @@ pub fn run_cli() {
                     println!("No case loaded.");
                     continue 'cli;
                 };
+                let selected = match subsystem_buses(n, &subsystems, parts.get(1)) {
+                    Ok(selected) => selected,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue 'cli;
+                    }
+                };
+                let in_subsystem =
+                    |id: usize| selected.as_ref().is_none_or(|ids| ids.contains(&id));
                 println!(
-                    "{:>4}  {:>5}  {:<16}  {:>10}  {:>10}  {:>6}",
-                    "ID", "Bus", "Name", "P(MW)", "Q(MVAR)", "Status"
+                    "{:>4}  {:>5}  {:<16}  {:>10}  {:>10}  {:>10}  {:>10}  {:>6}",
+                    "ID", "Bus", "Name", "P(MW)", "Q(MVAR)", "Qmin", "Qmax", "Status"
                 );
-                println!("{}", "-".repeat(60));
-                for g in &n.generators {
+                println!("{}", "-".repeat(84));
+                for g in n.generators.iter().filter(|g| in_subsystem(g.gen_bus_id)) {
+                    // Q limits at the dispatched P, from the capability curve if any
+                    let (q_min, q_max) = g.q_limits();
                     println!(
-                        "{:>4}  {:>5}  {:<16}  {:>10.3}  {:>10.3}  {:>6}",
+                        "{:>4}  {:>5}  {:<16}  {:>10.3}  {:>10.3}  {:>10.3}  {:>10.3}  {:>6}",
                         g.gen_id,
                         g.gen_bus_id,
                         g.gen_name,
                         g.p_gen,
                         g.q_gen,
+                        q_min,
+                        q_max,
                         if g.gen_status { "ON" } else { "OFF" }
                     );
                 }
@@ -145,23 +263,27 @@ pub fn run_cli() {
 
             "import" => {
                 if parts.len() < 2 {
-                    println!("Usage: import <file.json|file.bin>");
+                    println!("Usage: import <file.json|file.bin|file.rawx|file.m|pypsa folder>");
                     continue 'cli;
                 }
                 let path = parts[1];
-                let result: Result<Network, String> = if path.ends_with(".bin") {
-                    std::fs::read(path)
-                        .map_err(|e| format!("Error reading file: {}", e))
-                        .and_then(|bytes| {
-                            bincode::deserialize(&bytes)
-                                .map_err(|e| format!("Error parsing bincode: {}", e))
-                        })
+                let result: Result<Network, String> = if Path::new(path).is_dir() {
+                    read_pypsa(path)
+                } else if path.ends_with(".rawx") {
+                    read_rawx(path)
+                } else if path.ends_with(".m") {
+                    read_matpower(path)
+                } else if path.ends_with(".bin") {
+                    read_native(path).map(|case| case.network)
                 } else {
                     std::fs::read_to_string(path)
                         .map_err(|e| format!("Error reading file: {}", e))
                         .and_then(|contents| {
-                            serde_json::from_str(&contents)
-                                .map_err(|e| format!("Error parsing JSON: {}", e))
+                            if is_pandapower(&contents) {
+                                parse_pandapower_str(&contents)
+                            } else {
+                                from_json(&contents).map(|case| case.network)
+                            }
                         })
                 };
                 match result {
@@ -187,24 +309,24 @@ pub fn run_cli() {
                     continue 'cli;
                 };
                 if parts.len() < 2 {
-                    println!("Usage: export <file.json|file.bin>");
+                    println!(
+                        "Usage: export <file.json|file.bin|file.raw|file.rawx|file.m> [pandapower|pypsa]"
+                    );
                     continue 'cli;
                 }
                 let path = parts[1];
-                let result: Result<(), String> = if path.ends_with(".bin") {
-                    bincode::serialize(n)
-                        .map_err(|e| format!("Error serializing: {}", e))
-                        .and_then(|bytes| {
-                            std::fs::write(path, &bytes)
-                                .map_err(|e| format!("Error writing file: {}", e))
-                        })
+                let result: Result<(), String> = if parts.get(2) == Some(&"pypsa") {
+                    write_pypsa(path, n)
+                } else if parts.get(2) == Some(&"pandapower") {
+                    write_pandapower(path, n)
+                } else if path.ends_with(".rawx") {
+                    write_rawx(path, n)
+                } else if path.ends_with(".m") {
+                    write_matpower(path, n)
+                } else if path.ends_with(".raw") {
+                    write_case_v33(path, n)
                 } else {
-                    serde_json::to_string_pretty(n)
-                        .map_err(|e| format!("Error serializing: {}", e))
-                        .and_then(|json| {
-                            std::fs::write(path, &json)
-                                .map_err(|e| format!("Error writing file: {}", e))
-                        })
+                    write_native(path, n)
                 };
                 match result {
                     Ok(_) => println!("Exported to {}", path),
@@ -217,30 +339,244 @@ pub fn run_cli() {
                     println!("No case loaded.");
                     continue 'cli;
                 };
+                let selected = match subsystem_buses(n, &subsystems, parts.get(1)) {
+                    Ok(selected) => selected,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue 'cli;
+                    }
+                };
+                let in_subsystem =
+                    |id: usize| selected.as_ref().is_none_or(|ids| ids.contains(&id));
+                println!(
+                    "{:>4}  {:>5}  {:<20}  {:>10}  {:>10}  {:>6}",
+                    "ID", "Bus", "Name", "P(MW)", "Q(MVAR)", "Status"
+                );
+                println!("{}", "-".repeat(63));
+                for l in n.loads.iter().filter(|l| in_subsystem(l.bus_id)) {
+                    println!(
+                        "{:>4}  {:>5}  {:<20}  {:>10.3}  {:>10.3}  {:>6}",
+                        l.load_id,
+                        l.bus_id,
+                        l.load_name,
+                        l.real_load,
+                        l.imag_load,
+                        if l.load_status { "ON" } else { "OFF" }
+                    );
+                }
+            }
+
+            "violations" => {
+                let Some(ref n) = net else {
+                    println!("No case loaded.");
+                    continue 'cli;
+                };
+                let mut filter = ViolationFilter::default();
+                let mut ratings = RatingSet::Operating;
+                for arg in &parts[1..] {
+                    let parsed = match arg.split_once('=') {
+                        Some(("pct", v)) => v.parse().map(|v| filter.threshold = v).is_ok(),
+                        Some(("minkv", v)) => v.parse().map(|v| filter.min_kv = Some(v)).is_ok(),
+                        Some(("maxkv", v)) => v.parse().map(|v| filter.max_kv = Some(v)).is_ok(),
+                        Some(("area", v)) => v
+                            .split(',')
+                            .map(|a| a.parse::<usize>())
+                            .collect::<Result<Vec<_>, _>>()
+                            .map(|areas| filter.areas = areas)
+                            .is_ok(),
+                        Some(("sub", name)) => {
+                            match subsystems
+                                .iter()
+                                .find(|s| s.name.eq_ignore_ascii_case(name))
+                            {
+                                Some(sub) => {
+                                    filter.subsystem = Some(sub.clone());
+                                    true
+                                }
+                                None => {
+                                    println!("Unknown subsystem '{}'", name);
+                                    continue 'cli;
+                                }
+                            }
+                        }
+                        Some(("rating", "a" | "A")) => {
+                            ratings = RatingSet::Operating;
+                            true
+                        }
+                        Some(("rating", "b" | "B")) => {
+                            ratings = RatingSet::Contingency;
+                            true
+                        }
+                        _ => false,
+                    };
+                    if !parsed {
+                        println!(
+                            "Usage: violations [pct=<percent>] [minkv=<kV>] [maxkv=<kV>] [area=<n,...>] [sub=<name>] [rating=a|b]"
+                        );
+                        continue 'cli;
+                    }
+                }
+
+                // Loaded monitored elements restrict the scan
+                if !monitored.is_empty() {
+                    match MonitorSet::resolve(n, &monitored, &subsystems) {
+                        Ok(set) => filter.monitored = Some(set),
+                        Err(e) => {
+                            println!("{}", e);
+                            continue 'cli;
+                        }
+                    }
+                }
+
+                let violations = n.check_violations(ratings, &filter);
                 println!(
-                    "{:>4}  {:>5}  {:<20}  {:>10}  {:>10}",
-                    "ID", "Bus", "Name", "P(MW)", "Q(MVAR)"
+                    "{:<24}  {:<5}  {:>10}  {:>10}  {:>8}",
+                    "Element", "Type", "Value", "Limit", "Percent"
                 );
-                println!("{}", "-".repeat(55));
-                for l in &n.loads {
+                println!("{}", "-".repeat(66));
+                for v in &violations {
                     println!(
-                        "{:>4}  {:>5}  {:<20}  {:>10.3}  {:>10.3}",
-                        l.load_id, l.bus_id, l.load_name, l.real_load, l.imag_load
+                        "{:<24}  {:<5}  {:>10.4}  {:>10.4}  {:>8.1}",
+                        v.element.to_string(),
+                        v.limit_type.to_string(),
+                        v.value,
+                        v.limit,
+                        v.percent
                     );
                 }
+                println!("{} violations", violations.len());
+            }
+
+            "subsystems" => {
+                if parts.len() < 2 {
+                    for sub in &subsystems {
+                        println!("  {} ({} selections)", sub.name, sub.selections.len());
+                    }
+                    println!("Usage: subsystems <file.sub>");
+                    continue 'cli;
+                }
+                match read_sub(parts[1]) {
+                    Ok(loaded) => {
+                        println!("Loaded {} subsystems", loaded.len());
+                        subsystems = loaded;
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+
+            "monitor" => {
+                if parts.len() < 2 {
+                    println!("Usage: monitor <file.mon>");
+                    continue 'cli;
+                }
+                match read_mon(parts[1]) {
+                    Ok(loaded) => {
+                        println!("Loaded {} monitor records", loaded.len());
+                        monitored = loaded;
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+
+            "aux" => {
+                if parts.len() < 2 {
+                    println!("Usage: aux <file.aux>");
+                    continue 'cli;
+                }
+                let applied = match net.as_mut() {
+                    Some(n) => read_aux(parts[1]).and_then(|blocks| apply_aux(n, &blocks)),
+                    None => read_aux_case(parts[1]).map(|(n, data)| {
+                        net = Some(n);
+                        data
+                    }),
+                };
+                match applied {
+                    Ok(data) => {
+                        let n = net.as_ref().unwrap();
+                        println!(
+                            "Applied {}: {} buses, {} branches, {} contingencies, {} interfaces",
+                            parts[1],
+                            n.buses.len(),
+                            n.branches.len(),
+                            data.contingencies.len(),
+                            data.interfaces.len()
+                        );
+                        for interface in &data.interfaces {
+                            println!(
+                                "  {:<24} {:>3} branches  flow={:>9.2} MW  limit={:>9.2} MW",
+                                interface.name,
+                                interface.branches.len(),
+                                n.interface_flow(interface),
+                                interface.operating_limit
+                            );
+                        }
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+
+            "capability" => {
+                if parts.len() < 2 {
+                    println!("Usage: capability <file.csv|file.gcp>");
+                    continue 'cli;
+                }
+                let Some(ref mut n) = net else {
+                    println!("No case loaded.");
+                    continue 'cli;
+                };
+                match read_capability(n, parts[1]) {
+                    Ok(count) => println!("Loaded capability curves for {} machines", count),
+                    Err(e) => println!("{}", e),
+                }
+            }
+
+            "schema" => {
+                if parts.len() < 2 {
+                    println!("Usage: schema <file.json>");
+                    continue 'cli;
+                }
+                match write_json_schema(parts[1]) {
+                    Ok(_) => println!("Case JSON Schema written to {}", parts[1]),
+                    Err(e) => println!("{}", e),
+                }
             }
 
             "help" => {
                 println!("Commands:");
-                println!("  open <file>   Load a RAW case from cases/ directory");
+                println!(
+                    "  open <file>   Load a case from cases/ (.raw, .rawx, .m, .cdf, .uct, .epc or a CGMES directory)"
+                );
                 println!("  open          List available case files");
-                println!("  solve         Run DC load flow");
-                println!("  buses         Print bus table");
-                println!("  branches      Print branch table");
-                println!("  generators    Print generator table");
-                println!("  loads         Print load table");
-                println!("  import <file> Load network from file (.json or .bin)");
-                println!("  export <file> Export network to file (.json or .bin)");
+                println!("  solve [ac]    Run DC load flow, or AC with 'ac'");
+                println!("  buses [sub]   Print bus table, optionally for one loaded subsystem");
+                println!(
+                    "  branches [sub]  Print branch table, optionally for one loaded subsystem"
+                );
+                println!(
+                    "  generators [sub]  Print generator table, optionally for one loaded subsystem"
+                );
+                println!("  loads [sub]   Print load table, optionally for one loaded subsystem");
+                println!(
+                    "  violations    Report limit violations (pct=, minkv=, maxkv=, area=, sub=<name>, rating=a|b)"
+                );
+                println!(
+                    "  subsystems <file>  Load PSS/E .sub subsystems; without a file, list them"
+                );
+                println!(
+                    "  monitor <file>  Load a PSS/E .mon file; violations then checks only its elements"
+                );
+                println!("  aux <file>    Apply a PowerWorld AUX file, or build a case from it");
+                println!(
+                    "  capability <file>  Load generator capability curves (.csv or PSS/E .gcp)"
+                );
+                println!(
+                    "  import <file> Load network from file (.json, .bin, .rawx, .m, pandapower .json or a PyPSA folder)"
+                );
+                println!("  export <file> Export network to file (.json, .bin, .raw, .rawx or .m)");
+                println!(
+                    "  export <path> pandapower|pypsa  Export as pandapower JSON or a PyPSA folder"
+                );
+                println!("  schema <file> Write the JSON Schema of native .json cases");
                 println!("  help          Show this help");
                 println!("  quit / exit   Exit");
             }
@@ -0,0 +1,1061 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+
+use crate::case::*;
+use crate::monitor::{RatingSet, Violation, ViolationFilter};
+use crate::sensitivity::multi_outage_flows;
+
+/// Whether a SET/CHANGE command moves a quantity to a value or by a value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChangeMode {
+    To,
+    By,
+}
+
+/// Unit of the value in a SET/CHANGE command.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChangeUnit {
+    Mw,
+    Percent,
+}
+
+/// A single outage or adjustment applied to the network by a contingency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ContingencyAction {
+    /// OPEN BRANCH FROM BUS i TO BUS j CIRCUIT ckt
+    OpenBranch {
+        from_bus: usize,
+        to_bus: usize,
+        ckt: String,
+    },
+    /// TRIP MACHINE id FROM BUS i / REMOVE UNIT id FROM BUS i
+    TripGenerator { bus_id: usize, machine_id: String },
+    /// SET BUS i LOAD TO value / CHANGE BUS i LOAD BY value
+    LoadChange {
+        bus_id: usize,
+        mode: ChangeMode,
+        value: f32,
+        unit: ChangeUnit,
+    },
+    /// SET MACHINE id AT BUS i DISPATCH TO value / CHANGE ... BY value
+    DispatchChange {
+        bus_id: usize,
+        machine_id: String,
+        mode: ChangeMode,
+        value: f32,
+        unit: ChangeUnit,
+    },
+}
+
+/// Display the action as a PSS/E .con command line.
+impl fmt::Display for ContingencyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = |mode: &ChangeMode| match mode {
+            ChangeMode::To => ("SET", "TO"),
+            ChangeMode::By => ("CHANGE", "BY"),
+        };
+        let unit = |unit: &ChangeUnit| match unit {
+            ChangeUnit::Mw => "MW",
+            ChangeUnit::Percent => "PERCENT",
+        };
+        match self {
+            ContingencyAction::OpenBranch {
+                from_bus,
+                to_bus,
+                ckt,
+            } => write!(
+                f,
+                "OPEN BRANCH FROM BUS {} TO BUS {} CIRCUIT {}",
+                from_bus,
+                to_bus,
+                quote_id(ckt)
+            ),
+            ContingencyAction::TripGenerator { bus_id, machine_id } => write!(
+                f,
+                "REMOVE MACHINE {} FROM BUS {}",
+                quote_id(machine_id),
+                bus_id
+            ),
+            ContingencyAction::LoadChange {
+                bus_id,
+                mode,
+                value,
+                unit: u,
+            } => {
+                let (cmd, prep) = verb(mode);
+                write!(
+                    f,
+                    "{} BUS {} LOAD {} {} {}",
+                    cmd,
+                    bus_id,
+                    prep,
+                    value,
+                    unit(u)
+                )
+            }
+            ContingencyAction::DispatchChange {
+                bus_id,
+                machine_id,
+                mode,
+                value,
+                unit: u,
+            } => {
+                let (cmd, prep) = verb(mode);
+                write!(
+                    f,
+                    "{} MACHINE {} AT BUS {} DISPATCH {} {} {}",
+                    cmd,
+                    quote_id(machine_id),
+                    bus_id,
+                    prep,
+                    value,
+                    unit(u)
+                )
+            }
+        }
+    }
+}
+
+/// A named group of actions that are applied together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contingency {
+    pub name: String,
+    pub actions: Vec<ContingencyAction>,
+}
+
+impl Contingency {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            actions: Vec::new(),
+        }
+    }
+}
+
+/// Display the contingency as a CONTINGENCY ... END block.
+impl fmt::Display for Contingency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.contains(char::is_whitespace) {
+            writeln!(f, "CONTINGENCY '{}'", self.name)?;
+        } else {
+            writeln!(f, "CONTINGENCY {}", self.name)?;
+        }
+        for action in &self.actions {
+            writeln!(f, " {}", action)?;
+        }
+        write!(f, "END")
+    }
+}
+
+/// Quote circuit and machine ids that are not plain alphanumerics.
+fn quote_id(id: &str) -> String {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        id.to_string()
+    } else {
+        format!("'{}'", id)
+    }
+}
+
+/// Split a .con line into tokens, keeping quoted ids together and dropping `/` comments.
+pub(crate) fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => {
+                tokens.push(current.trim().to_string());
+                current.clear();
+                quote = None;
+            }
+            Some(_) => current.push(c),
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '/' => break,
+            None if c.is_whitespace() || c == ',' => {
+                if !current.is_empty() {
+                    tokens.push(current.clone());
+                    current.clear();
+                }
+            }
+            None => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Cursor over the tokens of one command line.
+struct Tokens<'a> {
+    tokens: &'a [String],
+    pos: usize,
+    line: usize,
+}
+
+impl Tokens<'_> {
+    fn next(&mut self) -> Result<&str, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| format!("Line {}: unexpected end of command", self.line))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Consume the next token if it is one of the given keywords.
+    fn accept(&mut self, keywords: &[&str]) -> Option<String> {
+        let token = self.tokens.get(self.pos)?.to_uppercase();
+        if keywords.contains(&token.as_str()) {
+            self.pos += 1;
+            Some(token)
+        } else {
+            None
+        }
+    }
+
+    fn expect(&mut self, keywords: &[&str]) -> Result<String, String> {
+        let line = self.line;
+        let token = self.next()?.to_uppercase();
+        if keywords.contains(&token.as_str()) {
+            Ok(token)
+        } else {
+            Err(format!(
+                "Line {}: expected {} but found '{}'",
+                line,
+                keywords.join("/"),
+                token
+            ))
+        }
+    }
+
+    fn bus(&mut self) -> Result<usize, String> {
+        self.expect(&["BUS"])?;
+        let line = self.line;
+        let token = self.next()?;
+        token
+            .parse()
+            .map_err(|_| format!("Line {}: invalid bus number '{}'", line, token))
+    }
+
+    fn value(&mut self) -> Result<f32, String> {
+        let line = self.line;
+        let token = self.next()?;
+        token
+            .parse()
+            .map_err(|_| format!("Line {}: invalid value '{}'", line, token))
+    }
+
+    fn unit(&mut self) -> ChangeUnit {
+        match self.accept(&["MW", "PERCENT"]).as_deref() {
+            Some("PERCENT") => ChangeUnit::Percent,
+            _ => ChangeUnit::Mw,
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.tokens.get(self.pos) {
+            Some(extra) => Err(format!("Line {}: unexpected '{}'", self.line, extra)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse one command line inside a CONTINGENCY block.
+fn parse_action(tokens: &[String], line: usize) -> Result<ContingencyAction, String> {
+    let mut t = Tokens {
+        tokens,
+        pos: 0,
+        line,
+    };
+    let command = t.next()?.to_uppercase();
+
+    let action = match command.as_str() {
+        "OPEN" | "TRIP" | "REMOVE" | "DISCONNECT" if t.accept(&["BRANCH", "LINE"]).is_some() => {
+            t.expect(&["FROM"])?;
+            let from_bus = t.bus()?;
+            t.expect(&["TO"])?;
+            let to_bus = t.bus()?;
+            let ckt = match t.accept(&["CIRCUIT", "CKT"]) {
+                Some(_) => t.next()?.to_string(),
+                None => String::from("1"),
+            };
+            ContingencyAction::OpenBranch {
+                from_bus,
+                to_bus,
+                ckt,
+            }
+        }
+
+        "TRIP" | "REMOVE" | "DISCONNECT" => {
+            t.expect(&["MACHINE", "UNIT"])?;
+            let machine_id = t.next()?.to_string();
+            t.expect(&["FROM", "AT", "ON"])?;
+            let bus_id = t.bus()?;
+            ContingencyAction::TripGenerator { bus_id, machine_id }
+        }
+
+        "SET" | "CHANGE" => match t.expect(&["BUS", "MACHINE", "UNIT"])?.as_str() {
+            "BUS" => {
+                t.pos -= 1;
+                let bus_id = t.bus()?;
+                t.expect(&["LOAD"])?;
+                let mode = match t.expect(&["TO", "BY"])?.as_str() {
+                    "TO" => ChangeMode::To,
+                    _ => ChangeMode::By,
+                };
+                let value = t.value()?;
+                ContingencyAction::LoadChange {
+                    bus_id,
+                    mode,
+                    value,
+                    unit: t.unit(),
+                }
+            }
+            _ => {
+                let machine_id = t.next()?.to_string();
+                t.expect(&["FROM", "AT", "ON"])?;
+                let bus_id = t.bus()?;
+                t.expect(&["DISPATCH"])?;
+                let mode = match t.expect(&["TO", "BY"])?.as_str() {
+                    "TO" => ChangeMode::To,
+                    _ => ChangeMode::By,
+                };
+                let value = t.value()?;
+                ContingencyAction::DispatchChange {
+                    bus_id,
+                    machine_id,
+                    mode,
+                    value,
+                    unit: t.unit(),
+                }
+            }
+        },
+
+        other => return Err(format!("Line {}: unsupported command '{}'", line, other)),
+    };
+
+    t.finish()?;
+    Ok(action)
+}
+
+/// Parses the contents of a PSS/E .con file into a list of contingencies.
+pub fn parse_con_str(content: &str) -> Result<Vec<Contingency>, String> {
+    let mut contingencies = Vec::new();
+    let mut current: Option<Contingency> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+
+        // COM records are comments
+        if trimmed.is_empty() || trimmed.to_uppercase().starts_with("COM") {
+            continue;
+        }
+
+        let tokens = tokenize(trimmed);
+        let Some(first) = tokens.first() else {
+            continue;
+        };
+
+        match first.to_uppercase().as_str() {
+            "CONTINGENCY" => {
+                if current.is_some() {
+                    return Err(format!(
+                        "Line {}: CONTINGENCY inside an open block",
+                        line_number
+                    ));
+                }
+                let name = tokens
+                    .get(1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("CTG{}", contingencies.len() + 1));
+                current = Some(Contingency::new(name));
+            }
+            "END" => match current.take() {
+                Some(contingency) => contingencies.push(contingency),
+                // A bare END closes the file
+                None => break,
+            },
+            _ => match current.as_mut() {
+                Some(contingency) => contingency
+                    .actions
+                    .push(parse_action(&tokens, line_number)?),
+                None => {
+                    return Err(format!(
+                        "Line {}: command outside of a CONTINGENCY block",
+                        line_number
+                    ));
+                }
+            },
+        }
+    }
+
+    if let Some(contingency) = current {
+        return Err(format!(
+            "Contingency '{}' is missing its END",
+            contingency.name
+        ));
+    }
+
+    Ok(contingencies)
+}
+
+/// Reads a PSS/E .con file from disk.
+pub fn read_con(path: &str) -> Result<Vec<Contingency>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_con_str(&content)
+}
+
+/// Formats contingencies as the contents of a PSS/E .con file.
+pub fn format_con(contingencies: &[Contingency]) -> String {
+    let mut out = String::new();
+    for contingency in contingencies {
+        out.push_str(&contingency.to_string());
+        out.push('\n');
+    }
+    out.push_str("END\n");
+    out
+}
+
+/// Writes contingencies to a PSS/E .con file.
+pub fn write_con(path: &str, contingencies: &[Contingency]) -> Result<(), String> {
+    fs::write(path, format_con(contingencies))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// New MW value after a SET/CHANGE command is applied to `current`.
+fn changed_value(current: f32, mode: ChangeMode, value: f32, unit: ChangeUnit) -> f32 {
+    match (mode, unit) {
+        (ChangeMode::To, ChangeUnit::Mw) => value,
+        (ChangeMode::To, ChangeUnit::Percent) => current * value / 100.0,
+        (ChangeMode::By, ChangeUnit::Mw) => current + value,
+        (ChangeMode::By, ChangeUnit::Percent) => current * (1.0 + value / 100.0),
+    }
+}
+
+impl Network {
+    /// Applies every action of a contingency to the network.
+    /// Fails on the first action whose element cannot be found.
+    pub fn apply_contingency(&mut self, contingency: &Contingency) -> Result<(), String> {
+        for action in &contingency.actions {
+            self.apply_action(action)
+                .map_err(|e| format!("{}: {}", contingency.name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Applies a single contingency action to the network.
+    pub fn apply_action(&mut self, action: &ContingencyAction) -> Result<(), String> {
+        match action {
+            ContingencyAction::OpenBranch {
+                from_bus,
+                to_bus,
+                ckt,
+            } => {
+                let matches = self.find_branches(*from_bus, *to_bus, ckt);
+                for &i in &matches {
+                    self.branches[i].branch_status = false;
+                }
+                if matches.is_empty() {
+                    return Err(format!(
+                        "no branch from {} to {} circuit {}",
+                        from_bus, to_bus, ckt
+                    ));
+                }
+            }
+
+            ContingencyAction::TripGenerator { bus_id, machine_id } => {
+                let generator = self.find_machine(*bus_id, machine_id)?;
+                generator.gen_status = false;
+            }
+
+            ContingencyAction::LoadChange {
+                bus_id,
+                mode,
+                value,
+                unit,
+            } => {
+                if !self
+                    .loads
+                    .iter()
+                    .any(|l| l.bus_id == *bus_id && l.load_status)
+                {
+                    return Err(format!("no in-service load at bus {}", bus_id));
+                }
+                let current: f32 = self
+                    .loads
+                    .iter()
+                    .filter(|l| l.bus_id == *bus_id && l.load_status)
+                    .map(|l| l.power_at(1.0).0)
+                    .sum();
+                let target = changed_value(current, *mode, *value, *unit);
+
+                // Loads at the bus keep their share, ZIP split and power factor
+                let factor = if current != 0.0 {
+                    target / current
+                } else if target == 0.0 {
+                    1.0
+                } else {
+                    return Err(format!(
+                        "cannot scale the zero MW load at bus {} to {} MW",
+                        bus_id, target
+                    ));
+                };
+                for load in self
+                    .loads
+                    .iter_mut()
+                    .filter(|l| l.bus_id == *bus_id && l.load_status)
+                {
+                    load.scale(factor);
+                }
+            }
+
+            ContingencyAction::DispatchChange {
+                bus_id,
+                machine_id,
+                mode,
+                value,
+                unit,
+            } => {
+                let generator = self.find_machine(*bus_id, machine_id)?;
+                generator.p_gen = changed_value(generator.p_gen, *mode, *value, *unit);
+            }
+        }
+        Ok(())
+    }
+
+    /// Indices of the branches between two buses, in either direction, that a
+    /// (from, to, ckt) reference selects. A reference to a multi-section line
+    /// selects all of its sections.
+    pub fn find_branches(&self, from_bus: usize, to_bus: usize, ckt: &str) -> Vec<usize> {
+        if let Some(group) = self.find_line_group(from_bus, to_bus, ckt) {
+            return self
+                .group_sections(&self.line_groups[group])
+                .unwrap_or_default();
+        }
+        self.branches
+            .iter()
+            .enumerate()
+            .filter(|(_, br)| {
+                ((br.from_bus == from_bus && br.to_bus == to_bus)
+                    || (br.from_bus == to_bus && br.to_bus == from_bus))
+                    && same_id(&br.ckt, ckt)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Find a generator by bus number and machine id.
+    fn find_machine(&mut self, bus_id: usize, machine_id: &str) -> Result<&mut Generator, String> {
+        let index = self
+            .find_generator(bus_id, machine_id)
+            .ok_or_else(|| format!("no machine {} at bus {}", machine_id, bus_id))?;
+        Ok(&mut self.generators[index])
+    }
+}
+
+/// Single-branch outages for every in-service branch with an end at or above
+/// `min_kv`. A multi-section line is one outage of all its sections.
+pub fn branches_above_kv(network: &Network, min_kv: f32) -> Vec<Contingency> {
+    let kv = |bus_id: usize| {
+        network
+            .buses
+            .iter()
+            .find(|b| b.bus_id == bus_id)
+            .map(|b| b.nom_voltage)
+            .unwrap_or(0.0)
+    };
+
+    let mut contingencies: Vec<Contingency> = Vec::new();
+    let mut grouped: HashSet<usize> = HashSet::new();
+    for group in &network.line_groups {
+        let Some(sections) = network.group_sections(group) else {
+            continue;
+        };
+        grouped.extend(&sections);
+        if !sections.iter().all(|&i| network.branches[i].branch_status)
+            || kv(group.from_bus).max(kv(group.to_bus)) < min_kv
+        {
+            continue;
+        }
+        let mut contingency = Contingency::new(format!(
+            "BR_{}_{}_{}",
+            group.from_bus,
+            group.to_bus,
+            group.id.trim()
+        ));
+        contingency.actions.push(ContingencyAction::OpenBranch {
+            from_bus: group.from_bus,
+            to_bus: group.to_bus,
+            ckt: group.id.clone(),
+        });
+        contingencies.push(contingency);
+    }
+
+    for (index, br) in network.branches.iter().enumerate() {
+        if grouped.contains(&index)
+            || !br.branch_status
+            || kv(br.from_bus).max(kv(br.to_bus)) < min_kv
+        {
+            continue;
+        }
+        let name = format!("BR_{}_{}_{}", br.from_bus, br.to_bus, br.ckt.trim());
+        if contingencies.iter().any(|c| c.name == name) {
+            continue;
+        }
+        let mut contingency = Contingency::new(name);
+        contingency.actions.push(ContingencyAction::OpenBranch {
+            from_bus: br.from_bus,
+            to_bus: br.to_bus,
+            ckt: br.ckt.clone(),
+        });
+        contingencies.push(contingency);
+    }
+    contingencies
+}
+
+/// Single-unit outages for every in-service generator in the given area.
+pub fn generators_in_area(network: &Network, area: usize) -> Vec<Contingency> {
+    network
+        .generators
+        .iter()
+        .filter(|g| g.gen_status)
+        .filter(|g| {
+            network
+                .buses
+                .iter()
+                .any(|b| b.bus_id == g.gen_bus_id && b.area == area)
+        })
+        .map(|g| {
+            let machine_id = g.machine_id.clone();
+            let mut contingency = Contingency::new(format!("GEN_{}_{}", g.gen_bus_id, machine_id));
+            contingency.actions.push(ContingencyAction::TripGenerator {
+                bus_id: g.gen_bus_id,
+                machine_id,
+            });
+            contingency
+        })
+        .collect()
+}
+
+/// Post-contingency DC solution summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContingencyResult {
+    pub name: String,
+    pub solved: bool,
+    /// Set when an action could not be applied.
+    pub error: Option<String>,
+    /// Post-contingency branch flows in MW, indexed like `Network::branches`.
+    pub flows: Vec<f32>,
+    /// Most heavily loaded branch as (branch id, percent of contingency limit).
+    pub max_loading: Option<(usize, f32)>,
+    /// Post-contingency violations against contingency ratings.
+    #[serde(default)]
+    pub violations: Vec<Violation>,
+}
+
+impl ContingencyResult {
+    /// Summarize a network that has just been solved (or failed to solve),
+    /// looking only at the elements the filter reports.
+    pub(crate) fn from_solution(
+        name: &str,
+        network: &Network,
+        solved: bool,
+        filter: &ViolationFilter,
+    ) -> Self {
+        let (max_loading, violations) = if solved {
+            let buses = network.bus_indices();
+            let max_loading = network
+                .branches
+                .iter()
+                .enumerate()
+                .filter(|(_, br)| br.branch_status && br.contingency_limit > 0.0)
+                .filter(|&(i, _)| filter.includes_branch(network, &buses, i))
+                .map(|(_, br)| (br.id, br.flow.abs() / br.contingency_limit * 100.0))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            let violations = network.check_violations(RatingSet::Contingency, filter);
+            (max_loading, violations)
+        } else {
+            (None, Vec::new())
+        };
+        Self {
+            name: name.to_string(),
+            solved,
+            error: None,
+            flows: network.branches.iter().map(|br| br.flow).collect(),
+            max_loading,
+            violations,
+        }
+    }
+
+    pub(crate) fn failed(name: &str, error: String) -> Self {
+        Self {
+            name: name.to_string(),
+            solved: false,
+            error: Some(error),
+            flows: Vec::new(),
+            max_loading: None,
+            violations: Vec::new(),
+        }
+    }
+}
+
+/// ContingencyResult display implementation.
+impl fmt::Display for ContingencyResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.error, self.solved, self.max_loading) {
+            (Some(e), _, _) => write!(f, "{:<32} ERROR  {}", self.name, e),
+            (None, false, _) => write!(f, "{:<32} DID NOT SOLVE", self.name),
+            (None, true, Some((id, pct))) => write!(
+                f,
+                "{:<32} SOLVED  max loading {:>7.1}% on branch {}",
+                self.name, pct, id
+            ),
+            (None, true, None) => write!(f, "{:<32} SOLVED", self.name),
+        }
+    }
+}
+
+/// NERC TPL category of a paired outage.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PairCategory {
+    /// P6: first outage, system adjustment, second outage.
+    N11,
+    /// P7: both elements lost together (common tower).
+    N2,
+}
+
+/// PairCategory enum display implementation.
+impl fmt::Display for PairCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PairCategory::N11 => write!(f, "N-1-1"),
+            PairCategory::N2 => write!(f, "N-2"),
+        }
+    }
+}
+
+/// Two contingencies studied together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContingencyPair {
+    pub first: Contingency,
+    pub second: Contingency,
+    pub category: PairCategory,
+    /// System adjustments made between the two outages (N-1-1 only).
+    pub adjustments: Vec<ContingencyAction>,
+}
+
+impl ContingencyPair {
+    pub fn new(first: Contingency, second: Contingency, category: PairCategory) -> Self {
+        Self {
+            first,
+            second,
+            category,
+            adjustments: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{} + {}", self.first.name, self.second.name)
+    }
+}
+
+/// Result of a paired outage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairResult {
+    pub first: String,
+    pub second: String,
+    pub category: PairCategory,
+    /// Solution after the first outage, before adjustments (N-1-1 only).
+    pub intermediate: Option<ContingencyResult>,
+    pub result: ContingencyResult,
+}
+
+/// PairResult display implementation.
+impl fmt::Display for PairResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<5} {}", self.category.to_string(), self.result)
+    }
+}
+
+impl Network {
+    /// Solves the DC load flow with a contingency applied to a copy of the network.
+    pub fn run_contingency(&self, contingency: &Contingency) -> ContingencyResult {
+        self.run_contingency_filtered(contingency, &ViolationFilter::default())
+    }
+
+    /// Like `run_contingency`, but loadings and violations are only reported
+    /// for elements the filter accepts, e.g. a subsystem or monitored set.
+    pub fn run_contingency_filtered(
+        &self,
+        contingency: &Contingency,
+        filter: &ViolationFilter,
+    ) -> ContingencyResult {
+        let mut post = self.clone();
+        if let Err(e) = post.apply_contingency(contingency) {
+            return ContingencyResult::failed(&contingency.name, e);
+        }
+        let solved = post.dc_approximation();
+        ContingencyResult::from_solution(&contingency.name, &post, solved, filter)
+    }
+
+    /// Runs each contingency independently against this network.
+    pub fn run_contingencies(&self, contingencies: &[Contingency]) -> Vec<ContingencyResult> {
+        self.run_contingencies_filtered(contingencies, &ViolationFilter::default())
+    }
+
+    /// Runs each contingency independently, reporting only filtered elements.
+    pub fn run_contingencies_filtered(
+        &self,
+        contingencies: &[Contingency],
+        filter: &ViolationFilter,
+    ) -> Vec<ContingencyResult> {
+        contingencies
+            .iter()
+            .map(|c| self.run_contingency_filtered(c, filter))
+            .collect()
+    }
+
+    /// Runs a paired outage. N-2 pairs are applied together; N-1-1 pairs are
+    /// solved after the first outage, adjusted, then solved again after the second.
+    pub fn run_pair(&self, pair: &ContingencyPair) -> PairResult {
+        self.run_pair_filtered(pair, &ViolationFilter::default())
+    }
+
+    /// Like `run_pair`, reporting only filtered elements.
+    pub fn run_pair_filtered(
+        &self,
+        pair: &ContingencyPair,
+        filter: &ViolationFilter,
+    ) -> PairResult {
+        let name = pair.name();
+        let mut post = self.clone();
+
+        let intermediate = match pair.category {
+            PairCategory::N2 => None,
+            PairCategory::N11 => {
+                if let Err(e) = post.apply_contingency(&pair.first) {
+                    return pair.failed(&name, e);
+                }
+                let solved = post.dc_approximation();
+                let first =
+                    ContingencyResult::from_solution(&pair.first.name, &post, solved, filter);
+
+                for action in &pair.adjustments {
+                    if let Err(e) = post.apply_action(action) {
+                        return pair.failed(&name, format!("adjustment: {}", e));
+                    }
+                }
+                Some(first)
+            }
+        };
+
+        if pair.category == PairCategory::N2
+            && let Err(e) = post.apply_contingency(&pair.first)
+        {
+            return pair.failed(&name, e);
+        }
+        if let Err(e) = post.apply_contingency(&pair.second) {
+            return pair.failed(&name, e);
+        }
+        let solved = post.dc_approximation();
+
+        PairResult {
+            first: pair.first.name.clone(),
+            second: pair.second.name.clone(),
+            category: pair.category,
+            intermediate,
+            result: ContingencyResult::from_solution(&name, &post, solved, filter),
+        }
+    }
+
+    /// Runs each pair independently against this network.
+    pub fn run_pairs(&self, pairs: &[ContingencyPair]) -> Vec<PairResult> {
+        self.run_pairs_filtered(pairs, &ViolationFilter::default())
+    }
+
+    /// Runs each pair independently, reporting only filtered elements.
+    pub fn run_pairs_filtered(
+        &self,
+        pairs: &[ContingencyPair],
+        filter: &ViolationFilter,
+    ) -> Vec<PairResult> {
+        pairs
+            .iter()
+            .map(|p| self.run_pair_filtered(p, filter))
+            .collect()
+    }
+}
+
+impl ContingencyPair {
+    fn failed(&self, name: &str, error: String) -> PairResult {
+        PairResult {
+            first: self.first.name.clone(),
+            second: self.second.name.clone(),
+            category: self.category,
+            intermediate: None,
+            result: ContingencyResult::failed(name, error),
+        }
+    }
+
+    /// Pair of `first` and `second`; N-1-1 pairs take the adjustments made
+    /// between the two outages.
+    fn with_adjustments(
+        first: &Contingency,
+        second: &Contingency,
+        category: PairCategory,
+        adjustments: &[ContingencyAction],
+    ) -> Self {
+        let mut pair = ContingencyPair::new(first.clone(), second.clone(), category);
+        if category == PairCategory::N11 {
+            pair.adjustments = adjustments.to_vec();
+        }
+        pair
+    }
+}
+
+/// Every pairing of an outage from `first` with a different outage from
+/// `second`. N-1-1 pairs carry `adjustments`.
+pub fn pairs_from_lists(
+    first: &[Contingency],
+    second: &[Contingency],
+    category: PairCategory,
+    adjustments: &[ContingencyAction],
+) -> Vec<ContingencyPair> {
+    let mut pairs = Vec::new();
+    for a in first {
+        for b in second {
+            if a.name != b.name {
+                pairs.push(ContingencyPair::with_adjustments(
+                    a,
+                    b,
+                    category,
+                    adjustments,
+                ));
+            }
+        }
+    }
+    pairs
+}
+
+/// Indices of the branches a contingency opens, or None if it does anything else.
+fn opened_branches(network: &Network, contingency: &Contingency) -> Option<Vec<usize>> {
+    let mut indices = Vec::new();
+    for action in &contingency.actions {
+        let ContingencyAction::OpenBranch {
+            from_bus,
+            to_bus,
+            ckt,
+        } = action
+        else {
+            return None;
+        };
+        indices.extend(
+            network
+                .find_branches(*from_bus, *to_bus, ckt)
+                .into_iter()
+                .filter(|&i| network.branches[i].branch_status),
+        );
+    }
+    Some(indices)
+}
+
+/// In-service sections of each multi-section line mapped to the first of
+/// them. The sections are in series and carry one flow, so the LODFs see a
+/// group's outage as the outage of that section, without islanding the dummy
+/// buses.
+fn group_representatives(network: &Network) -> HashMap<usize, usize> {
+    let mut representative = HashMap::new();
+    for group in &network.line_groups {
+        let sections: Vec<usize> = network
+            .group_sections(group)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&i| network.branches[i].branch_status)
+            .collect();
+        if let Some(&first) = sections.first() {
+            representative.extend(sections.iter().map(|&i| (i, first)));
+        }
+    }
+    representative
+}
+
+/// Every combination of two outages from `outages` whose estimated combined
+/// effect loads some branch to at least `threshold` percent of its contingency
+/// limit, or islands the system. Post-outage flows are estimated from the base
+/// case DC solution with LODFs; pairs that do more than open branches are kept
+/// unscreened. N-1-1 outcomes depend on the order of the outages, so N-1-1
+/// pairs are kept in both orders and carry `adjustments`.
+pub fn screened_pairs(
+    network: &Network,
+    outages: &[Contingency],
+    category: PairCategory,
+    threshold: f32,
+    adjustments: &[ContingencyAction],
+) -> Vec<ContingencyPair> {
+    let mut base = network.clone();
+    let lodf = if base.dc_approximation() {
+        base.lodf_matrix()
+    } else {
+        None
+    };
+    let flows: Vec<f64> = base.branches.iter().map(|br| br.flow as f64).collect();
+    let representative = group_representatives(&base);
+    let opened: Vec<Option<Vec<usize>>> = outages
+        .iter()
+        .map(|c| {
+            opened_branches(&base, c).map(|indices| {
+                let mut collapsed: Vec<usize> = Vec::new();
+                for i in indices {
+                    let i = representative.get(&i).copied().unwrap_or(i);
+                    if !collapsed.contains(&i) {
+                        collapsed.push(i);
+                    }
+                }
+                collapsed
+            })
+        })
+        .collect();
+
+    let mut pairs = Vec::new();
+    for a in 0..outages.len() {
+        for b in a + 1..outages.len() {
+            let keep = match (&lodf, &opened[a], &opened[b]) {
+                (Some(lodf), Some(first), Some(second)) => {
+                    let mut outaged = first.clone();
+                    outaged.extend(second.iter().filter(|k| !first.contains(k)));
+                    match multi_outage_flows(lodf, &flows, &outaged) {
+                        Some(post) => base.branches.iter().zip(&post).any(|(br, flow)| {
+                            br.contingency_limit > 0.0
+                                && (flow.abs() as f32) / br.contingency_limit * 100.0 >= threshold
+                        }),
+                        None => true,
+                    }
+                }
+                _ => true,
+            };
+            if keep {
+                pairs.push(ContingencyPair::with_adjustments(
+                    &outages[a],
+                    &outages[b],
+                    category,
+                    adjustments,
+                ));
+                if category == PairCategory::N11 {
+                    pairs.push(ContingencyPair::with_adjustments(
+                        &outages[b],
+                        &outages[a],
+                        category,
+                        adjustments,
+                    ));
+                }
+            }
+        }
+    }
+    pairs
+}
@@ -0,0 +1,235 @@
+use crate::case::*;
+use std::collections::{HashMap, HashSet};
+
+/// Iterations allowed for the DC grid current balance.
+const MAX_ITERATIONS: usize = 50;
+/// Largest DC bus voltage change in kV accepted as converged.
+const TOLERANCE: f64 = 1e-6;
+
+/// Solves `a x = b` in place by Gaussian elimination with partial pivoting.
+/// DC grids are a handful of buses, often a single free one, which rsparse's
+/// fill-reducing ordering does not handle. Returns false if `a` is singular.
+fn solve_dense(mut a: Vec<Vec<f64>>, b: &mut [f64]) -> bool {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap_or(col);
+        if a[pivot][col].abs() < 1e-12 {
+            return false;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (x, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    for col in (0..n).rev() {
+        let tail: f64 = (col + 1..n).map(|k| a[col][k] * b[k]).sum();
+        b[col] = (b[col] - tail) / a[col][col];
+    }
+    true
+}
+
+/// DC current in kA carrying `p` MW at `v` kV.
+fn current(p: f32, v: f64) -> f32 {
+    if v > 0.0 { p / v as f32 } else { 0.0 }
+}
+
+impl Network {
+    /// Solves the DC grids behind the VSC converters. Voltage-controlled
+    /// converters hold their DC bus, power-controlled ones draw their setpoint
+    /// plus losses from the grid, and the current balance at the remaining DC
+    /// buses is iterated to convergence. Writes DC bus voltages, DC branch
+    /// flows and each converter's `p_ac`; losses are taken at the DC current.
+    /// Returns false if a grid has no voltage-controlled converter or the
+    /// iteration does not converge.
+    pub fn solve_dc_grid(&mut self) -> bool {
+        self.solve_dc_grid_at(None)
+    }
+
+    /// `solve_dc_grid` with converter losses at `ac_current` kA, indexed like
+    /// `vsc_converters`, once an AC solve has found them. A converter out of
+    /// service, at an out-of-service AC bus or on a DC bus with no in-service
+    /// branch and no other converter carries nothing and has no losses.
+    pub(crate) fn solve_dc_grid_at(&mut self, ac_current: Option<&[f32]>) -> bool {
+        if self.dc_buses.is_empty() {
+            return true;
+        }
+
+        // (from, to, conductance in siemens) of in-service branches
+        let branches: Vec<(usize, usize, f64)> = self
+            .dc_branches
+            .iter()
+            .filter(|br| br.status && br.resistance > 0.0)
+            .map(|br| (br.from_bus, br.to_bus, 1.0 / br.resistance as f64))
+            .collect();
+        let connected: HashSet<usize> = branches.iter().flat_map(|&(f, t, _)| [f, t]).collect();
+
+        let mut at_bus: HashMap<usize, usize> = HashMap::new();
+        let available: Vec<bool> = self
+            .vsc_converters
+            .iter()
+            .map(|c| c.status && self.bus_in_service(c.ac_bus_id))
+            .collect();
+        for (conv, _) in self
+            .vsc_converters
+            .iter()
+            .zip(&available)
+            .filter(|(_, a)| **a)
+        {
+            *at_bus.entry(conv.dc_bus_id).or_default() += 1;
+        }
+        let active: Vec<bool> = self
+            .vsc_converters
+            .iter()
+            .zip(&available)
+            .map(|(c, &a)| a && (connected.contains(&c.dc_bus_id) || at_bus[&c.dc_bus_id] > 1))
+            .collect();
+        let loss = |k: usize, conv: &VscConverter, dc_current: f32| {
+            conv.loss(ac_current.map_or(dc_current, |i| i[k]))
+        };
+
+        let fixed: HashMap<usize, f64> = self
+            .vsc_converters
+            .iter()
+            .zip(&active)
+            .filter(|(c, a)| **a && c.dc_control == VscDcControl::Voltage)
+            .map(|(c, _)| (c.dc_bus_id, c.dc_setpoint as f64))
+            .collect();
+
+        // Buses solved for: connected and not held by a converter
+        let mut index: HashMap<usize, usize> = HashMap::new();
+        for bus in &self.dc_buses {
+            if connected.contains(&bus.dc_bus_id) && !fixed.contains_key(&bus.dc_bus_id) {
+                index.insert(bus.dc_bus_id, index.len());
+            }
+        }
+
+        let start = fixed.values().copied().fold(0.0, f64::max);
+        let mut voltage: HashMap<usize, f64> = self
+            .dc_buses
+            .iter()
+            .map(|b| {
+                let v = match fixed.get(&b.dc_bus_id) {
+                    Some(&v) => v,
+                    None if b.voltage > 0.0 => b.voltage as f64,
+                    None => start,
+                };
+                (b.dc_bus_id, v)
+            })
+            .collect();
+
+        let n = index.len();
+        if n > 0 {
+            let mut g_matrix = vec![vec![0.0f64; n]; n];
+            for &(from, to, g) in &branches {
+                let (i, j) = (index.get(&from), index.get(&to));
+                if let Some(&i) = i {
+                    g_matrix[i][i] += g;
+                }
+                if let Some(&j) = j {
+                    g_matrix[j][j] += g;
+                }
+                if let (Some(&i), Some(&j)) = (i, j) {
+                    g_matrix[i][j] -= g;
+                    g_matrix[j][i] -= g;
+                }
+            }
+
+            let mut converged = false;
+            for _ in 0..MAX_ITERATIONS {
+                // Current injections (kA) at the solved buses, with the
+                // branches to held buses moved to the right-hand side
+                let mut rhs = vec![0.0f64; n];
+                for (k, conv) in self.vsc_converters.iter().enumerate() {
+                    if !active[k] || conv.dc_control != VscDcControl::Power {
+                        continue;
+                    }
+                    if let Some(&i) = index.get(&conv.dc_bus_id) {
+                        let v = voltage[&conv.dc_bus_id];
+                        let p = conv.dc_setpoint;
+                        rhs[i] -= current(p + loss(k, conv, current(p, v)), v) as f64;
+                    }
+                }
+                for &(from, to, g) in &branches {
+                    for (a, b) in [(from, to), (to, from)] {
+                        if let (Some(&i), Some(&v)) = (index.get(&a), fixed.get(&b)) {
+                            rhs[i] += g * v;
+                        }
+                    }
+                }
+
+                // A grid without a voltage-controlled converter is singular
+                if !solve_dense(g_matrix.clone(), &mut rhs) {
+                    return false;
+                }
+                let mut change: f64 = 0.0;
+                for (&bus_id, &i) in &index {
+                    if let Some(v) = voltage.get_mut(&bus_id) {
+                        change = change.max((rhs[i] - *v).abs());
+                        *v = rhs[i];
+                    }
+                }
+                if change < TOLERANCE {
+                    converged = true;
+                    break;
+                }
+            }
+            if !converged {
+                return false;
+            }
+        }
+
+        for bus in &mut self.dc_buses {
+            bus.voltage = voltage[&bus.dc_bus_id] as f32;
+        }
+        let voltage = |bus_id: usize| voltage.get(&bus_id).copied().unwrap_or(0.0);
+
+        // MW leaving each bus into the grid
+        let mut outflow: HashMap<usize, f64> = HashMap::new();
+        for br in &mut self.dc_branches {
+            br.flow = 0.0;
+            if !br.status || br.resistance <= 0.0 {
+                continue;
+            }
+            let (vf, vt) = (voltage(br.from_bus), voltage(br.to_bus));
+            let current = (vf - vt) / br.resistance as f64;
+            br.flow = (vf * current) as f32;
+            *outflow.entry(br.from_bus).or_default() += vf * current;
+            *outflow.entry(br.to_bus).or_default() -= vt * current;
+        }
+
+        // Power-controlled converters deliver their setpoint; voltage-controlled
+        // ones take up the balance
+        let mut p_ac = vec![0.0f32; self.vsc_converters.len()];
+        for (k, conv) in self.vsc_converters.iter().enumerate() {
+            if active[k] && conv.dc_control == VscDcControl::Power {
+                p_ac[k] = conv.dc_setpoint;
+                let v = voltage(conv.dc_bus_id);
+                let drawn = conv.dc_setpoint + loss(k, conv, current(conv.dc_setpoint, v));
+                *outflow.entry(conv.dc_bus_id).or_default() += drawn as f64;
+            }
+        }
+        let mut balanced: HashSet<usize> = HashSet::new();
+        for (k, conv) in self.vsc_converters.iter().enumerate() {
+            if active[k]
+                && conv.dc_control == VscDcControl::Voltage
+                && balanced.insert(conv.dc_bus_id)
+            {
+                let fed = outflow.get(&conv.dc_bus_id).copied().unwrap_or(0.0) as f32;
+                p_ac[k] = -fed - loss(k, conv, current(fed, voltage(conv.dc_bus_id)));
+            }
+        }
+        for (conv, p) in self.vsc_converters.iter_mut().zip(p_ac) {
+            conv.p_ac = p;
+        }
+
+        true
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde_json::{Value, json};
+
+use crate::case::*;
+use crate::contingency::tokenize;
+use crate::parse::parse_field;
+
+/// Sections of an EPC file, named by their header line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Title,
+    Comments,
+    Solution,
+    Bus,
+    Branch,
+    Transformer,
+    Generator,
+    Load,
+    Shunt,
+    Area,
+    Zone,
+    Other,
+}
+
+impl Section {
+    /// The section a header line opens, if it is one.
+    fn from_header(line: &str) -> Option<Self> {
+        let lower = line.to_lowercase();
+        if !lower.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        match lower.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["title", ..] => Some(Section::Title),
+            ["comments", ..] => Some(Section::Comments),
+            ["solution", "parameters", ..] => Some(Section::Solution),
+            [name, "data", ..] => Some(match *name {
+                "bus" => Section::Bus,
+                "branch" => Section::Branch,
+                "transformer" => Section::Transformer,
+                "generator" => Section::Generator,
+                "load" => Section::Load,
+                "shunt" => Section::Shunt,
+                "area" => Section::Area,
+                "zone" => Section::Zone,
+                _ => Section::Other,
+            }),
+            [_, _, "data", ..] => Some(Section::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a GE PSLF .epc file into a Network.
+pub fn read_epc(path: &str) -> Result<Network, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_epc_str(&content)
+}
+
+/// Joins records continued with a trailing `/` onto one line.
+fn logical_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim_end();
+        match trimmed.strip_suffix('/') {
+            Some(head) => {
+                current.push_str(head);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                lines.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// A winding of a transformer record: bus, nominal kV and tap in per unit.
+struct Winding {
+    bus_id: usize,
+    vnom: f32,
+    tap: f32,
+}
+
+/// Parses a GE PSLF .epc case into a Network. Branch data is already in per
+/// unit on the system base; transformer impedances are converted from the
+/// transformer MVA base, windings get an off-nominal ratio from their nominal
+/// kV and taps, and three-winding units are modelled through a star point bus.
+/// Shunts in per unit are converted to MW / MVAR at 1 pu, and loads keep
+/// their constant power, current and impedance parts. Area and zone records
+/// are kept as RAWX pass-through tables.
+pub fn parse_epc_str(content: &str) -> Result<Network, String> {
+    let mut network = Network::new(String::new(), 100.0, 60.0);
+    let mut section = Section::Other;
+    // Scheduled voltage by bus, for generator setpoints
+    let mut vsched: HashMap<usize, f32> = HashMap::new();
+    let mut areas: Vec<Value> = Vec::new();
+    let mut zones: Vec<Value> = Vec::new();
+    // (record, 0-based line) of transformers, built once every bus is known
+    let mut transformers: Vec<(Vec<String>, usize)> = Vec::new();
+
+    for (index, line) in logical_lines(content).iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        // Free text runs up to the next '!'
+        let free_text = matches!(section, Section::Title | Section::Comments);
+        if let Some(next) = Section::from_header(trimmed).filter(|_| !free_text) {
+            section = next;
+            continue;
+        }
+        if trimmed.eq_ignore_ascii_case("end") {
+            break;
+        }
+        if trimmed.starts_with('!') {
+            if matches!(
+                section,
+                Section::Title | Section::Comments | Section::Solution
+            ) {
+                section = Section::Other;
+            }
+            continue;
+        }
+
+        let tokens = tokenize(trimmed);
+        // Identifying fields come before the ':' and data after it
+        let colon = tokens.iter().position(|t| t == ":");
+        let (head, data): (Vec<&str>, Vec<&str>) = match colon {
+            Some(c) => (
+                tokens[..c].iter().map(String::as_str).collect(),
+                tokens[c + 1..].iter().map(String::as_str).collect(),
+            ),
+            None => (tokens.iter().map(String::as_str).collect(), Vec::new()),
+        };
+        let bad = |kind: &str| format!("Line {}: bad {} record", index + 1, kind);
+
+        match section {
+            Section::Title => {
+                if network.case_name.is_empty() {
+                    network.case_name = trimmed.to_string();
+                }
+            }
+
+            Section::Solution => {
+                if let ["sbase", value, ..] = head.as_slice() {
+                    network.s_base = value.parse().unwrap_or(100.0);
+                }
+            }
+
+            Section::Bus => {
+                // busnum "name" kv : ty vsched volt angle ar zone vmax vmin
+                // date_in date_out pid L own st latitude longitude island sdmon
+                // vmax1 vmin1 dvmax
+                let bus_id: usize = head
+                    .first()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| bad("bus"))?;
+                let bus_type = match parse_field(&data, 0, 1) {
+                    0 => BusType::Slack,
+                    2 | -2 => BusType::PV,
+                    _ => BusType::PQ,
+                };
+                let mut bus = Bus::new(bus_id, head.get(1).unwrap_or(&"").to_string(), bus_type);
+                bus.nom_voltage = parse_field(&head, 2, 0.0);
+                bus.voltage = parse_field(&data, 2, 1.0);
+                bus.angle = parse_field(&data, 3, 0.0);
+                bus.area = parse_field(&data, 4, 1);
+                bus.zone = parse_field(&data, 5, 1);
+                bus.owner = parse_field(&data, 12, 1);
+                let limit = |i: usize, default: f32| match parse_field(&data, i, 0.0) {
+                    v if v > 0.0 => v,
+                    _ => default,
+                };
+                bus.v_max_operating = limit(6, bus.v_max_operating);
+                bus.v_min_operating = limit(7, bus.v_min_operating);
+                bus.v_max_contingency = limit(18, bus.v_max_contingency);
+                bus.v_min_contingency = limit(19, bus.v_min_contingency);
+                if parse_field(&data, 13, 1) == 0 {
+                    bus.bus_status = false;
+                    bus.bus_type = BusType::OUT;
+                }
+                let schedule = parse_field(&data, 1, 0.0);
+                vsched.insert(
+                    bus_id,
+                    if schedule > 0.0 {
+                        schedule
+                    } else {
+                        bus.voltage
+                    },
+                );
+                network.buses.push(bus);
+            }
+
+            Section::Branch => {
+                // from "name" kv to "name" kv "ck" se "long_id" : st resist react
+                // charge rate1 rate2 rate3 rate4 aloss lngth ar zone ...
+                let from_bus: usize = parse_field(&head, 0, 0);
+                let to_bus: usize = parse_field(&head, 3, 0);
+                if from_bus == 0 || to_bus == 0 {
+                    return Err(bad("branch"));
+                }
+                let b: f32 = parse_field(&data, 3, 0.0);
+                network.branches.push(Branch {
+                    branch_type: BranchType::Line,
+                    id: network.branches.len(),
+                    from_bus,
+                    to_bus,
+                    ckt: head.get(6).unwrap_or(&"").to_string(),
+                    branch_name: head.get(8).unwrap_or(&"").to_string(),
+                    branch_status: parse_field(&data, 0, 1) == 1,
+                    resistance: parse_field(&data, 1, 0.0),
+                    reactance: parse_field(&data, 2, 0.0),
+                    from_shunt_conductance: 0.0,
+                    from_shunt_susceptance: b / 2.0,
+                    to_shunt_conductance: 0.0,
+                    to_shunt_susceptance: b / 2.0,
+                    tap_ratio: 1.0,
+                    phase_shift: 0.0,
+                    impedance_table: None,
+                    operating_limit: parse_field(&data, 4, 0.0),
+                    contingency_limit: parse_field(&data, 5, 0.0),
+                    flow: 0.0,
+                });
+            }
+
+            Section::Transformer => {
+                if parse_field(&head, 0, 0usize) == 0 || parse_field(&head, 3, 0usize) == 0 {
+                    return Err(bad("transformer"));
+                }
+                transformers.push((tokens.clone(), index));
+            }
+
+            Section::Generator => {
+                // bus "name" kv "id" "long_id" : st ireg "name" kv prf qrf ar zone
+                // pgen pmax pmin qgen qmax qmin mbase ...
+                let bus_id: usize = head
+                    .first()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| bad("generator"))?;
+                let id = head.get(3).unwrap_or(&"1").trim();
+                let mut generator = Generator::new(
+                    network.generators.len(),
+                    bus_id,
+                    format!("Bus{}-{}", bus_id, id),
+                );
+                generator.gen_status = parse_field(&data, 0, 1) == 1;
+                let regulated = match parse_field(&data, 1, 0) {
+                    0 => bus_id,
+                    bus => bus,
+                };
+                generator.v_setpoint = vsched.get(&regulated).copied().unwrap_or(1.0);
+                generator.p_gen = parse_field(&data, 8, 0.0);
+                generator.p_max = parse_field(&data, 9, 9999.0);
+                generator.p_min = parse_field(&data, 10, -9999.0);
+                generator.q_gen = parse_field(&data, 11, 0.0);
+                generator.q_max = parse_field(&data, 12, 9999.0);
+                generator.q_min = parse_field(&data, 13, -9999.0);
+                network.generators.push(generator);
+            }
+
+            Section::Load => {
+                // bus "name" kv "id" "long_id" : st mw mvar mw_i mvar_i mw_z mvar_z
+                // ar zone ...
+                let bus_id: usize = head
+                    .first()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| bad("load"))?;
+                let part = |i: usize| parse_field(&data, i, 0.0f32);
+                let id = head.get(3).unwrap_or(&"1").trim();
+                let mut load = Load::new(
+                    network.loads.len(),
+                    bus_id,
+                    format!("Bus{}-{}", bus_id, id),
+                    part(1),
+                    part(2),
+                );
+                load.real_current = part(3);
+                load.imag_current = part(4);
+                load.real_admittance = part(5);
+                load.imag_admittance = part(6);
+                load.load_status = parse_field(&data, 0, 1) == 1;
+                network.loads.push(load);
+            }
+
+            Section::Shunt => {
+                // bus "name" kv "id" tbus "name" kv "ck" se "long_id" : st ar zone
+                // pu_mw pu_mvar ...
+                let bus_id: usize = head
+                    .first()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| bad("shunt"))?;
+                if parse_field(&data, 0, 1) != 1 {
+                    continue;
+                }
+                let s_base = network.s_base;
+                let bus = network
+                    .buses
+                    .iter_mut()
+                    .find(|b| b.bus_id == bus_id)
+                    .ok_or_else(|| {
+                        format!("Line {}: shunt at unknown bus {}", index + 1, bus_id)
+                    })?;
+                bus.real_shunt += parse_field(&data, 3, 0.0) * s_base;
+                bus.imag_shunt += parse_field(&data, 4, 0.0) * s_base;
+            }
+
+            Section::Area => {
+                // arnum "arnam" swing pdes ptol ...
+                areas.push(json!([
+                    parse_field(&head, 0, 0),
+                    parse_field(&head, 2, 0),
+                    parse_field(&head, 3, 0.0),
+                    parse_field(&head, 4, 0.0),
+                    head.get(1).unwrap_or(&""),
+                ]));
+            }
+
+            Section::Zone => {
+                // zonum "zonam" ...
+                zones.push(json!([
+                    parse_field(&head, 0, 0),
+                    head.get(1).unwrap_or(&"")
+                ]));
+            }
+
+            Section::Comments | Section::Other => {}
+        }
+    }
+
+    for (tokens, index) in &transformers {
+        add_transformer(&mut network, tokens, *index)?;
+    }
+
+    if !areas.is_empty() {
+        let table = json!({"fields": ["iarea", "isw", "pdes", "ptol", "arname"], "data": areas});
+        network
+            .passthrough
+            .insert("area".to_string(), table.to_string());
+    }
+    if !zones.is_empty() {
+        let table = json!({"fields": ["izone", "zoname"], "data": zones});
+        network
+            .passthrough
+            .insert("zone".to_string(), table.to_string());
+    }
+
+    if network.buses.is_empty() {
+        return Err("No bus data found".to_string());
+    }
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// Adds a transformer record as one branch, or three to a star point bus when
+/// it has a tertiary winding.
+fn add_transformer(network: &mut Network, tokens: &[String], index: usize) -> Result<(), String> {
+    // from "name" kv to "name" kv "ck" "long_id" : st ty kreg "name" kv zt
+    // int "name" kv tert "name" kv ar zone tbase ps_r ps_x pt_r pt_x ts_r ts_x
+    // vnomp vnoms vnomt anglp gmag bmag rate1 rate2 rate3 rate4 aloss tmax tmin
+    // vtmax vtmin stepp tapp tapfp tapfs tapft ...
+    let colon = tokens
+        .iter()
+        .position(|t| t == ":")
+        .ok_or_else(|| format!("Line {}: bad transformer record", index + 1))?;
+    let head: Vec<&str> = tokens[..colon].iter().map(String::as_str).collect();
+    let data: Vec<&str> = tokens[colon + 1..].iter().map(String::as_str).collect();
+    let value = |i: usize, default: f32| parse_field(&data, i, default);
+
+    let s_base = network.s_base;
+    let t_base = match value(14, 0.0) {
+        t if t > 0.0 => t,
+        _ => s_base,
+    };
+    // Impedance pairs on the transformer base, converted to the system base
+    let z = |i: usize| {
+        (
+            value(i, 0.0) * s_base / t_base,
+            value(i + 1, 0.0) * s_base / t_base,
+        )
+    };
+    let (zps, zpt, zst) = (z(15), z(17), z(19));
+
+    let kv = |bus_id: usize| {
+        network
+            .buses
+            .iter()
+            .find(|b| b.bus_id == bus_id)
+            .map(|b| b.nom_voltage)
+            .ok_or_else(|| format!("Line {}: transformer at unknown bus {}", index + 1, bus_id))
+    };
+    let winding = |bus_id: usize, vnom_index: usize, tap: f32| -> Result<Winding, String> {
+        let bus_kv = kv(bus_id)?;
+        let vnom = match value(vnom_index, 0.0) {
+            v if v > 0.0 => v,
+            _ => bus_kv,
+        };
+        Ok(Winding {
+            bus_id,
+            vnom: if bus_kv > 0.0 { vnom / bus_kv } else { 1.0 },
+            tap,
+        })
+    };
+    let primary = winding(
+        parse_field(&head, 0, 0),
+        21,
+        value(37, 1.0) * value(38, 1.0),
+    )?;
+    let secondary = winding(parse_field(&head, 3, 0), 22, value(39, 1.0))?;
+    let tertiary = match parse_field(&data, 9, 0) {
+        0 => None,
+        bus_id => Some(winding(bus_id, 23, value(40, 1.0))?),
+    };
+    let star_id = network.buses.iter().map(|b| b.bus_id).max().unwrap_or(0) + 1;
+    let star_kv = kv(primary.bus_id)?;
+
+    let status = parse_field(&data, 0, 1) != 0;
+    let ckt = head.get(6).unwrap_or(&"").to_string();
+    let name = head.get(7).unwrap_or(&"").to_string();
+    let t_ratio = t_base / s_base;
+    let mut push =
+        |from: &Winding, to_bus: usize, to_ratio: f32, (r, x): (f32, f32), primary: bool| {
+            network.branches.push(Branch {
+                branch_type: BranchType::TwoWinding,
+                id: network.branches.len(),
+                from_bus: from.bus_id,
+                to_bus,
+                ckt: ckt.clone(),
+                branch_name: name.clone(),
+                branch_status: status,
+                resistance: r,
+                reactance: x,
+                // Magnetizing admittance on the transformer base, at the primary
+                from_shunt_conductance: if primary {
+                    value(25, 0.0) * t_ratio
+                } else {
+                    0.0
+                },
+                from_shunt_susceptance: if primary {
+                    value(26, 0.0) * t_ratio
+                } else {
+                    0.0
+                },
+                to_shunt_conductance: 0.0,
+                to_shunt_susceptance: 0.0,
+                tap_ratio: from.vnom * from.tap / to_ratio,
+                phase_shift: if primary { value(24, 0.0) } else { 0.0 },
+                impedance_table: None,
+                operating_limit: value(27, 0.0),
+                contingency_limit: value(28, 0.0),
+                flow: 0.0,
+            });
+        };
+
+    let Some(tertiary) = tertiary else {
+        push(
+            &primary,
+            secondary.bus_id,
+            secondary.vnom * secondary.tap,
+            zps,
+            true,
+        );
+        return Ok(());
+    };
+
+    // Star impedances from the winding-to-winding ones
+    let half = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| {
+        ((a.0 + b.0 - c.0) / 2.0, (a.1 + b.1 - c.1) / 2.0)
+    };
+    push(&primary, star_id, 1.0, half(zps, zpt, zst), true);
+    push(&secondary, star_id, 1.0, half(zps, zst, zpt), false);
+    push(&tertiary, star_id, 1.0, half(zpt, zst, zps), false);
+
+    let mut star = Bus::new(star_id, name, BusType::PQ);
+    star.nom_voltage = star_kv;
+    network.buses.push(star);
+    Ok(())
+}
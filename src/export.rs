@@ -0,0 +1,487 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+
+use crate::case::*;
+
+/// PSS/E bus type code (IDE) for a bus type.
+pub(crate) fn bus_code(bus_type: BusType) -> u8 {
+    match bus_type {
+        BusType::PQ => 1,
+        BusType::PV => 2,
+        BusType::Slack => 3,
+        BusType::OUT => 4,
+    }
+}
+
+/// Status flag as written in RAW records.
+pub(crate) fn status(in_service: bool) -> u8 {
+    if in_service { 1 } else { 0 }
+}
+
+/// Line charging as (B, BI, BJ): symmetric charging goes in B, anything
+/// else in the line shunts.
+pub(crate) fn line_charging(br: &Branch) -> (f32, f32, f32) {
+    if br.from_shunt_susceptance == br.to_shunt_susceptance {
+        (
+            br.from_shunt_susceptance + br.to_shunt_susceptance,
+            0.0,
+            0.0,
+        )
+    } else {
+        (0.0, br.from_shunt_susceptance, br.to_shunt_susceptance)
+    }
+}
+
+/// Two-terminal DC control mode code (MDC).
+pub(crate) fn dc_mode(control: DcControl) -> u8 {
+    match control {
+        DcControl::Blocked => 0,
+        DcControl::Power => 1,
+        DcControl::Current => 2,
+    }
+}
+
+/// DC branches as VSC DC line records, with their two converters. Each end
+/// bus must have one converter and no other branch; meshed DC grids and
+/// converters off any branch have no RAW record and fail the write.
+pub(crate) fn vsc_links(network: &Network) -> Result<Vec<(&DcBranch, [&VscConverter; 2])>, String> {
+    let dc_degree = |bus_id: usize| {
+        network
+            .dc_branches
+            .iter()
+            .filter(|br| br.from_bus == bus_id || br.to_bus == bus_id)
+            .count()
+    };
+    let converter_at = |bus_id: usize| -> Option<&VscConverter> {
+        let mut at_bus = network
+            .vsc_converters
+            .iter()
+            .filter(|c| c.dc_bus_id == bus_id);
+        let converter = at_bus.next()?;
+        at_bus.next().is_none().then_some(converter)
+    };
+    let links = network
+        .dc_branches
+        .iter()
+        .map(|br| {
+            let ends = (dc_degree(br.from_bus) == 1 && dc_degree(br.to_bus) == 1)
+                .then(|| Some([converter_at(br.from_bus)?, converter_at(br.to_bus)?]))
+                .flatten();
+            ends.map(|ends| (br, ends)).ok_or_else(|| {
+                format!(
+                    "DC branch '{}' is part of a DC grid that does not fit a VSC DC line record",
+                    br.name
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if let Some(c) = network
+        .vsc_converters
+        .iter()
+        .find(|c| dc_degree(c.dc_bus_id) == 0)
+    {
+        return Err(format!("VSC converter '{}' has no DC branch", c.name));
+    }
+    Ok(links)
+}
+
+/// VSC converter (TYPE, MODE, ACSET). RAW has no reactive power mode; a MVAR
+/// setpoint goes out as the power factor at the solved active power.
+pub(crate) fn vsc_codes(c: &VscConverter) -> (u8, u8, f32) {
+    let kind = match (c.status, c.dc_control) {
+        (false, _) => 0,
+        (true, VscDcControl::Voltage) => 1,
+        (true, VscDcControl::Power) => 2,
+    };
+    let (mode, acset) = match c.ac_control {
+        VscAcControl::Voltage => (1, c.ac_setpoint),
+        VscAcControl::PowerFactor => (2, c.ac_setpoint),
+        VscAcControl::ReactivePower => {
+            let s = c.p_ac.hypot(c.ac_setpoint);
+            (2, if s > 0.0 { c.p_ac.abs() / s } else { 1.0 })
+        }
+    };
+    (kind, mode, acset)
+}
+
+/// FACTS device (J, MODE): J is 0 without a series link, and MODE 0 is out
+/// of service.
+pub(crate) fn facts_mode(device: &FactsDevice) -> (usize, u8) {
+    let (to_bus, mode) = match &device.series {
+        None => (0, 1),
+        Some(link) => (
+            link.to_bus,
+            match link.control {
+                SeriesControl::Flow => 1,
+                SeriesControl::Bypassed => 2,
+                SeriesControl::Impedance => 3,
+                SeriesControl::Voltage => 4,
+            },
+        ),
+    };
+    (to_bus, if device.status { mode } else { 0 })
+}
+
+/// Formats a Network as a PSS/E v33 RAW case. Lines are written before
+/// transformers, so branch ids follow that order when the case is read back.
+/// Transformer shunts on the winding 2 side have no RAW field and are dropped.
+/// DC grids that do not fit VSC DC line records and impedance correction
+/// tables with imaginary factors or more than 11 points, which v33 has no
+/// fields for, are an error.
+pub fn format_raw_v33(network: &Network) -> Result<String, String> {
+    let mut out = String::new();
+    let bus = |bus_id: usize| network.buses.iter().find(|b| b.bus_id == bus_id);
+    let area_zone = |bus_id: usize| {
+        bus(bus_id)
+            .map(|b| (b.area, b.zone, b.owner))
+            .unwrap_or((1, 1, 1))
+    };
+
+    // Header: IC, SBASE, REV, XFRRAT, NXFRAT, BASFRQ / case name, then two title lines
+    let _ = writeln!(
+        out,
+        "0, {}, 33, 0, 1, {}     / {}",
+        network.s_base, network.frequency, network.case_name
+    );
+    let _ = writeln!(out, "{}", network.case_name);
+    let _ = writeln!(out);
+
+    // I, 'NAME', BASKV, IDE, AREA, ZONE, OWNER, VM, VA, NVHI, NVLO, EVHI, EVLO
+    for b in &network.buses {
+        let _ = writeln!(
+            out,
+            "{}, '{:<12}', {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}",
+            b.bus_id,
+            b.bus_name,
+            b.nom_voltage,
+            bus_code(b.bus_type),
+            b.area,
+            b.zone,
+            b.owner,
+            b.voltage,
+            b.angle,
+            b.v_max_operating,
+            b.v_min_operating,
+            b.v_max_contingency,
+            b.v_min_contingency
+        );
+    }
+    let _ = writeln!(out, "0 / END OF BUS DATA, BEGIN LOAD DATA");
+
+    // I, 'ID', STATUS, AREA, ZONE, PL, QL, IP, IQ, YP, YQ, OWNER, SCALE, INTRPT
+    for l in &network.loads {
+        let (area, zone, owner) = area_zone(l.bus_id);
+        let _ = writeln!(
+            out,
+            "{}, '{}', {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, 1, 0",
+            l.bus_id,
+            l.load_ident,
+            status(l.load_status),
+            area,
+            zone,
+            l.real_load,
+            l.imag_load,
+            l.real_current,
+            l.imag_current,
+            l.real_admittance,
+            // YQ is negative for an inductive load
+            0.0 - l.imag_admittance,
+            owner
+        );
+    }
+    let _ = writeln!(out, "0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA");
+
+    // I, 'ID', STATUS, GL, BL
+    for b in &network.buses {
+        if b.real_shunt != 0.0 || b.imag_shunt != 0.0 {
+            let _ = writeln!(
+                out,
+                "{}, '1', 1, {}, {}",
+                b.bus_id, b.real_shunt, b.imag_shunt
+            );
+        }
+    }
+    let _ = writeln!(out, "0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA");
+
+    // I, 'ID', PG, QG, QT, QB, VS, IREG, MBASE, ZR, ZX, RT, XT, GTAP, STAT, RMPCT, PT, PB,
+    // O1, F1, ..., O4, F4, WMOD, WPF
+    for g in &network.generators {
+        let (_, _, owner) = area_zone(g.gen_bus_id);
+        let _ = writeln!(
+            out,
+            "{}, '{}', {}, {}, {}, {}, {}, 0, {}, 0, 1, 0, 0, 1, {}, 100, {}, {}, {}, 1, 0, 1, 0, 1, 0, 1, 0, 1",
+            g.gen_bus_id,
+            g.machine_id,
+            g.p_gen,
+            g.q_gen,
+            g.q_max,
+            g.q_min,
+            g.v_setpoint,
+            network.s_base,
+            status(g.gen_status),
+            g.p_max,
+            g.p_min,
+            owner
+        );
+    }
+    let _ = writeln!(out, "0 / END OF GENERATOR DATA, BEGIN BRANCH DATA");
+
+    let circuits = network.circuit_ids();
+
+    // I, J, 'CKT', R, X, B, RATEA, RATEB, RATEC, GI, BI, GJ, BJ, ST, MET, LEN, O1, F1, ..., O4, F4
+    for (br, ckt) in network
+        .branches
+        .iter()
+        .zip(&circuits)
+        .filter(|(br, _)| br.branch_type == BranchType::Line)
+    {
+        let (b, bi, bj) = line_charging(br);
+        let (_, _, owner) = area_zone(br.from_bus);
+        let _ = writeln!(
+            out,
+            "{}, {}, '{}', {}, {}, {}, {}, {}, 0, {}, {}, {}, {}, {}, 1, 0, {}, 1, 0, 1, 0, 1, 0, 1",
+            br.from_bus,
+            br.to_bus,
+            ckt,
+            br.resistance,
+            br.reactance,
+            b,
+            br.operating_limit,
+            br.contingency_limit,
+            br.from_shunt_conductance,
+            bi,
+            br.to_shunt_conductance,
+            bj,
+            status(br.branch_status),
+            owner
+        );
+    }
+    let _ = writeln!(out, "0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA");
+
+    // Line 1: I, J, K, 'CKT', CW, CZ, CM, MAG1, MAG2, NMETR, 'NAME', STAT, O1, F1, ..., O4, F4, 'VECGRP'
+    // Line 2: R1-2, X1-2, SBASE1-2
+    // Line 3: WINDV1, NOMV1, ANG1, RATA1, RATB1, RATC1, COD1, CONT1, RMA1, RMI1, VMA1, VMI1, NTP1, TAB1, CR1, CX1, CNXA1
+    // Line 4: WINDV2, NOMV2
+    for (br, ckt) in network
+        .branches
+        .iter()
+        .zip(&circuits)
+        .filter(|(br, _)| br.branch_type == BranchType::TwoWinding)
+    {
+        let (_, _, owner) = area_zone(br.from_bus);
+        let _ = writeln!(
+            out,
+            "{}, {}, 0, '{}', 1, 1, 1, {}, {}, 2, '{:<12}', {}, {}, 1, 0, 1, 0, 1, 0, 1, '            '",
+            br.from_bus,
+            br.to_bus,
+            ckt,
+            br.from_shunt_conductance,
+            br.from_shunt_susceptance,
+            br.branch_name.trim(),
+            status(br.branch_status),
+            owner
+        );
+        let _ = writeln!(
+            out,
+            "{}, {}, {}",
+            br.resistance, br.reactance, network.s_base
+        );
+        // COD1 3 (phase shift control) marks a correction table against the angle
+        let (cod, tab) = match br.impedance_table {
+            Some(link) => (if link.by_angle { 3 } else { 0 }, link.table),
+            None => (0, 0),
+        };
+        let _ = writeln!(
+            out,
+            "{}, 0, {}, {}, {}, 0, {}, 0, 1.1, 0.9, 1.1, 0.9, 33, {}, 0, 0, 0",
+            br.tap_ratio, br.phase_shift, br.operating_limit, br.contingency_limit, cod, tab
+        );
+        let _ = writeln!(out, "1, 0");
+    }
+    let _ = writeln!(out, "0 / END OF TRANSFORMER DATA, BEGIN AREA DATA");
+
+    // I, ISW, PDES, PTOL, 'ARNAME'
+    let areas: BTreeSet<usize> = network.buses.iter().map(|b| b.area).collect();
+    for area in &areas {
+        let _ = writeln!(out, "{}, 0, 0, 10, '            '", area);
+    }
+    let _ = writeln!(out, "0 / END OF AREA DATA, BEGIN TWO-TERMINAL DC DATA");
+
+    // Line 1: 'NAME', MDC, RDC, SETVL, VSCHD, VCMOD, RCOMP, DELTI, METER, DCVMIN, CCCITMX, CCCACC
+    // Lines 2 and 3: IP, NB, ANMX, ANMN, RC, XC, EBAS, TR, TAP, TMX, TMN, STP, IC, IF, IT, ID, XCAP
+    for line in &network.dc_lines {
+        let _ = writeln!(
+            out,
+            "'{}', {}, {}, {}, {}, {}, {}, {}, 'I', 0, 20, 1",
+            line.name,
+            dc_mode(line.control),
+            line.resistance,
+            line.setpoint,
+            line.scheduled_voltage,
+            line.switch_voltage,
+            line.compounding_resistance,
+            line.current_margin
+        );
+        for c in [&line.rectifier, &line.inverter] {
+            let _ = writeln!(
+                out,
+                "{}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, 0, 0, 0, '1', 0",
+                c.bus_id,
+                c.bridges,
+                c.angle_max,
+                c.angle_min,
+                c.commutating_resistance,
+                c.commutating_reactance,
+                c.base_kv,
+                c.transformer_ratio,
+                c.tap,
+                c.tap_max,
+                c.tap_min,
+                c.tap_step
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "0 / END OF TWO-TERMINAL DC DATA, BEGIN VSC DC LINE DATA"
+    );
+
+    // Line 1: 'NAME', MDC, RDC, O1, F1
+    // Lines 2 and 3: IBUS, TYPE, MODE, DCSET, ACSET, ALOSS, BLOSS, MINLOSS, SMAX, IMAX, PWF, MAXQ, MINQ, REMOT, RMPCT
+    // Each DC branch needs one converter at each end and nothing else
+    for (br, converters) in vsc_links(network)? {
+        let _ = writeln!(
+            out,
+            "'{}', {}, {}, 1, 1",
+            br.name,
+            status(br.status),
+            br.resistance
+        );
+        for c in converters {
+            let (kind, mode, acset) = vsc_codes(c);
+            let _ = writeln!(
+                out,
+                "{}, {}, {}, {}, {}, {}, {}, {}, {}, {}, 1, {}, {}, 0, 100",
+                c.ac_bus_id,
+                kind,
+                mode,
+                c.dc_setpoint,
+                acset,
+                c.loss_a,
+                c.loss_b,
+                c.loss_min,
+                c.s_max,
+                c.i_max,
+                c.q_max,
+                c.q_min
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "0 / END OF VSC DC LINE DATA, BEGIN IMPEDANCE CORRECTION DATA"
+    );
+
+    // I, T1, F1, T2, F2, ..., T11, F11
+    for table in &network.impedance_tables {
+        if table.points.iter().any(|&(_, _, im)| im != 0.0) {
+            return Err(format!(
+                "Impedance correction table {} has complex factors, which v33 cannot hold",
+                table.number
+            ));
+        }
+        if table.points.len() > 11 {
+            return Err(format!(
+                "Impedance correction table {} has {} points; v33 holds at most 11",
+                table.number,
+                table.points.len()
+            ));
+        }
+        let mut line = table.number.to_string();
+        for (t, f, _) in &table.points {
+            let _ = write!(line, ", {}, {}", t, f);
+        }
+        let _ = writeln!(out, "{}", line);
+    }
+    let _ = writeln!(
+        out,
+        "0 / END OF IMPEDANCE CORRECTION DATA, BEGIN MULTI-TERMINAL DC DATA"
+    );
+    let _ = writeln!(
+        out,
+        "0 / END OF MULTI-TERMINAL DC DATA, BEGIN MULTI-SECTION LINE DATA"
+    );
+
+    // I, J, 'ID', MET, DUM1, DUM2, ..., DUM9
+    for group in &network.line_groups {
+        let mut line = format!(
+            "{}, {}, '{}', {}",
+            group.from_bus,
+            group.to_bus,
+            group.id,
+            if group.metered_from { 1 } else { 2 }
+        );
+        for bus in &group.dummy_buses {
+            let _ = write!(line, ", {}", bus);
+        }
+        let _ = writeln!(out, "{}", line);
+    }
+    let _ = writeln!(out, "0 / END OF MULTI-SECTION LINE DATA, BEGIN ZONE DATA");
+
+    // I, 'ZONAME'
+    let zones: BTreeSet<usize> = network.buses.iter().map(|b| b.zone).collect();
+    for zone in &zones {
+        let _ = writeln!(out, "{}, '            '", zone);
+    }
+    let _ = writeln!(out, "0 / END OF ZONE DATA, BEGIN INTER-AREA TRANSFER DATA");
+    let _ = writeln!(out, "0 / END OF INTER-AREA TRANSFER DATA, BEGIN OWNER DATA");
+
+    // I, 'OWNAME'
+    let owners: BTreeSet<usize> = network.buses.iter().map(|b| b.owner).collect();
+    for owner in &owners {
+        let _ = writeln!(out, "{}, '            '", owner);
+    }
+    let _ = writeln!(out, "0 / END OF OWNER DATA, BEGIN FACTS DEVICE DATA");
+
+    // 'NAME', I, J, MODE, PDES, QDES, VSET, SHMX, TRMX, VTMN, VTMX, VSMX, IMX, LINX, RMPCT, OWNER, SET1, SET2, VSREF, REMOT, 'MNAME'
+    // RAW limits the shunt link symmetrically, so SHMX is the larger limit
+    for device in &network.facts_devices {
+        let (to_bus, mode) = facts_mode(device);
+        let link = device.series.as_ref();
+        let (_, _, owner) = area_zone(device.bus_id);
+        let _ = writeln!(
+            out,
+            "'{}', {}, {}, {}, {}, {}, {}, {}, 9999, 0.9, 1.1, 1, {}, {}, 100, {}, {}, {}, 0, 0, ''",
+            device.name,
+            device.bus_id,
+            to_bus,
+            mode,
+            link.map_or(0.0, |l| l.p_setpoint),
+            link.map_or(0.0, |l| l.q_setpoint),
+            device.v_setpoint,
+            device.q_max.max(-device.q_min),
+            link.map_or(0.0, |l| l.rating),
+            link.map_or(0.05, |l| l.reactance),
+            owner,
+            link.map_or(0.0, |l| l.setpoint_real),
+            link.map_or(0.0, |l| l.setpoint_imag)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "0 / END OF FACTS DEVICE DATA, BEGIN SWITCHED SHUNT DATA"
+    );
+    let _ = writeln!(out, "0 / END OF SWITCHED SHUNT DATA, BEGIN GNE DATA");
+    let _ = writeln!(out, "0 / END OF GNE DATA, BEGIN INDUCTION MACHINE DATA");
+    let _ = writeln!(out, "0 / END OF INDUCTION MACHINE DATA");
+    let _ = writeln!(out, "Q");
+
+    Ok(out)
+}
+
+/// Writes a Network to disk as a PSS/E v33 RAW case.
+pub fn write_case_v33(path: &str, network: &Network) -> Result<(), String> {
+    fs::write(path, format_raw_v33(network)?)
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
@@ -1,4 +1,23 @@
+pub mod acflow;
+pub mod capability;
 pub mod case;
+pub mod cdf;
+pub mod cgmes;
 pub mod cli;
+pub mod contingency;
+pub mod dcgrid;
+pub mod epc;
+pub mod export;
 pub mod loadflow;
+pub mod matpower;
+pub mod monitor;
+pub mod native;
+pub mod pandapower;
 pub mod parse;
+pub mod powerworld;
+pub mod pypsa;
+pub mod ras;
+pub mod rawx;
+pub mod sensitivity;
+pub mod subsystem;
+pub mod ucte;
@@ -1,16 +1,35 @@
 use crate::case::*;
 use rsparse::data::Trpl;
+use std::collections::{HashMap, HashSet};
+
+/// Solves `a x = b` in place with rsparse's LU, whose fill-reducing ordering
+/// needs two unknowns; a single one is divided out. Returns false if `a` is
+/// singular.
+pub(crate) fn solve_sparse(a: &Trpl<f64>, b: &mut [f64], tolerance: f64) -> bool {
+    if b.len() == 1 {
+        let pivot: f64 = a.x.iter().sum();
+        b[0] /= pivot;
+        return pivot != 0.0;
+    }
+    rsparse::lusol(&a.to_sprs(), b, 0, tolerance).is_ok()
+}
 
 impl Network {
-    /// Runs DC load flow and writes bus angles and branch flows directly into the network.
-    /// Returns true on success, false on failure.
-    pub fn dc_approximation(&mut self) -> bool {
-        self.rebuild_bus_map();
-        let n = self.bus_map.len();
+    /// (from, to, X) of in-service FACTS series links that B' carries; links
+    /// under flow control are injections instead.
+    fn facts_series_reactances(&self) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        self.facts_devices
+            .iter()
+            .filter(|d| d.status)
+            .filter_map(|d| {
+                let link = d.series.as_ref()?;
+                Some((d.bus_id, link.to_bus, link.dc_reactance()?))
+            })
+    }
 
-        if n == 0 {
-            return false;
-        }
+    /// Builds the DC susceptance matrix B' over the non-slack buses in `bus_map`.
+    pub(crate) fn b_prime(&self) -> Trpl<f64> {
+        let n = self.bus_map.len();
 
         // initialize the admittance matrix
         let mut b_prime = Trpl::<f64>::new();
@@ -18,41 +37,86 @@ impl Network {
         b_prime.n = n;
 
         // Collect OUT bus IDs for quick lookup
-        let out_buses: std::collections::HashSet<usize> = self
+        let out_buses: HashSet<usize> = self
             .buses
             .iter()
             .filter(|b| b.bus_type == BusType::OUT)
             .map(|b| b.bus_id)
             .collect();
 
-        for branch in &self.branches {
-            if !branch.branch_status || branch.reactance == 0.0 {
-                continue;
-            }
+        // Parallel branches are summed here rather than with Trpl::sum_dupl,
+        // which is quadratic in the number of entries.
+        let mut entries: HashMap<(usize, usize), f64> = HashMap::new();
+
+        // In-service branches at their corrected reactance and FACTS series
+        // links, as (from, to, X)
+        let series = self
+            .branches
+            .iter()
+            .zip(self.effective_reactances())
+            .filter(|(br, x)| br.branch_status && *x != 0.0)
+            .map(|(br, x)| (br.from_bus, br.to_bus, x))
+            .chain(self.facts_series_reactances());
 
+        for (from_bus, to_bus, reactance) in series {
             // Skip branches connected to out-of-service buses
-            if out_buses.contains(&branch.from_bus) || out_buses.contains(&branch.to_bus) {
+            if out_buses.contains(&from_bus) || out_buses.contains(&to_bus) {
                 continue;
             }
 
-            let bij = 1.0 / branch.reactance as f64;
+            let bij = 1.0 / reactance as f64;
 
-            let from = self.bus_map.get(&branch.from_bus);
-            let to = self.bus_map.get(&branch.to_bus);
+            let from = self.bus_map.get(&from_bus);
+            let to = self.bus_map.get(&to_bus);
 
             // B'_ii += 1/X, B'_jj += 1/X, B'_ij -= 1/X, B'_ji -= 1/X
             if let (Some(&i), Some(&j)) = (from, to) {
-                b_prime.append(i, i, bij);
-                b_prime.append(j, j, bij);
-                b_prime.append(i, j, -bij);
-                b_prime.append(j, i, -bij);
+                *entries.entry((i, i)).or_default() += bij;
+                *entries.entry((j, j)).or_default() += bij;
+                *entries.entry((i, j)).or_default() -= bij;
+                *entries.entry((j, i)).or_default() -= bij;
             } else if let Some(&i) = from {
-                b_prime.append(i, i, bij);
+                *entries.entry((i, i)).or_default() += bij;
             } else if let Some(&j) = to {
-                b_prime.append(j, j, bij);
+                *entries.entry((j, j)).or_default() += bij;
             }
         }
-        b_prime.sum_dupl();
+
+        let mut entries: Vec<((usize, usize), f64)> = entries.into_iter().collect();
+        entries.sort_by_key(|&(pos, _)| pos);
+        for ((i, j), value) in entries {
+            b_prime.append(i, j, value);
+        }
+
+        b_prime
+    }
+
+    /// Runs DC load flow and writes bus angles and branch flows directly into the network.
+    /// Returns true on success, false on failure.
+    pub fn dc_approximation(&mut self) -> bool {
+        self.rebuild_bus_map();
+        let n = self.bus_map.len();
+
+        if n == 0 {
+            return false;
+        }
+
+        // Sequential AC/DC: the DC grids are solved first and their converters
+        // enter the AC solve as fixed injections. The DC approximation leaves
+        // AC voltages alone, so there is nothing to iterate back.
+        if !self.solve_dc_grid() {
+            return false;
+        }
+
+        let b_prime = self.b_prime();
+
+        // Collect OUT bus IDs for quick lookup
+        let out_buses: HashSet<usize> = self
+            .buses
+            .iter()
+            .filter(|b| b.bus_type == BusType::OUT)
+            .map(|b| b.bus_id)
+            .collect();
 
         // Build P injection vector (in per unit)
         let mut p = vec![0.0f64; n];
@@ -65,15 +129,51 @@ impl Network {
             }
         }
 
-        for load in &self.loads {
+        // Voltage-dependent loads are taken at the stored bus voltage magnitude,
+        // which the DC solution leaves as it is
+        let voltage: HashMap<usize, f32> =
+            self.buses.iter().map(|b| (b.bus_id, b.voltage)).collect();
+        let load_mw = |load: &Load| {
+            load.power_at(voltage.get(&load.bus_id).copied().unwrap_or(1.0))
+                .0
+        };
+
+        for load in self.loads.iter().filter(|l| l.load_status) {
             if let Some(&idx) = self.bus_map.get(&load.bus_id) {
-                p[idx] -= load.real_load as f64 / self.s_base as f64;
+                p[idx] -= load_mw(load) as f64 / self.s_base as f64;
+            }
+        }
+
+        // HVDC links are fixed injections at their converter buses
+        let dc_injections = self.hvdc_injections();
+        for (bus_id, mw) in &dc_injections {
+            if let Some(&idx) = self.bus_map.get(bus_id) {
+                p[idx] += *mw as f64 / self.s_base as f64;
+            }
+        }
+
+        // FACTS series links under flow control move their setpoint, and a
+        // series voltage moves what its angle drives through the link
+        for device in self.facts_devices.iter().filter(|d| d.status) {
+            let Some(link) = &device.series else {
+                continue;
+            };
+            if !out_buses.contains(&device.bus_id) && !out_buses.contains(&link.to_bus) {
+                let mw = match link.dc_reactance() {
+                    Some(x) => link.dc_shift() as f64 / x as f64,
+                    None => link.scheduled_flow() as f64 / self.s_base as f64,
+                };
+                if let Some(&idx) = self.bus_map.get(&device.bus_id) {
+                    p[idx] -= mw;
+                }
+                if let Some(&idx) = self.bus_map.get(&link.to_bus) {
+                    p[idx] += mw;
+                }
             }
         }
 
         // Solve B' * theta = P
-        let csc = b_prime.to_sprs();
-        if rsparse::lusol(&csc, &mut p, 0, 1e-6).is_err() {
+        if !solve_sparse(&b_prime, &mut p, 1e-6) {
             return false;
         }
         // p now contains theta (radians) for each non-slack bus
@@ -87,13 +187,18 @@ impl Network {
                 bus.angle = 0.0;
             } else if let Some(&idx) = self.bus_map.get(&bus.bus_id) {
                 bus.angle = p[idx].to_degrees() as f32;
+            } else {
+                // An isolated dummy bus
+                bus.voltage = 0.0;
+                bus.angle = 0.0;
             }
         }
 
         // Compute and write branch flows (MW) directly into branch structs
-        for branch in &mut self.branches {
+        let reactances = self.effective_reactances();
+        for (branch, reactance) in self.branches.iter_mut().zip(reactances) {
             if !branch.branch_status
-                || branch.reactance == 0.0
+                || reactance == 0.0
                 || out_buses.contains(&branch.from_bus)
                 || out_buses.contains(&branch.to_bus)
             {
@@ -113,8 +218,30 @@ impl Network {
                 .map(|&idx| p[idx])
                 .unwrap_or(0.0);
 
-            branch.flow =
-                ((theta_i - theta_j) / branch.reactance as f64 * self.s_base as f64) as f32;
+            branch.flow = ((theta_i - theta_j) / reactance as f64 * self.s_base as f64) as f32;
+        }
+
+        let angle = |bus_id: usize| self.bus_map.get(&bus_id).map(|&idx| p[idx]).unwrap_or(0.0);
+        let s_base = self.s_base as f64;
+        for device in &mut self.facts_devices {
+            let Some(link) = &mut device.series else {
+                continue;
+            };
+            link.flow = if !device.status
+                || out_buses.contains(&device.bus_id)
+                || out_buses.contains(&link.to_bus)
+            {
+                0.0
+            } else {
+                match link.dc_reactance() {
+                    Some(x) => {
+                        let angle =
+                            angle(device.bus_id) - angle(link.to_bus) + link.dc_shift() as f64;
+                        (angle / x as f64 * s_base) as f32
+                    }
+                    None => link.scheduled_flow(),
+                }
+            };
         }
 
         // Back-calculate slack bus generator output from branch flows
@@ -130,8 +257,8 @@ impl Network {
             let p_load: f32 = self
                 .loads
                 .iter()
-                .filter(|l| l.bus_id == slack_id)
-                .map(|l| l.real_load)
+                .filter(|l| l.bus_id == slack_id && l.load_status)
+                .map(load_mw)
                 .sum();
 
             let p_flow_out: f32 = self
@@ -147,10 +274,13 @@ impl Network {
                         0.0
                     }
                 })
-                .sum();
+                .sum::<f32>()
+                + self.facts_flow_out(slack_id);
+
+            let p_dc = dc_injections.get(&slack_id).copied().unwrap_or(0.0);
 
             // Total required generation at this bus
-            let p_required = p_load + p_flow_out;
+            let p_required = p_load + p_flow_out - p_dc;
 
             // Sum of other (non-first) generators on this bus
             let slack_gens: Vec<usize> = self
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::case::*;
+use crate::export::{bus_code, status};
+
+/// Removes `%` comments, leaving quoted text alone.
+fn strip_comments(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let mut quoted = false;
+            for (i, c) in line.char_indices() {
+                match c {
+                    '\'' => quoted = !quoted,
+                    '%' if !quoted => return &line[..i],
+                    _ => {}
+                }
+            }
+            line
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Right-hand side of `mpc.<name> = ...;`, or None when the field is absent.
+fn assignment<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("mpc.{}", name);
+    let mut rest = content;
+    while let Some(pos) = rest.find(&key) {
+        let after = rest[pos + key.len()..].trim_start();
+        if let Some(rhs) = after.strip_prefix('=') {
+            let rhs = rhs.trim_start();
+            // Matrices and cell arrays run to their closing bracket
+            let end = match rhs.chars().next() {
+                Some('[') => rhs.find(']')? + 1,
+                Some('{') => rhs.find('}')? + 1,
+                _ => rhs.find(';').unwrap_or(rhs.len()),
+            };
+            return Some(&rhs[..end]);
+        }
+        rest = &rest[pos + key.len()..];
+    }
+    None
+}
+
+/// Parses a numeric matrix; rows end at `;` or a line break.
+fn matrix(content: &str, name: &str) -> Result<Vec<Vec<f32>>, String> {
+    let Some(rhs) = assignment(content, name) else {
+        return Ok(Vec::new());
+    };
+    let inner = rhs.trim_start_matches('[').trim_end_matches(']');
+    inner
+        .split([';', '\n'])
+        .map(|row| {
+            row.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|v| !v.is_empty())
+                .map(|v| {
+                    v.parse::<f32>()
+                        .map_err(|_| format!("Bad value '{}' in mpc.{}", v, name))
+                })
+                .collect::<Result<Vec<f32>, String>>()
+        })
+        .filter(|row| row.as_ref().map_or(true, |r| !r.is_empty()))
+        .collect()
+}
+
+/// Quoted strings of a cell array such as `mpc.bus_name = { 'A'; 'B' };`.
+fn cell_strings(content: &str, name: &str) -> Vec<String> {
+    let Some(rhs) = assignment(content, name) else {
+        return Vec::new();
+    };
+    rhs.split('\'')
+        .skip(1)
+        .step_by(2)
+        .map(|s| s.trim().to_string())
+        .collect()
+}
+
+/// Column `i` of a matrix row, or `default` when the row is short.
+fn col(row: &[f32], i: usize, default: f32) -> f32 {
+    row.get(i).copied().unwrap_or(default)
+}
+
+/// Reads a MATPOWER case file into a Network.
+pub fn read_matpower(path: &str) -> Result<Network, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_matpower_str(&content)
+}
+
+/// Parses a MATPOWER case (version 2) into a Network. Bus demand becomes one
+/// load per bus and GS/BS the bus shunt. Branches with a tap ratio or phase
+/// shift are read as transformers.
+pub fn parse_matpower_str(content: &str) -> Result<Network, String> {
+    let content = strip_comments(content);
+
+    // function mpc = case_name
+    let case_name = content
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("function"))
+        .and_then(|l| l.split('=').nth(1))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let s_base: f32 = assignment(&content, "baseMVA")
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(100.0);
+
+    let mut network = Network::new(case_name, s_base, 60.0);
+    let bus_names = cell_strings(&content, "bus_name");
+
+    // bus_i, type, Pd, Qd, Gs, Bs, area, Vm, Va, baseKV, zone, Vmax, Vmin
+    for (i, row) in matrix(&content, "bus")?.iter().enumerate() {
+        if row.len() < 13 {
+            return Err(format!(
+                "mpc.bus row {} has {} columns, expected 13",
+                i + 1,
+                row.len()
+            ));
+        }
+        let bus_id = row[0] as usize;
+        let bus_type = match row[1] as u8 {
+            3 => BusType::Slack,
+            2 => BusType::PV,
+            1 => BusType::PQ,
+            4 => BusType::OUT,
+            code => return Err(format!("Unknown bus type code: {}", code)),
+        };
+        let mut bus = Bus::new(
+            bus_id,
+            bus_names
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| bus_id.to_string()),
+            bus_type,
+        );
+        bus.real_shunt = row[4];
+        bus.imag_shunt = row[5];
+        bus.area = row[6] as usize;
+        bus.voltage = row[7];
+        bus.angle = row[8];
+        bus.nom_voltage = row[9];
+        bus.zone = row[10] as usize;
+        bus.v_max_operating = row[11];
+        bus.v_max_contingency = row[11];
+        bus.v_min_operating = row[12];
+        bus.v_min_contingency = row[12];
+        network.buses.push(bus);
+
+        if row[2] != 0.0 || row[3] != 0.0 {
+            network.loads.push(Load::new(
+                network.loads.len(),
+                bus_id,
+                format!("Bus{}-1", bus_id),
+                row[2],
+                row[3],
+            ));
+        }
+    }
+
+    // bus, Pg, Qg, Qmax, Qmin, Vg, mBase, status, Pmax, Pmin, ...
+    let mut machines: HashMap<usize, usize> = HashMap::new();
+    for (i, row) in matrix(&content, "gen")?.iter().enumerate() {
+        if row.len() < 10 {
+            return Err(format!(
+                "mpc.gen row {} has {} columns, expected 10",
+                i + 1,
+                row.len()
+            ));
+        }
+        let bus_id = row[0] as usize;
+        let machine = machines.entry(bus_id).or_insert(0);
+        *machine += 1;
+        let mut generator = Generator::new(
+            network.generators.len(),
+            bus_id,
+            format!("Bus{}-{}", bus_id, machine),
+        );
+        generator.p_gen = row[1];
+        generator.q_gen = row[2];
+        generator.q_max = row[3];
+        generator.q_min = row[4];
+        generator.v_setpoint = row[5];
+        generator.gen_status = row[7] > 0.0;
+        generator.p_max = row[8];
+        generator.p_min = row[9];
+        network.generators.push(generator);
+    }
+
+    // model, startup, shutdown, n, cost data; rows past the generator count are Q costs
+    for (i, row) in matrix(&content, "gencost")?.iter().enumerate() {
+        let Some(generator) = network.generators.get_mut(i) else {
+            break;
+        };
+        let n = col(row, 3, 0.0) as usize;
+        let data = row.get(4..).unwrap_or(&[]);
+        let curve = match col(row, 0, 2.0) as u8 {
+            1 => CostCurve::PiecewiseLinear(
+                data.chunks(2)
+                    .take(n)
+                    .map(|p| (p[0], col(p, 1, 0.0)))
+                    .collect(),
+            ),
+            2 => CostCurve::Polynomial(data.iter().take(n).copied().collect()),
+            model => return Err(format!("Unknown gencost model: {}", model)),
+        };
+        generator.cost = Some(GenCost {
+            startup: col(row, 1, 0.0),
+            shutdown: col(row, 2, 0.0),
+            curve,
+        });
+    }
+
+    // fbus, tbus, r, x, b, rateA, rateB, rateC, ratio, angle, status, angmin, angmax
+    for (i, row) in matrix(&content, "branch")?.iter().enumerate() {
+        if row.len() < 11 {
+            return Err(format!(
+                "mpc.branch row {} has {} columns, expected 11",
+                i + 1,
+                row.len()
+            ));
+        }
+        let is_transformer = row[8] != 0.0 || row[9] != 0.0;
+        network.branches.push(Branch {
+            branch_type: if is_transformer {
+                BranchType::TwoWinding
+            } else {
+                BranchType::Line
+            },
+            id: network.branches.len(),
+            from_bus: row[0] as usize,
+            to_bus: row[1] as usize,
+            ckt: String::new(),
+            branch_name: String::from("            "),
+            branch_status: row[10] > 0.0,
+            resistance: row[2],
+            reactance: row[3],
+            from_shunt_conductance: 0.0,
+            from_shunt_susceptance: row[4] / 2.0,
+            to_shunt_conductance: 0.0,
+            to_shunt_susceptance: row[4] / 2.0,
+            // A zero ratio means a nominal tap
+            tap_ratio: if row[8] == 0.0 { 1.0 } else { row[8] },
+            phase_shift: row[9],
+            impedance_table: None,
+            operating_limit: row[5],
+            contingency_limit: row[6],
+            flow: 0.0,
+        });
+    }
+
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// Formats a Network as a MATPOWER case function named `function_name`.
+/// Loads are summed per bus, line shunt conductance and transformer
+/// magnetizing admittance have no MATPOWER field and are dropped, and
+/// gencost is only written when every generator has a cost.
+pub fn format_matpower(network: &Network, function_name: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "function mpc = {}", function_name);
+    let _ = writeln!(out, "%{}", network.case_name);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "%% MATPOWER Case Format : Version 2");
+    let _ = writeln!(out, "mpc.version = '2';");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "%%-----  Power Flow Data  -----%%");
+    let _ = writeln!(out, "%% system MVA base");
+    let _ = writeln!(out, "mpc.baseMVA = {};", network.s_base);
+    let _ = writeln!(out);
+
+    let mut demand: HashMap<usize, (f32, f32)> = HashMap::new();
+    // MATPOWER demand is constant power, so ZIP loads are taken at 1 pu
+    for l in network.loads.iter().filter(|l| l.load_status) {
+        let (p, q) = l.power_at(1.0);
+        let entry = demand.entry(l.bus_id).or_insert((0.0, 0.0));
+        entry.0 += p;
+        entry.1 += q;
+    }
+
+    let _ = writeln!(out, "%% bus data");
+    let _ = writeln!(
+        out,
+        "%\tbus_i\ttype\tPd\tQd\tGs\tBs\tarea\tVm\tVa\tbaseKV\tzone\tVmax\tVmin"
+    );
+    let _ = writeln!(out, "mpc.bus = [");
+    for b in &network.buses {
+        let (pd, qd) = demand.get(&b.bus_id).copied().unwrap_or((0.0, 0.0));
+        let _ = writeln!(
+            out,
+            "\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{};",
+            b.bus_id,
+            bus_code(b.bus_type),
+            pd,
+            qd,
+            b.real_shunt,
+            b.imag_shunt,
+            b.area,
+            b.voltage,
+            b.angle,
+            b.nom_voltage,
+            b.zone,
+            b.v_max_operating,
+            b.v_min_operating
+        );
+    }
+    let _ = writeln!(out, "];");
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "%% generator data");
+    let _ = writeln!(
+        out,
+        "%\tbus\tPg\tQg\tQmax\tQmin\tVg\tmBase\tstatus\tPmax\tPmin\tPc1\tPc2\tQc1min\tQc1max\tQc2min\tQc2max\tramp_agc\tramp_10\tramp_30\tramp_q\tapf"
+    );
+    let _ = writeln!(out, "mpc.gen = [");
+    for g in &network.generators {
+        let _ = writeln!(
+            out,
+            "\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t0\t0\t0\t0\t0\t0\t0\t0\t0\t0\t0;",
+            g.gen_bus_id,
+            g.p_gen,
+            g.q_gen,
+            g.q_max,
+            g.q_min,
+            g.v_setpoint,
+            network.s_base,
+            status(g.gen_status),
+            g.p_max,
+            g.p_min
+        );
+    }
+    let _ = writeln!(out, "];");
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "%% branch data");
+    let _ = writeln!(
+        out,
+        "%\tfbus\ttbus\tr\tx\tb\trateA\trateB\trateC\tratio\tangle\tstatus\tangmin\tangmax"
+    );
+    let _ = writeln!(out, "mpc.branch = [");
+    for br in &network.branches {
+        let ratio = match br.branch_type {
+            BranchType::Line => 0.0,
+            BranchType::TwoWinding => br.tap_ratio,
+        };
+        let _ = writeln!(
+            out,
+            "\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t0\t{}\t{}\t{}\t-360\t360;",
+            br.from_bus,
+            br.to_bus,
+            br.resistance,
+            br.reactance,
+            br.from_shunt_susceptance + br.to_shunt_susceptance,
+            br.operating_limit,
+            br.contingency_limit,
+            ratio,
+            br.phase_shift,
+            status(br.branch_status)
+        );
+    }
+    let _ = writeln!(out, "];");
+
+    let costs: Option<Vec<&GenCost>> = network.generators.iter().map(|g| g.cost.as_ref()).collect();
+    if let Some(costs) = costs.filter(|c| !c.is_empty()) {
+        let rows: Vec<Vec<f32>> = costs
+            .iter()
+            .map(|c| {
+                let (model, n, data) = match &c.curve {
+                    CostCurve::PiecewiseLinear(points) => (
+                        1.0,
+                        points.len(),
+                        points.iter().flat_map(|&(x, y)| [x, y]).collect(),
+                    ),
+                    CostCurve::Polynomial(coefficients) => {
+                        (2.0, coefficients.len(), coefficients.clone())
+                    }
+                };
+                let mut row = vec![model, c.startup, c.shutdown, n as f32];
+                row.extend(data);
+                row
+            })
+            .collect();
+        // Matrix rows must be the same length
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "%%-----  OPF Data  -----%%");
+        let _ = writeln!(out, "%% generator cost data");
+        let _ = writeln!(out, "%\t1\tstartup\tshutdown\tn\tx1\ty1\t...\txn\tyn");
+        let _ = writeln!(out, "%\t2\tstartup\tshutdown\tn\tc(n-1)\t...\tc0");
+        let _ = writeln!(out, "mpc.gencost = [");
+        for row in rows {
+            let cells: Vec<String> = (0..width).map(|i| col(&row, i, 0.0).to_string()).collect();
+            let _ = writeln!(out, "\t{};", cells.join("\t"));
+        }
+        let _ = writeln!(out, "];");
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "%% bus names");
+    let _ = writeln!(out, "mpc.bus_name = {{");
+    for b in &network.buses {
+        let _ = writeln!(out, "\t'{}';", b.bus_name.replace('\'', ""));
+    }
+    let _ = writeln!(out, "}};");
+
+    out
+}
+
+/// Writes a Network to disk as a MATPOWER case; the function is named after
+/// the file as MATPOWER requires.
+pub fn write_matpower(path: &str, network: &Network) -> Result<(), String> {
+    let function_name = Path::new(path)
+        .file_stem()
+        .map(|s| {
+            s.to_string_lossy()
+                .replace(|c: char| !c.is_alphanumeric(), "_")
+        })
+        .unwrap_or_else(|| String::from("mpc_case"));
+    fs::write(path, format_matpower(network, &function_name))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::case::*;
+use crate::subsystem::{MonitorSet, Subsystem};
+
+/// Which set of limits a scan checks against.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RatingSet {
+    /// Normal voltage range and RateA
+    Operating,
+    /// Emergency voltage range and RateB
+    Contingency,
+}
+
+/// Kind of limit that was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LimitType {
+    LowVoltage,
+    HighVoltage,
+    BranchFlow,
+}
+
+/// LimitType enum display implementation.
+impl fmt::Display for LimitType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitType::LowVoltage => write!(f, "VLOW"),
+            LimitType::HighVoltage => write!(f, "VHIGH"),
+            LimitType::BranchFlow => write!(f, "FLOW"),
+        }
+    }
+}
+
+/// Network element a violation was found on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MonitoredElement {
+    Bus(usize),
+    Branch {
+        id: usize,
+        from_bus: usize,
+        to_bus: usize,
+    },
+    /// A multi-section line, reported at its most heavily loaded section
+    LineGroup {
+        from_bus: usize,
+        to_bus: usize,
+        id: String,
+    },
+}
+
+/// MonitoredElement enum display implementation.
+impl fmt::Display for MonitoredElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            MonitoredElement::Bus(bus_id) => format!("Bus {}", bus_id),
+            MonitoredElement::Branch {
+                id,
+                from_bus,
+                to_bus,
+            } => format!("Branch {} ({}-{})", id, from_bus, to_bus),
+            MonitoredElement::LineGroup {
+                from_bus,
+                to_bus,
+                id,
+            } => format!("Line {}-{} {}", from_bus, to_bus, id),
+        };
+        f.pad(&text)
+    }
+}
+
+/// A single limit violation found in a solved network.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    pub element: MonitoredElement,
+    pub limit_type: LimitType,
+    /// Voltage in per unit or |flow| in MW
+    pub value: f32,
+    /// The limit in the same units as `value`
+    pub limit: f32,
+    /// `value` as a percent of `limit`
+    pub percent: f32,
+}
+
+/// Violation struct display implementation.
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<24} {:<5} value={:>10.4}  limit={:>10.4}  {:>7.1}%",
+            self.element, self.limit_type, self.value, self.limit, self.percent
+        )
+    }
+}
+
+/// A branch of an interface, metered from `from_bus` towards `to_bus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceBranch {
+    pub from_bus: usize,
+    pub to_bus: usize,
+    pub ckt: String,
+}
+
+/// A named group of branches whose summed flow is limited, such as a tie
+/// between areas or a flowgate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interface {
+    pub name: String,
+    pub branches: Vec<InterfaceBranch>,
+    pub operating_limit: f32,
+    pub contingency_limit: f32,
+}
+
+impl Interface {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            branches: Vec::new(),
+            operating_limit: 0.0,
+            contingency_limit: 0.0,
+        }
+    }
+}
+
+/// Restricts which elements a violation scan reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViolationFilter {
+    /// Only elements at or above this nominal kV
+    pub min_kv: Option<f32>,
+    /// Only elements at or below this nominal kV
+    pub max_kv: Option<f32>,
+    /// Only elements in these areas; empty means all areas
+    pub areas: Vec<usize>,
+    /// Branches are reported at or above this percent of their rating
+    pub threshold: f32,
+    /// Only buses in this subsystem
+    pub subsystem: Option<Subsystem>,
+    /// Only these monitored elements, with their voltage ranges
+    pub monitored: Option<MonitorSet>,
+}
+
+impl Default for ViolationFilter {
+    fn default() -> Self {
+        Self {
+            min_kv: None,
+            max_kv: None,
+            areas: Vec::new(),
+            threshold: 100.0,
+            subsystem: None,
+            monitored: None,
+        }
+    }
+}
+
+impl ViolationFilter {
+    /// Whether a bus passes the kV, area and subsystem criteria.
+    pub fn includes_bus(&self, bus: &Bus) -> bool {
+        self.min_kv.is_none_or(|min| bus.nom_voltage >= min)
+            && self.max_kv.is_none_or(|max| bus.nom_voltage <= max)
+            && (self.areas.is_empty() || self.areas.contains(&bus.area))
+            && self.subsystem.as_ref().is_none_or(|s| s.contains(bus))
+    }
+
+    /// Whether `network.branches[index]` is reported: it must be monitored and
+    /// have either terminal bus pass the bus criteria. `buses` is the map from
+    /// `Network::bus_indices`.
+    pub fn includes_branch(
+        &self,
+        network: &Network,
+        buses: &HashMap<usize, usize>,
+        index: usize,
+    ) -> bool {
+        let br = &network.branches[index];
+        self.monitored
+            .as_ref()
+            .is_none_or(|m| m.branches.contains(&index))
+            && [br.from_bus, br.to_bus]
+                .iter()
+                .filter_map(|bus_id| buses.get(bus_id))
+                .any(|&i| self.includes_bus(&network.buses[i]))
+    }
+}
+
+impl Network {
+    /// Summed MW flow of an interface's in-service branches in their metered
+    /// direction. A branch selected twice is counted once, and a multi-section
+    /// line counts the section at its metered end.
+    pub fn interface_flow(&self, interface: &Interface) -> f32 {
+        let mut counted = HashSet::new();
+        let mut flow = 0.0;
+        for element in &interface.branches {
+            let selected =
+                match self.find_line_group(element.from_bus, element.to_bus, &element.ckt) {
+                    Some(g) => {
+                        let group = &self.line_groups[g];
+                        self.group_sections(group)
+                            .and_then(|sections| match group.metered_from {
+                                true => sections.first().copied(),
+                                false => sections.last().copied(),
+                            })
+                            .into_iter()
+                            .collect()
+                    }
+                    None => self.find_branches(element.from_bus, element.to_bus, &element.ckt),
+                };
+            for i in selected {
+                let br = &self.branches[i];
+                if !br.branch_status || !counted.insert(i) {
+                    continue;
+                }
+                // A section shares one end with the element
+                flow += if br.from_bus == element.from_bus || br.to_bus == element.to_bus {
+                    br.flow
+                } else {
+                    -br.flow
+                };
+            }
+        }
+        flow
+    }
+
+    /// Scans a solved network for bus voltages outside their range and branch
+    /// flows above the chosen percent of their rating. With a monitor set only
+    /// its branches and buses are checked, against its voltage ranges. The
+    /// sections of a multi-section line are reported as one element.
+    pub fn check_violations(&self, ratings: RatingSet, filter: &ViolationFilter) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        // Dummy buses of an opened multi-section line are dead, not low
+        let isolated = self.isolated_dummy_buses();
+        for bus in &self.buses {
+            if !bus.bus_status
+                || bus.bus_type == BusType::OUT
+                || isolated.contains(&bus.bus_id)
+                || !filter.includes_bus(bus)
+            {
+                continue;
+            }
+            let (v_min, v_max) = match (&filter.monitored, ratings) {
+                (Some(monitored), _) => match monitored.buses.get(&bus.bus_id) {
+                    Some(&range) => range,
+                    None => continue,
+                },
+                (None, RatingSet::Operating) => (bus.v_min_operating, bus.v_max_operating),
+                (None, RatingSet::Contingency) => (bus.v_min_contingency, bus.v_max_contingency),
+            };
+            let (limit_type, limit) = if bus.voltage < v_min {
+                (LimitType::LowVoltage, v_min)
+            } else if bus.voltage > v_max {
+                (LimitType::HighVoltage, v_max)
+            } else {
+                continue;
+            };
+            violations.push(Violation {
+                element: MonitoredElement::Bus(bus.bus_id),
+                limit_type,
+                value: bus.voltage,
+                limit,
+                percent: bus.voltage / limit * 100.0,
+            });
+        }
+
+        // Section index -> line group, and the worst section of each group
+        let mut section_of: HashMap<usize, usize> = HashMap::new();
+        for (g, group) in self.line_groups.iter().enumerate() {
+            for i in self.group_sections(group).unwrap_or_default() {
+                section_of.insert(i, g);
+            }
+        }
+        let mut worst: BTreeMap<usize, Violation> = BTreeMap::new();
+        let buses = self.bus_indices();
+
+        for (index, br) in self.branches.iter().enumerate() {
+            let limit = match ratings {
+                RatingSet::Operating => br.operating_limit,
+                RatingSet::Contingency => br.contingency_limit,
+            };
+            // A zero rating means the branch is unmonitored
+            if !br.branch_status || limit <= 0.0 || !filter.includes_branch(self, &buses, index) {
+                continue;
+            }
+            let percent = br.flow.abs() / limit * 100.0;
+            if percent < filter.threshold {
+                continue;
+            }
+            let violation = Violation {
+                element: MonitoredElement::Branch {
+                    id: br.id,
+                    from_bus: br.from_bus,
+                    to_bus: br.to_bus,
+                },
+                limit_type: LimitType::BranchFlow,
+                value: br.flow.abs(),
+                limit,
+                percent,
+            };
+            match section_of.get(&index) {
+                Some(&g) => {
+                    if worst.get(&g).is_none_or(|v| percent > v.percent) {
+                        worst.insert(g, violation);
+                    }
+                }
+                None => violations.push(violation),
+            }
+        }
+
+        for (g, mut violation) in worst {
+            let group = &self.line_groups[g];
+            violation.element = MonitoredElement::LineGroup {
+                from_bus: group.from_bus,
+                to_bus: group.to_bus,
+                id: group.id.clone(),
+            };
+            violations.push(violation);
+        }
+
+        violations
+    }
+}
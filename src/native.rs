@@ -0,0 +1,2553 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::case::{self, raw_id};
+// The schema 0 layout below migrates to the schema 1 types by their bare names.
+use v1::{Branch, BranchType, Bus, BusType, Generator, Load, Network};
+
+/// First bytes of a native binary case.
+pub const MAGIC: [u8; 8] = *b"MANTISNC";
+
+/// Layout version written by this build. Bump it whenever a field is added to
+/// the Network model, and freeze the types it changes in a module of the old
+/// schema that migrates them to the new layout.
+pub const SCHEMA_VERSION: u32 = 12;
+
+/// `format` tag of a native JSON case.
+const FORMAT: &str = "mantis";
+
+/// Descriptive header stored alongside the network.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Program and version that wrote the file
+    pub writer: String,
+    /// Seconds since the Unix epoch when the file was written
+    pub created: u64,
+    pub description: String,
+}
+
+impl Metadata {
+    /// Metadata stamped with this build and the current time.
+    pub fn now(description: &str) -> Self {
+        Self {
+            writer: format!("mantis {}", env!("CARGO_PKG_VERSION")),
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            description: description.to_string(),
+        }
+    }
+}
+
+/// A native case as read from disk, migrated to the current layout.
+#[derive(Debug, Clone)]
+pub struct NativeCase {
+    pub network: case::Network,
+    pub metadata: Metadata,
+    /// Layout version found in the file; 0 for unversioned saves
+    pub schema_version: u32,
+}
+
+#[derive(Serialize)]
+struct CaseFileRef<'a> {
+    format: &'static str,
+    schema_version: u32,
+    metadata: &'a Metadata,
+    checksum: Option<u32>,
+    network: &'a case::Network,
+}
+
+/// A native JSON case with the network in the layout of its schema version.
+#[derive(Deserialize)]
+struct CaseFile<N> {
+    #[serde(default)]
+    metadata: Metadata,
+    #[serde(default)]
+    checksum: Option<u32>,
+    network: N,
+}
+
+/// The Network of one schema version, which migrates through every later
+/// schema to the current one.
+trait Layout: Serialize + DeserializeOwned {
+    fn upgrade(self) -> case::Network;
+}
+
+impl Layout for case::Network {
+    fn upgrade(self) -> case::Network {
+        self
+    }
+}
+
+/// Just enough of a JSON document to tell a native container from a bare network.
+#[derive(Deserialize)]
+struct JsonHeader {
+    format: Option<String>,
+    schema_version: Option<u32>,
+}
+
+/// CRC-32 (IEEE 802.3) of `bytes`.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn verify(expected: Option<u32>, actual: u32) -> Result<(), String> {
+    match expected {
+        Some(sum) if sum != actual => Err(format!(
+            "Checksum mismatch: file says {:08x}, contents give {:08x}",
+            sum, actual
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks a JSON checksum against the compact JSON of `network`, serialized in
+/// the layout it was written with.
+fn verify_json<N: Serialize>(expected: Option<u32>, network: &N) -> Result<(), String> {
+    if expected.is_none() {
+        return Ok(());
+    }
+    let compact = serde_json::to_vec(network).map_err(|e| format!("Error serializing: {}", e))?;
+    verify(expected, crc32(&compact))
+}
+
+fn newer_version(version: u32) -> String {
+    format!(
+        "Case schema version {} is newer than this build supports ({})",
+        version, SCHEMA_VERSION
+    )
+}
+
+/// Encodes a Network as a native binary case: `MAGIC`, the schema version as a
+/// little-endian u32, then the bincode of (metadata, checksum, network). The
+/// checksum covers the network bytes.
+pub fn to_bytes(
+    network: &case::Network,
+    metadata: &Metadata,
+    checksum: bool,
+) -> Result<Vec<u8>, String> {
+    let body = bincode::serialize(network).map_err(|e| format!("Error serializing: {}", e))?;
+    let sum = checksum.then(|| crc32(&body));
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&SCHEMA_VERSION.to_le_bytes());
+    bytes.extend(
+        bincode::serialize(&(metadata, sum)).map_err(|e| format!("Error serializing: {}", e))?,
+    );
+    bytes.extend(body);
+    Ok(bytes)
+}
+
+/// Decodes a bincode network in layout `N` and migrates it.
+fn bincode_network<N: Layout>(bytes: &[u8]) -> Result<case::Network, String> {
+    bincode::deserialize::<N>(bytes)
+        .map(N::upgrade)
+        .map_err(|e| format!("Error parsing bincode: {}", e))
+}
+
+/// Decodes a native binary case. Bytes without the magic header are taken to
+/// be an unversioned (schema 0) bincode Network and migrated.
+pub fn from_bytes(bytes: &[u8]) -> Result<NativeCase, String> {
+    let Some(rest) = bytes.strip_prefix(&MAGIC[..]) else {
+        let legacy: NetworkV0 =
+            bincode::deserialize(bytes).map_err(|e| format!("Error parsing bincode: {}", e))?;
+        let mut network = legacy.migrate().upgrade();
+        fill_ids(&mut network);
+        return Ok(NativeCase {
+            network,
+            metadata: Metadata::default(),
+            schema_version: 0,
+        });
+    };
+    if rest.len() < 4 {
+        return Err("Truncated case header".to_string());
+    }
+    let version = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]);
+    if version > SCHEMA_VERSION {
+        return Err(newer_version(version));
+    }
+    let mut reader = &rest[4..];
+    let (metadata, sum): (Metadata, Option<u32>) = bincode::deserialize_from(&mut reader)
+        .map_err(|e| format!("Error parsing case header: {}", e))?;
+    verify(sum, crc32(reader))?;
+    let mut network = match version {
+        0 | 1 => bincode_network::<v1::Network>(reader)?,
+        2 => bincode_network::<v2::Network>(reader)?,
+        3 => bincode_network::<v3::Network>(reader)?,
+        4 => bincode_network::<v4::Network>(reader)?,
+        5 => bincode_network::<v5::Network>(reader)?,
+        6 => bincode_network::<v6::Network>(reader)?,
+        7 => bincode_network::<v7::Network>(reader)?,
+        8 => bincode_network::<v8::Network>(reader)?,
+        9 => bincode_network::<v9::Network>(reader)?,
+        10 => bincode_network::<v10::Network>(reader)?,
+        11 => bincode_network::<v11::Network>(reader)?,
+        _ => bincode_network::<case::Network>(reader)?,
+    };
+    if version < 2 {
+        fill_ids(&mut network);
+    }
+    Ok(NativeCase {
+        network,
+        metadata,
+        schema_version: version,
+    })
+}
+
+/// Formats a Network as a native JSON case. The checksum covers the compact
+/// JSON of the `network` member.
+pub fn to_json(
+    network: &case::Network,
+    metadata: &Metadata,
+    checksum: bool,
+) -> Result<String, String> {
+    let sum = if checksum {
+        let compact =
+            serde_json::to_vec(network).map_err(|e| format!("Error serializing: {}", e))?;
+        Some(crc32(&compact))
+    } else {
+        None
+    };
+    let file = CaseFileRef {
+        format: FORMAT,
+        schema_version: SCHEMA_VERSION,
+        metadata,
+        checksum: sum,
+        network,
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| format!("Error serializing: {}", e))
+}
+
+/// Parses a native JSON case whose network is in layout `N`, checks its
+/// checksum against that layout and migrates it.
+fn json_network<N: Layout>(content: &str) -> Result<(case::Network, Metadata), String> {
+    let file: CaseFile<N> =
+        serde_json::from_str(content).map_err(|e| format!("Error parsing JSON: {}", e))?;
+    verify_json(file.checksum, &file.network)?;
+    Ok((file.network.upgrade(), file.metadata))
+}
+
+/// Parses a native JSON case. A bare Network object from before the container
+/// existed is taken as schema 0 and migrated, and missing identifiers are
+/// filled in as for binary cases.
+pub fn from_json(content: &str) -> Result<NativeCase, String> {
+    let header: JsonHeader =
+        serde_json::from_str(content).map_err(|e| format!("Error parsing JSON: {}", e))?;
+    if header.format.as_deref() != Some(FORMAT) {
+        let legacy: NetworkV0 =
+            serde_json::from_str(content).map_err(|e| format!("Error parsing JSON: {}", e))?;
+        let mut network = legacy.migrate().upgrade();
+        fill_ids(&mut network);
+        return Ok(NativeCase {
+            network,
+            metadata: Metadata::default(),
+            schema_version: 0,
+        });
+    }
+    let version = header.schema_version.unwrap_or(0);
+    if version > SCHEMA_VERSION {
+        return Err(newer_version(version));
+    }
+    let (mut network, metadata) = match version {
+        0 | 1 => json_network::<v1::Network>(content)?,
+        2 => json_network::<v2::Network>(content)?,
+        3 => json_network::<v3::Network>(content)?,
+        4 => json_network::<v4::Network>(content)?,
+        5 => json_network::<v5::Network>(content)?,
+        6 => json_network::<v6::Network>(content)?,
+        7 => json_network::<v7::Network>(content)?,
+        8 => json_network::<v8::Network>(content)?,
+        9 => json_network::<v9::Network>(content)?,
+        10 => json_network::<v10::Network>(content)?,
+        11 => json_network::<v11::Network>(content)?,
+        _ => json_network::<case::Network>(content)?,
+    };
+    if version < 2 {
+        fill_ids(&mut network);
+    }
+    Ok(NativeCase {
+        network,
+        metadata,
+        schema_version: version,
+    })
+}
+
+/// Reads a native case, binary for `.bin` and JSON otherwise.
+pub fn read_native(path: &str) -> Result<NativeCase, String> {
+    let mut case = if path.ends_with(".bin") {
+        let bytes = std::fs::read(path).map_err(|e| format!("Error reading file: {}", e))?;
+        from_bytes(&bytes)?
+    } else {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Error reading file: {}", e))?;
+        from_json(&content)?
+    };
+    case.network.rebuild_bus_map();
+    Ok(case)
+}
+
+/// Writes a native case with a checksum, binary for `.bin` and JSON otherwise.
+pub fn write_native(path: &str, network: &case::Network) -> Result<(), String> {
+    let metadata = Metadata::now("");
+    if path.ends_with(".bin") {
+        to_bytes(network, &metadata, true).and_then(|bytes| {
+            std::fs::write(path, bytes).map_err(|e| format!("Error writing file: {}", e))
+        })
+    } else {
+        to_json(network, &metadata, true).and_then(|json| {
+            std::fs::write(path, json).map_err(|e| format!("Error writing file: {}", e))
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 0: the unversioned bincode layout. Frozen; do not edit.
+
+#[derive(Deserialize)]
+struct BusV0 {
+    bus_id: usize,
+    bus_name: String,
+    bus_type: BusType,
+    nom_voltage: f32,
+    bus_status: bool,
+    voltage: f32,
+    angle: f32,
+    real_shunt: f32,
+    imag_shunt: f32,
+    v_min_operating: f32,
+    v_min_contingency: f32,
+    v_max_operating: f32,
+    v_max_contingency: f32,
+}
+
+#[derive(Deserialize)]
+struct LoadV0 {
+    load_id: usize,
+    bus_id: usize,
+    load_name: String,
+    real_load: f32,
+    imag_load: f32,
+}
+
+#[derive(Deserialize)]
+struct BranchV0 {
+    branch_type: BranchType,
+    id: usize,
+    from_bus: usize,
+    to_bus: usize,
+    branch_name: String,
+    branch_status: bool,
+    resistance: f32,
+    reactance: f32,
+    from_shunt_conductance: f32,
+    from_shunt_susceptance: f32,
+    to_shunt_conductance: f32,
+    to_shunt_susceptance: f32,
+    tap_ratio: f32,
+    phase_shift: f32,
+    operating_limit: f32,
+    contingency_limit: f32,
+    flow: f32,
+}
+
+#[derive(Deserialize)]
+struct GeneratorV0 {
+    gen_id: usize,
+    gen_bus_id: usize,
+    gen_name: String,
+    gen_status: bool,
+    p_gen: f32,
+    q_gen: f32,
+    v_setpoint: f32,
+    p_min: f32,
+    p_max: f32,
+    q_min: f32,
+    q_max: f32,
+}
+
+#[derive(Deserialize)]
+struct NetworkV0 {
+    case_name: String,
+    s_base: f32,
+    frequency: f32,
+    buses: Vec<BusV0>,
+    branches: Vec<BranchV0>,
+    loads: Vec<LoadV0>,
+    generators: Vec<GeneratorV0>,
+}
+
+impl NetworkV0 {
+    /// Schema 0 to 1: buses gain area/zone/owner (all 1), generators an empty
+    /// cost, and the network an empty pass-through table.
+    fn migrate(self) -> Network {
+        let mut network = Network::new(self.case_name, self.s_base, self.frequency);
+        network.buses = self
+            .buses
+            .into_iter()
+            .map(|b| Bus {
+                bus_id: b.bus_id,
+                bus_name: b.bus_name,
+                bus_type: b.bus_type,
+                nom_voltage: b.nom_voltage,
+                area: 1,
+                zone: 1,
+                owner: 1,
+                bus_status: b.bus_status,
+                voltage: b.voltage,
+                angle: b.angle,
+                real_shunt: b.real_shunt,
+                imag_shunt: b.imag_shunt,
+                v_min_operating: b.v_min_operating,
+                v_min_contingency: b.v_min_contingency,
+                v_max_operating: b.v_max_operating,
+                v_max_contingency: b.v_max_contingency,
+            })
+            .collect();
+        network.branches = self
+            .branches
+            .into_iter()
+            .map(|b| Branch {
+                branch_type: b.branch_type,
+                id: b.id,
+                from_bus: b.from_bus,
+                to_bus: b.to_bus,
+                branch_name: b.branch_name,
+                branch_status: b.branch_status,
+                resistance: b.resistance,
+                reactance: b.reactance,
+                from_shunt_conductance: b.from_shunt_conductance,
+                from_shunt_susceptance: b.from_shunt_susceptance,
+                to_shunt_conductance: b.to_shunt_conductance,
+                to_shunt_susceptance: b.to_shunt_susceptance,
+                tap_ratio: b.tap_ratio,
+                phase_shift: b.phase_shift,
+                operating_limit: b.operating_limit,
+                contingency_limit: b.contingency_limit,
+                flow: b.flow,
+            })
+            .collect();
+        network.loads = self
+            .loads
+            .into_iter()
+            .map(|l| Load::new(l.load_id, l.bus_id, l.load_name, l.real_load, l.imag_load))
+            .collect();
+        network.generators = self
+            .generators
+            .into_iter()
+            .map(|g| Generator {
+                gen_id: g.gen_id,
+                gen_bus_id: g.gen_bus_id,
+                gen_name: g.gen_name,
+                gen_status: g.gen_status,
+                p_gen: g.p_gen,
+                q_gen: g.q_gen,
+                v_setpoint: g.v_setpoint,
+                p_min: g.p_min,
+                p_max: g.p_max,
+                q_min: g.q_min,
+                q_max: g.q_max,
+                cost: None,
+            })
+            .collect();
+        network
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 1: adds bus area/zone/owner, generator cost and pass-through tables.
+
+mod v1 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v2;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    #[allow(clippy::upper_case_acronyms)]
+    pub enum BusType {
+        Slack,
+        PQ,
+        PV,
+        OUT,
+    }
+
+    impl BusType {
+        pub fn migrate(self) -> case::BusType {
+            match self {
+                BusType::Slack => case::BusType::Slack,
+                BusType::PQ => case::BusType::PQ,
+                BusType::PV => case::BusType::PV,
+                BusType::OUT => case::BusType::OUT,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum BranchType {
+        Line,
+        TwoWinding,
+    }
+
+    impl BranchType {
+        pub fn migrate(self) -> case::BranchType {
+            match self {
+                BranchType::Line => case::BranchType::Line,
+                BranchType::TwoWinding => case::BranchType::TwoWinding,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum CostCurve {
+        PiecewiseLinear(Vec<(f32, f32)>),
+        Polynomial(Vec<f32>),
+    }
+
+    impl CostCurve {
+        pub fn migrate(self) -> case::CostCurve {
+            match self {
+                CostCurve::PiecewiseLinear(x) => case::CostCurve::PiecewiseLinear(x),
+                CostCurve::Polynomial(x) => case::CostCurve::Polynomial(x),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct GenCost {
+        pub startup: f32,
+        pub shutdown: f32,
+        pub curve: CostCurve,
+    }
+
+    impl GenCost {
+        pub fn migrate(self) -> case::GenCost {
+            case::GenCost {
+                startup: self.startup,
+                shutdown: self.shutdown,
+                curve: self.curve.migrate(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Bus {
+        pub bus_id: usize,
+        pub bus_name: String,
+        pub bus_type: BusType,
+        pub nom_voltage: f32,
+        pub area: usize,
+        pub zone: usize,
+        pub owner: usize,
+        pub bus_status: bool,
+        pub voltage: f32,
+        pub angle: f32,
+        pub real_shunt: f32,
+        pub imag_shunt: f32,
+        pub v_min_operating: f32,
+        pub v_min_contingency: f32,
+        pub v_max_operating: f32,
+        pub v_max_contingency: f32,
+    }
+
+    impl Bus {
+        pub fn migrate(self) -> case::Bus {
+            case::Bus {
+                bus_id: self.bus_id,
+                bus_name: self.bus_name,
+                bus_type: self.bus_type.migrate(),
+                nom_voltage: self.nom_voltage,
+                area: self.area,
+                zone: self.zone,
+                owner: self.owner,
+                bus_status: self.bus_status,
+                voltage: self.voltage,
+                angle: self.angle,
+                real_shunt: self.real_shunt,
+                imag_shunt: self.imag_shunt,
+                v_min_operating: self.v_min_operating,
+                v_min_contingency: self.v_min_contingency,
+                v_max_operating: self.v_max_operating,
+                v_max_contingency: self.v_max_contingency,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Load {
+        pub load_id: usize,
+        pub bus_id: usize,
+        pub load_name: String,
+        pub real_load: f32,
+        pub imag_load: f32,
+    }
+
+    impl Load {
+        pub fn new(
+            load_id: usize,
+            bus_id: usize,
+            load_name: String,
+            real_load: f32,
+            imag_load: f32,
+        ) -> Self {
+            Self {
+                load_id,
+                bus_id,
+                load_name,
+                real_load,
+                imag_load,
+            }
+        }
+
+        /// The RAW id is left for `fill_ids`; every schema 1 load is in service.
+        pub fn migrate(self) -> v2::Load {
+            v2::Load {
+                load_id: self.load_id,
+                bus_id: self.bus_id,
+                load_ident: String::new(),
+                load_name: self.load_name,
+                load_status: true,
+                real_load: self.real_load,
+                imag_load: self.imag_load,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Branch {
+        pub branch_type: BranchType,
+        pub id: usize,
+        pub from_bus: usize,
+        pub to_bus: usize,
+        pub branch_name: String,
+        pub branch_status: bool,
+        pub resistance: f32,
+        pub reactance: f32,
+        pub from_shunt_conductance: f32,
+        pub from_shunt_susceptance: f32,
+        pub to_shunt_conductance: f32,
+        pub to_shunt_susceptance: f32,
+        pub tap_ratio: f32,
+        pub phase_shift: f32,
+        pub operating_limit: f32,
+        pub contingency_limit: f32,
+        pub flow: f32,
+    }
+
+    impl Branch {
+        /// The circuit id is left for `fill_ids`.
+        pub fn migrate(self) -> v2::Branch {
+            v2::Branch {
+                branch_type: self.branch_type,
+                id: self.id,
+                from_bus: self.from_bus,
+                to_bus: self.to_bus,
+                ckt: String::new(),
+                branch_name: self.branch_name,
+                branch_status: self.branch_status,
+                resistance: self.resistance,
+                reactance: self.reactance,
+                from_shunt_conductance: self.from_shunt_conductance,
+                from_shunt_susceptance: self.from_shunt_susceptance,
+                to_shunt_conductance: self.to_shunt_conductance,
+                to_shunt_susceptance: self.to_shunt_susceptance,
+                tap_ratio: self.tap_ratio,
+                phase_shift: self.phase_shift,
+                operating_limit: self.operating_limit,
+                contingency_limit: self.contingency_limit,
+                flow: self.flow,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Generator {
+        pub gen_id: usize,
+        pub gen_bus_id: usize,
+        pub gen_name: String,
+        pub gen_status: bool,
+        pub p_gen: f32,
+        pub q_gen: f32,
+        pub v_setpoint: f32,
+        pub p_min: f32,
+        pub p_max: f32,
+        pub q_min: f32,
+        pub q_max: f32,
+        pub cost: Option<GenCost>,
+    }
+
+    impl Generator {
+        /// The machine id is left for `fill_ids`.
+        pub fn migrate(self) -> v2::Generator {
+            v2::Generator {
+                gen_id: self.gen_id,
+                gen_bus_id: self.gen_bus_id,
+                machine_id: String::new(),
+                gen_name: self.gen_name,
+                gen_status: self.gen_status,
+                p_gen: self.p_gen,
+                q_gen: self.q_gen,
+                v_setpoint: self.v_setpoint,
+                p_min: self.p_min,
+                p_max: self.p_max,
+                q_min: self.q_min,
+                q_max: self.q_max,
+                cost: self.cost,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+    }
+
+    impl Network {
+        pub fn new(case_name: String, s_base: f32, frequency: f32) -> Self {
+            Self {
+                case_name,
+                s_base,
+                frequency,
+                buses: Vec::new(),
+                branches: Vec::new(),
+                loads: Vec::new(),
+                generators: Vec::new(),
+                passthrough: BTreeMap::new(),
+            }
+        }
+
+        /// Schema 1 to 2: branches gain circuit ids, machines and loads their RAW
+        /// ids, and loads a status; see `fill_ids`.
+        pub fn migrate(self) -> v2::Network {
+            v2::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches.into_iter().map(Branch::migrate).collect(),
+                loads: self.loads.into_iter().map(Load::migrate).collect(),
+                generators: self
+                    .generators
+                    .into_iter()
+                    .map(Generator::migrate)
+                    .collect(),
+                passthrough: self.passthrough,
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 2: adds circuit, machine and load ids and the load status.
+
+mod v2 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::{BranchType, Bus, GenCost};
+    use super::v3;
+    use super::v7;
+    use super::v9;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Load {
+        pub load_id: usize,
+        pub bus_id: usize,
+        pub load_ident: String,
+        pub load_name: String,
+        pub load_status: bool,
+        pub real_load: f32,
+        pub imag_load: f32,
+    }
+
+    impl Load {
+        /// Schema 2 loads are all constant power.
+        pub fn migrate(self) -> v3::Load {
+            v3::Load {
+                load_id: self.load_id,
+                bus_id: self.bus_id,
+                load_ident: self.load_ident,
+                load_name: self.load_name,
+                load_status: self.load_status,
+                real_load: self.real_load,
+                imag_load: self.imag_load,
+                real_current: 0.0,
+                imag_current: 0.0,
+                real_admittance: 0.0,
+                imag_admittance: 0.0,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Branch {
+        pub branch_type: BranchType,
+        pub id: usize,
+        pub from_bus: usize,
+        pub to_bus: usize,
+        pub ckt: String,
+        pub branch_name: String,
+        pub branch_status: bool,
+        pub resistance: f32,
+        pub reactance: f32,
+        pub from_shunt_conductance: f32,
+        pub from_shunt_susceptance: f32,
+        pub to_shunt_conductance: f32,
+        pub to_shunt_susceptance: f32,
+        pub tap_ratio: f32,
+        pub phase_shift: f32,
+        pub operating_limit: f32,
+        pub contingency_limit: f32,
+        pub flow: f32,
+    }
+
+    impl Branch {
+        /// Impedance correction tables start with schema 7.
+        pub fn migrate(self) -> v7::Branch {
+            v7::Branch {
+                branch_type: self.branch_type,
+                id: self.id,
+                from_bus: self.from_bus,
+                to_bus: self.to_bus,
+                ckt: self.ckt,
+                branch_name: self.branch_name,
+                branch_status: self.branch_status,
+                resistance: self.resistance,
+                reactance: self.reactance,
+                from_shunt_conductance: self.from_shunt_conductance,
+                from_shunt_susceptance: self.from_shunt_susceptance,
+                to_shunt_conductance: self.to_shunt_conductance,
+                to_shunt_susceptance: self.to_shunt_susceptance,
+                tap_ratio: self.tap_ratio,
+                phase_shift: self.phase_shift,
+                impedance_table: None,
+                operating_limit: self.operating_limit,
+                contingency_limit: self.contingency_limit,
+                flow: self.flow,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Generator {
+        pub gen_id: usize,
+        pub gen_bus_id: usize,
+        pub machine_id: String,
+        pub gen_name: String,
+        pub gen_status: bool,
+        pub p_gen: f32,
+        pub q_gen: f32,
+        pub v_setpoint: f32,
+        pub p_min: f32,
+        pub p_max: f32,
+        pub q_min: f32,
+        pub q_max: f32,
+        pub cost: Option<GenCost>,
+    }
+
+    impl Generator {
+        /// Capability curves start with schema 9.
+        pub fn migrate(self) -> v9::Generator {
+            v9::Generator {
+                gen_id: self.gen_id,
+                gen_bus_id: self.gen_bus_id,
+                machine_id: self.machine_id,
+                gen_name: self.gen_name,
+                gen_status: self.gen_status,
+                p_gen: self.p_gen,
+                q_gen: self.q_gen,
+                v_setpoint: self.v_setpoint,
+                p_min: self.p_min,
+                p_max: self.p_max,
+                q_min: self.q_min,
+                q_max: self.q_max,
+                cost: self.cost,
+                capability: Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+    }
+
+    impl Network {
+        /// Schema 2 to 3: loads gain constant current and admittance parts.
+        pub fn migrate(self) -> v3::Network {
+            v3::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads.into_iter().map(Load::migrate).collect(),
+                generators: self.generators,
+                passthrough: self.passthrough,
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 3: adds ZIP load parts.
+
+mod v3 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::Bus;
+    use super::v2::{Branch, Generator};
+    use super::v4;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Load {
+        pub load_id: usize,
+        pub bus_id: usize,
+        pub load_ident: String,
+        pub load_name: String,
+        pub load_status: bool,
+        pub real_load: f32,
+        pub imag_load: f32,
+        pub real_current: f32,
+        pub imag_current: f32,
+        pub real_admittance: f32,
+        pub imag_admittance: f32,
+    }
+
+    impl Load {
+        pub fn migrate(self) -> case::Load {
+            case::Load {
+                load_id: self.load_id,
+                bus_id: self.bus_id,
+                load_ident: self.load_ident,
+                load_name: self.load_name,
+                load_status: self.load_status,
+                real_load: self.real_load,
+                imag_load: self.imag_load,
+                real_current: self.real_current,
+                imag_current: self.imag_current,
+                real_admittance: self.real_admittance,
+                imag_admittance: self.imag_admittance,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+    }
+
+    impl Network {
+        /// Schema 3 to 4: the network gains two-terminal DC lines.
+        pub fn migrate(self) -> v4::Network {
+            v4::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: Vec::new(),
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 4: adds two-terminal DC lines.
+
+mod v4 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::Bus;
+    use super::v2::{Branch, Generator};
+    use super::v3::Load;
+    use super::v5;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum DcControl {
+        Blocked,
+        Power,
+        Current,
+    }
+
+    impl DcControl {
+        pub fn migrate(self) -> case::DcControl {
+            match self {
+                DcControl::Blocked => case::DcControl::Blocked,
+                DcControl::Power => case::DcControl::Power,
+                DcControl::Current => case::DcControl::Current,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct DcConverter {
+        pub bus_id: usize,
+        pub bridges: u32,
+        pub angle_max: f32,
+        pub angle_min: f32,
+        pub commutating_resistance: f32,
+        pub commutating_reactance: f32,
+        pub base_kv: f32,
+        pub transformer_ratio: f32,
+        pub tap: f32,
+        pub tap_max: f32,
+        pub tap_min: f32,
+        pub tap_step: f32,
+    }
+
+    impl DcConverter {
+        pub fn migrate(self) -> case::DcConverter {
+            case::DcConverter {
+                bus_id: self.bus_id,
+                bridges: self.bridges,
+                angle_max: self.angle_max,
+                angle_min: self.angle_min,
+                commutating_resistance: self.commutating_resistance,
+                commutating_reactance: self.commutating_reactance,
+                base_kv: self.base_kv,
+                transformer_ratio: self.transformer_ratio,
+                tap: self.tap,
+                tap_max: self.tap_max,
+                tap_min: self.tap_min,
+                tap_step: self.tap_step,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct DcLine {
+        pub name: String,
+        pub control: DcControl,
+        pub resistance: f32,
+        pub setpoint: f32,
+        pub scheduled_voltage: f32,
+        pub switch_voltage: f32,
+        pub compounding_resistance: f32,
+        pub current_margin: f32,
+        pub rectifier: DcConverter,
+        pub inverter: DcConverter,
+    }
+
+    impl DcLine {
+        pub fn migrate(self) -> case::DcLine {
+            case::DcLine {
+                name: self.name,
+                control: self.control.migrate(),
+                resistance: self.resistance,
+                setpoint: self.setpoint,
+                scheduled_voltage: self.scheduled_voltage,
+                switch_voltage: self.switch_voltage,
+                compounding_resistance: self.compounding_resistance,
+                current_margin: self.current_margin,
+                rectifier: self.rectifier.migrate(),
+                inverter: self.inverter.migrate(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+    }
+
+    impl Network {
+        /// Schema 4 to 5: the network gains DC grids of VSC converters.
+        pub fn migrate(self) -> v5::Network {
+            v5::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: Vec::new(),
+                dc_branches: Vec::new(),
+                vsc_converters: Vec::new(),
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 5: adds DC grids of VSC converters.
+
+mod v5 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::Bus;
+    use super::v2::{Branch, Generator};
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v6;
+    use super::v10;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct DcBus {
+        pub dc_bus_id: usize,
+        pub name: String,
+        pub voltage: f32,
+    }
+
+    impl DcBus {
+        pub fn migrate(self) -> case::DcBus {
+            case::DcBus {
+                dc_bus_id: self.dc_bus_id,
+                name: self.name,
+                voltage: self.voltage,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct DcBranch {
+        pub from_bus: usize,
+        pub to_bus: usize,
+        pub name: String,
+        pub status: bool,
+        pub resistance: f32,
+        pub flow: f32,
+    }
+
+    impl DcBranch {
+        pub fn migrate(self) -> case::DcBranch {
+            case::DcBranch {
+                from_bus: self.from_bus,
+                to_bus: self.to_bus,
+                name: self.name,
+                status: self.status,
+                resistance: self.resistance,
+                flow: self.flow,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum VscDcControl {
+        Power,
+        Voltage,
+    }
+
+    impl VscDcControl {
+        pub fn migrate(self) -> case::VscDcControl {
+            match self {
+                VscDcControl::Power => case::VscDcControl::Power,
+                VscDcControl::Voltage => case::VscDcControl::Voltage,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum VscAcControl {
+        Voltage,
+        ReactivePower,
+        PowerFactor,
+    }
+
+    impl VscAcControl {
+        pub fn migrate(self) -> case::VscAcControl {
+            match self {
+                VscAcControl::Voltage => case::VscAcControl::Voltage,
+                VscAcControl::ReactivePower => case::VscAcControl::ReactivePower,
+                VscAcControl::PowerFactor => case::VscAcControl::PowerFactor,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct VscConverter {
+        pub name: String,
+        pub ac_bus_id: usize,
+        pub dc_bus_id: usize,
+        pub status: bool,
+        pub dc_control: VscDcControl,
+        pub dc_setpoint: f32,
+        pub ac_control: VscAcControl,
+        pub ac_setpoint: f32,
+        pub loss_a: f32,
+        pub loss_b: f32,
+        pub loss_min: f32,
+        pub s_max: f32,
+        pub i_max: f32,
+        pub q_max: f32,
+        pub q_min: f32,
+        pub p_ac: f32,
+    }
+
+    impl VscConverter {
+        /// The solved MVAR starts with schema 10.
+        pub fn migrate(self) -> v10::VscConverter {
+            v10::VscConverter {
+                name: self.name,
+                ac_bus_id: self.ac_bus_id,
+                dc_bus_id: self.dc_bus_id,
+                status: self.status,
+                dc_control: self.dc_control,
+                dc_setpoint: self.dc_setpoint,
+                ac_control: self.ac_control,
+                ac_setpoint: self.ac_setpoint,
+                loss_a: self.loss_a,
+                loss_b: self.loss_b,
+                loss_min: self.loss_min,
+                s_max: self.s_max,
+                i_max: self.i_max,
+                q_max: self.q_max,
+                q_min: self.q_min,
+                p_ac: self.p_ac,
+                q_ac: 0.0,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+    }
+
+    impl Network {
+        /// Schema 5 to 6: the network gains FACTS devices.
+        pub fn migrate(self) -> v6::Network {
+            v6::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: self.dc_buses,
+                dc_branches: self.dc_branches,
+                vsc_converters: self.vsc_converters,
+                facts_devices: Vec::new(),
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 6: adds FACTS devices.
+
+mod v6 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::Bus;
+    use super::v2::{Branch, Generator};
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v5::{DcBranch, DcBus, VscConverter};
+    use super::{v7, v11};
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum SeriesControl {
+        Flow,
+        Impedance,
+        Voltage,
+        Bypassed,
+    }
+
+    impl SeriesControl {
+        pub fn migrate(self) -> case::SeriesControl {
+            match self {
+                SeriesControl::Flow => case::SeriesControl::Flow,
+                SeriesControl::Impedance => case::SeriesControl::Impedance,
+                SeriesControl::Voltage => case::SeriesControl::Voltage,
+                SeriesControl::Bypassed => case::SeriesControl::Bypassed,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct SeriesLink {
+        pub to_bus: usize,
+        pub control: SeriesControl,
+        pub p_setpoint: f32,
+        pub q_setpoint: f32,
+        pub setpoint_real: f32,
+        pub setpoint_imag: f32,
+        pub rating: f32,
+        pub flow: f32,
+    }
+
+    impl SeriesLink {
+        pub fn migrate(self) -> v11::SeriesLink {
+            v11::SeriesLink {
+                to_bus: self.to_bus,
+                control: self.control,
+                p_setpoint: self.p_setpoint,
+                q_setpoint: self.q_setpoint,
+                setpoint_real: self.setpoint_real,
+                setpoint_imag: self.setpoint_imag,
+                rating: self.rating,
+                // The RAW default LINX
+                reactance: 0.05,
+                flow: self.flow,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct FactsDevice {
+        pub name: String,
+        pub bus_id: usize,
+        pub status: bool,
+        pub v_setpoint: f32,
+        pub q_max: f32,
+        pub q_min: f32,
+        pub series: Option<SeriesLink>,
+    }
+
+    impl FactsDevice {
+        pub fn migrate(self) -> v11::FactsDevice {
+            v11::FactsDevice {
+                name: self.name,
+                bus_id: self.bus_id,
+                status: self.status,
+                v_setpoint: self.v_setpoint,
+                q_max: self.q_max,
+                q_min: self.q_min,
+                series: self.series.map(SeriesLink::migrate),
+                q_shunt: 0.0,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+        pub facts_devices: Vec<FactsDevice>,
+    }
+
+    impl Network {
+        /// Schema 6 to 7: the network gains impedance correction tables.
+        pub fn migrate(self) -> v7::Network {
+            v7::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches.into_iter().map(Branch::migrate).collect(),
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: self.dc_buses,
+                dc_branches: self.dc_branches,
+                vsc_converters: self.vsc_converters,
+                facts_devices: self.facts_devices,
+                impedance_tables: Vec::new(),
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 7: adds transformer impedance correction tables.
+
+mod v7 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::{BranchType, Bus};
+    use super::v2::Generator;
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v5::{DcBranch, DcBus, VscConverter};
+    use super::v6::FactsDevice;
+    use super::v8;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct TableLink {
+        pub table: usize,
+        pub by_angle: bool,
+    }
+
+    impl TableLink {
+        pub fn migrate(self) -> case::TableLink {
+            case::TableLink {
+                table: self.table,
+                by_angle: self.by_angle,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ImpedanceTable {
+        pub number: usize,
+        pub points: Vec<(f32, f32)>,
+    }
+
+    impl ImpedanceTable {
+        pub fn migrate(self) -> case::ImpedanceTable {
+            case::ImpedanceTable {
+                number: self.number,
+                points: self
+                    .points
+                    .into_iter()
+                    .map(|(x, factor)| (x, factor, 0.0))
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Branch {
+        pub branch_type: BranchType,
+        pub id: usize,
+        pub from_bus: usize,
+        pub to_bus: usize,
+        pub ckt: String,
+        pub branch_name: String,
+        pub branch_status: bool,
+        pub resistance: f32,
+        pub reactance: f32,
+        pub from_shunt_conductance: f32,
+        pub from_shunt_susceptance: f32,
+        pub to_shunt_conductance: f32,
+        pub to_shunt_susceptance: f32,
+        pub tap_ratio: f32,
+        pub phase_shift: f32,
+        pub impedance_table: Option<TableLink>,
+        pub operating_limit: f32,
+        pub contingency_limit: f32,
+        pub flow: f32,
+    }
+
+    impl Branch {
+        pub fn migrate(self) -> case::Branch {
+            case::Branch {
+                branch_type: self.branch_type.migrate(),
+                id: self.id,
+                from_bus: self.from_bus,
+                to_bus: self.to_bus,
+                ckt: self.ckt,
+                branch_name: self.branch_name,
+                branch_status: self.branch_status,
+                resistance: self.resistance,
+                reactance: self.reactance,
+                from_shunt_conductance: self.from_shunt_conductance,
+                from_shunt_susceptance: self.from_shunt_susceptance,
+                to_shunt_conductance: self.to_shunt_conductance,
+                to_shunt_susceptance: self.to_shunt_susceptance,
+                tap_ratio: self.tap_ratio,
+                phase_shift: self.phase_shift,
+                impedance_table: self.impedance_table.map(TableLink::migrate),
+                operating_limit: self.operating_limit,
+                contingency_limit: self.contingency_limit,
+                flow: self.flow,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+        pub facts_devices: Vec<FactsDevice>,
+        pub impedance_tables: Vec<ImpedanceTable>,
+    }
+
+    impl Network {
+        /// Schema 7 to 8: the network gains multi-section line groups.
+        pub fn migrate(self) -> v8::Network {
+            v8::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: self.dc_buses,
+                dc_branches: self.dc_branches,
+                vsc_converters: self.vsc_converters,
+                facts_devices: self.facts_devices,
+                impedance_tables: self.impedance_tables,
+                line_groups: Vec::new(),
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 8: adds multi-section line groups.
+
+mod v8 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::Bus;
+    use super::v2::Generator;
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v5::{DcBranch, DcBus, VscConverter};
+    use super::v6::FactsDevice;
+    use super::v7::{Branch, ImpedanceTable};
+    use super::v9;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct LineGroup {
+        pub from_bus: usize,
+        pub to_bus: usize,
+        pub id: String,
+        pub metered_from: bool,
+        pub dummy_buses: Vec<usize>,
+    }
+
+    impl LineGroup {
+        pub fn migrate(self) -> case::LineGroup {
+            case::LineGroup {
+                from_bus: self.from_bus,
+                to_bus: self.to_bus,
+                id: self.id,
+                metered_from: self.metered_from,
+                dummy_buses: self.dummy_buses,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+        pub facts_devices: Vec<FactsDevice>,
+        pub impedance_tables: Vec<ImpedanceTable>,
+        pub line_groups: Vec<LineGroup>,
+    }
+
+    impl Network {
+        /// Schema 8 to 9: generators gain reactive capability curves.
+        pub fn migrate(self) -> v9::Network {
+            v9::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self
+                    .generators
+                    .into_iter()
+                    .map(Generator::migrate)
+                    .collect(),
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: self.dc_buses,
+                dc_branches: self.dc_branches,
+                vsc_converters: self.vsc_converters,
+                facts_devices: self.facts_devices,
+                impedance_tables: self.impedance_tables,
+                line_groups: self.line_groups,
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 9: adds generator capability curves.
+
+mod v9 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::{Bus, GenCost};
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v5::{DcBranch, DcBus, VscConverter};
+    use super::v6::FactsDevice;
+    use super::v7::{Branch, ImpedanceTable};
+    use super::v8::LineGroup;
+    use super::v10;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct CapabilityPoint {
+        pub p: f32,
+        pub q_min: f32,
+        pub q_max: f32,
+    }
+
+    impl CapabilityPoint {
+        pub fn migrate(self) -> case::CapabilityPoint {
+            case::CapabilityPoint {
+                p: self.p,
+                q_min: self.q_min,
+                q_max: self.q_max,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Generator {
+        pub gen_id: usize,
+        pub gen_bus_id: usize,
+        pub machine_id: String,
+        pub gen_name: String,
+        pub gen_status: bool,
+        pub p_gen: f32,
+        pub q_gen: f32,
+        pub v_setpoint: f32,
+        pub p_min: f32,
+        pub p_max: f32,
+        pub q_min: f32,
+        pub q_max: f32,
+        pub cost: Option<GenCost>,
+        pub capability: Vec<CapabilityPoint>,
+    }
+
+    impl Generator {
+        pub fn migrate(self) -> case::Generator {
+            case::Generator {
+                gen_id: self.gen_id,
+                gen_bus_id: self.gen_bus_id,
+                machine_id: self.machine_id,
+                gen_name: self.gen_name,
+                gen_status: self.gen_status,
+                p_gen: self.p_gen,
+                q_gen: self.q_gen,
+                v_setpoint: self.v_setpoint,
+                p_min: self.p_min,
+                p_max: self.p_max,
+                q_min: self.q_min,
+                q_max: self.q_max,
+                cost: self.cost.map(GenCost::migrate),
+                capability: self
+                    .capability
+                    .into_iter()
+                    .map(CapabilityPoint::migrate)
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+        pub facts_devices: Vec<FactsDevice>,
+        pub impedance_tables: Vec<ImpedanceTable>,
+        pub line_groups: Vec<LineGroup>,
+    }
+
+    impl Network {
+        /// Schema 9 to 10: VSC converters gain their solved MVAR.
+        pub fn migrate(self) -> v10::Network {
+            v10::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: self.dc_buses,
+                dc_branches: self.dc_branches,
+                vsc_converters: self
+                    .vsc_converters
+                    .into_iter()
+                    .map(VscConverter::migrate)
+                    .collect(),
+                facts_devices: self.facts_devices,
+                impedance_tables: self.impedance_tables,
+                line_groups: self.line_groups,
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 10: adds the solved MVAR of VSC converters.
+
+mod v10 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    use super::v1::Bus;
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v5::{DcBranch, DcBus, VscAcControl, VscDcControl};
+    use super::v6::FactsDevice;
+    use super::v7::{Branch, ImpedanceTable};
+    use super::v8::LineGroup;
+    use super::v9::Generator;
+    use super::v11;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct VscConverter {
+        pub name: String,
+        pub ac_bus_id: usize,
+        pub dc_bus_id: usize,
+        pub status: bool,
+        pub dc_control: VscDcControl,
+        pub dc_setpoint: f32,
+        pub ac_control: VscAcControl,
+        pub ac_setpoint: f32,
+        pub loss_a: f32,
+        pub loss_b: f32,
+        pub loss_min: f32,
+        pub s_max: f32,
+        pub i_max: f32,
+        pub q_max: f32,
+        pub q_min: f32,
+        pub p_ac: f32,
+        pub q_ac: f32,
+    }
+
+    impl VscConverter {
+        pub fn migrate(self) -> case::VscConverter {
+            case::VscConverter {
+                name: self.name,
+                ac_bus_id: self.ac_bus_id,
+                dc_bus_id: self.dc_bus_id,
+                status: self.status,
+                dc_control: self.dc_control.migrate(),
+                dc_setpoint: self.dc_setpoint,
+                ac_control: self.ac_control.migrate(),
+                ac_setpoint: self.ac_setpoint,
+                loss_a: self.loss_a,
+                loss_b: self.loss_b,
+                loss_min: self.loss_min,
+                s_max: self.s_max,
+                i_max: self.i_max,
+                q_max: self.q_max,
+                q_min: self.q_min,
+                p_ac: self.p_ac,
+                q_ac: self.q_ac,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+        pub facts_devices: Vec<FactsDevice>,
+        pub impedance_tables: Vec<ImpedanceTable>,
+        pub line_groups: Vec<LineGroup>,
+    }
+
+    impl Network {
+        /// Schema 10 to 11: FACTS series links gain their reactance and
+        /// devices their solved shunt MVAR.
+        pub fn migrate(self) -> v11::Network {
+            v11::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses,
+                branches: self.branches,
+                loads: self.loads,
+                generators: self.generators,
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines,
+                dc_buses: self.dc_buses,
+                dc_branches: self.dc_branches,
+                vsc_converters: self.vsc_converters,
+                facts_devices: self
+                    .facts_devices
+                    .into_iter()
+                    .map(FactsDevice::migrate)
+                    .collect(),
+                impedance_tables: self.impedance_tables,
+                line_groups: self.line_groups,
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate().upgrade()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema 11: adds the FACTS series reactance and solved shunt MVAR.
+
+mod v11 {
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::v1::Bus;
+    use super::v3::Load;
+    use super::v4::DcLine;
+    use super::v5::{DcBranch, DcBus};
+    use super::v6::SeriesControl;
+    use super::v7::{Branch, ImpedanceTable};
+    use super::v8::LineGroup;
+    use super::v9::Generator;
+    use super::v10::VscConverter;
+    use crate::case;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct SeriesLink {
+        pub to_bus: usize,
+        pub control: SeriesControl,
+        pub p_setpoint: f32,
+        pub q_setpoint: f32,
+        pub setpoint_real: f32,
+        pub setpoint_imag: f32,
+        pub rating: f32,
+        pub reactance: f32,
+        pub flow: f32,
+    }
+
+    impl SeriesLink {
+        pub fn migrate(self) -> case::SeriesLink {
+            case::SeriesLink {
+                to_bus: self.to_bus,
+                control: self.control.migrate(),
+                p_setpoint: self.p_setpoint,
+                q_setpoint: self.q_setpoint,
+                setpoint_real: self.setpoint_real,
+                setpoint_imag: self.setpoint_imag,
+                rating: self.rating,
+                reactance: self.reactance,
+                flow: self.flow,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct FactsDevice {
+        pub name: String,
+        pub bus_id: usize,
+        pub status: bool,
+        pub v_setpoint: f32,
+        pub q_max: f32,
+        pub q_min: f32,
+        pub series: Option<SeriesLink>,
+        pub q_shunt: f32,
+    }
+
+    impl FactsDevice {
+        pub fn migrate(self) -> case::FactsDevice {
+            case::FactsDevice {
+                name: self.name,
+                bus_id: self.bus_id,
+                status: self.status,
+                v_setpoint: self.v_setpoint,
+                q_max: self.q_max,
+                q_min: self.q_min,
+                series: self.series.map(SeriesLink::migrate),
+                q_shunt: self.q_shunt,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Network {
+        pub case_name: String,
+        pub s_base: f32,
+        pub frequency: f32,
+        pub buses: Vec<Bus>,
+        pub branches: Vec<Branch>,
+        pub loads: Vec<Load>,
+        pub generators: Vec<Generator>,
+        pub passthrough: BTreeMap<String, String>,
+        pub dc_lines: Vec<DcLine>,
+        pub dc_buses: Vec<DcBus>,
+        pub dc_branches: Vec<DcBranch>,
+        pub vsc_converters: Vec<VscConverter>,
+        pub facts_devices: Vec<FactsDevice>,
+        pub impedance_tables: Vec<ImpedanceTable>,
+        pub line_groups: Vec<LineGroup>,
+    }
+
+    impl Network {
+        /// Schema 11 to 12: impedance correction factors become complex.
+        pub fn migrate(self) -> case::Network {
+            case::Network {
+                case_name: self.case_name,
+                s_base: self.s_base,
+                frequency: self.frequency,
+                buses: self.buses.into_iter().map(Bus::migrate).collect(),
+                branches: self.branches.into_iter().map(Branch::migrate).collect(),
+                loads: self.loads.into_iter().map(Load::migrate).collect(),
+                generators: self
+                    .generators
+                    .into_iter()
+                    .map(Generator::migrate)
+                    .collect(),
+                passthrough: self.passthrough,
+                dc_lines: self.dc_lines.into_iter().map(DcLine::migrate).collect(),
+                dc_buses: self.dc_buses.into_iter().map(DcBus::migrate).collect(),
+                dc_branches: self
+                    .dc_branches
+                    .into_iter()
+                    .map(DcBranch::migrate)
+                    .collect(),
+                vsc_converters: self
+                    .vsc_converters
+                    .into_iter()
+                    .map(VscConverter::migrate)
+                    .collect(),
+                facts_devices: self
+                    .facts_devices
+                    .into_iter()
+                    .map(FactsDevice::migrate)
+                    .collect(),
+                impedance_tables: self
+                    .impedance_tables
+                    .into_iter()
+                    .map(ImpedanceTable::migrate)
+                    .collect(),
+                line_groups: self
+                    .line_groups
+                    .into_iter()
+                    .map(LineGroup::migrate)
+                    .collect(),
+                bus_map: HashMap::new(),
+            }
+        }
+    }
+
+    impl super::Layout for Network {
+        fn upgrade(self) -> case::Network {
+            self.migrate()
+        }
+    }
+}
+
+/// Fills identifiers a case saved before schema 2 lacks: circuit ids are
+/// numbered per bus pair, machine and load ids recovered from the
+/// "Bus{n}-{id}" names, and the blank-padded branch names older readers
+/// wrote are trimmed.
+fn fill_ids(network: &mut case::Network) {
+    network.number_circuits();
+    for br in &mut network.branches {
+        br.branch_name = br.branch_name.trim().to_string();
+    }
+    for g in network
+        .generators
+        .iter_mut()
+        .filter(|g| g.machine_id.is_empty())
+    {
+        g.machine_id = raw_id(&g.gen_name, g.gen_bus_id);
+    }
+    for l in network.loads.iter_mut().filter(|l| l.load_ident.is_empty()) {
+        l.load_ident = raw_id(&l.load_name, l.bus_id);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JSON Schema of the native JSON container.
+
+fn number() -> Value {
+    json!({"type": "number"})
+}
+
+fn integer() -> Value {
+    json!({"type": "integer", "minimum": 0})
+}
+
+fn string() -> Value {
+    json!({"type": "string"})
+}
+
+fn boolean() -> Value {
+    json!({"type": "boolean"})
+}
+
+fn array_of(items: Value) -> Value {
+    json!({"type": "array", "items": items})
+}
+
+fn reference(name: &str) -> Value {
+    json!({"$ref": format!("#/$defs/{}", name)})
+}
+
+/// Closed object schema; fields listed in `optional` may be omitted.
+fn object(fields: Vec<(&str, Value)>, optional: &[&str]) -> Value {
+    let required: Vec<&str> = fields
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| !optional.contains(name))
+        .collect();
+    let properties: serde_json::Map<String, Value> = fields
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), schema))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// JSON Schema (draft 2020-12) of the native JSON case at `SCHEMA_VERSION`.
+/// Keep it in step with the serde layout of the case structs.
+pub fn json_schema() -> Value {
+    let mut defs = BTreeMap::new();
+    defs.insert("BusType", json!({"enum": ["Slack", "PQ", "PV", "OUT"]}));
+    defs.insert("BranchType", json!({"enum": ["Line", "TwoWinding"]}));
+    defs.insert(
+        "Bus",
+        object(
+            vec![
+                ("bus_id", integer()),
+                ("bus_name", string()),
+                ("bus_type", reference("BusType")),
+                ("nom_voltage", number()),
+                ("area", integer()),
+                ("zone", integer()),
+                ("owner", integer()),
+                ("bus_status", boolean()),
+                ("voltage", number()),
+                ("angle", number()),
+                ("real_shunt", number()),
+                ("imag_shunt", number()),
+                ("v_min_operating", number()),
+                ("v_min_contingency", number()),
+                ("v_max_operating", number()),
+                ("v_max_contingency", number()),
+            ],
+            &["area", "zone", "owner"],
+        ),
+    );
+    defs.insert(
+        "Load",
+        object(
+            vec![
+                ("load_id", integer()),
+                ("bus_id", integer()),
+                ("load_ident", string()),
+                ("load_name", string()),
+                ("load_status", boolean()),
+                ("real_load", number()),
+                ("imag_load", number()),
+                ("real_current", number()),
+                ("imag_current", number()),
+                ("real_admittance", number()),
+                ("imag_admittance", number()),
+            ],
+            &[
+                "load_ident",
+                "load_status",
+                "real_current",
+                "imag_current",
+                "real_admittance",
+                "imag_admittance",
+            ],
+        ),
+    );
+    defs.insert(
+        "Branch",
+        object(
+            vec![
+                ("branch_type", reference("BranchType")),
+                ("id", integer()),
+                ("from_bus", integer()),
+                ("to_bus", integer()),
+                ("ckt", string()),
+                ("branch_name", string()),
+                ("branch_status", boolean()),
+                ("resistance", number()),
+                ("reactance", number()),
+                ("from_shunt_conductance", number()),
+                ("from_shunt_susceptance", number()),
+                ("to_shunt_conductance", number()),
+                ("to_shunt_susceptance", number()),
+                ("tap_ratio", number()),
+                ("phase_shift", number()),
+                (
+                    "impedance_table",
+                    json!({"oneOf": [reference("TableLink"), {"type": "null"}]}),
+                ),
+                ("operating_limit", number()),
+                ("contingency_limit", number()),
+                ("flow", number()),
+            ],
+            &["ckt", "impedance_table"],
+        ),
+    );
+    defs.insert(
+        "CostCurve",
+        json!({
+            "oneOf": [
+                object(
+                    vec![(
+                        "PiecewiseLinear",
+                        array_of(json!({
+                            "type": "array",
+                            "prefixItems": [number(), number()],
+                            "minItems": 2,
+                            "maxItems": 2,
+                        })),
+                    )],
+                    &[],
+                ),
+                object(vec![("Polynomial", array_of(number()))], &[]),
+            ]
+        }),
+    );
+    defs.insert(
+        "GenCost",
+        object(
+            vec![
+                ("startup", number()),
+                ("shutdown", number()),
+                ("curve", reference("CostCurve")),
+            ],
+            &[],
+        ),
+    );
+    defs.insert(
+        "Generator",
+        object(
+            vec![
+                ("gen_id", integer()),
+                ("gen_bus_id", integer()),
+                ("machine_id", string()),
+                ("gen_name", string()),
+                ("gen_status", boolean()),
+                ("p_gen", number()),
+                ("q_gen", number()),
+                ("v_setpoint", number()),
+                ("p_min", number()),
+                ("p_max", number()),
+                ("q_min", number()),
+                ("q_max", number()),
+                (
+                    "cost",
+                    json!({"oneOf": [reference("GenCost"), {"type": "null"}]}),
+                ),
+                ("capability", array_of(reference("CapabilityPoint"))),
+            ],
+            &["machine_id", "cost", "capability"],
+        ),
+    );
+    defs.insert(
+        "CapabilityPoint",
+        object(
+            vec![("p", number()), ("q_min", number()), ("q_max", number())],
+            &[],
+        ),
+    );
+    defs.insert(
+        "DcControl",
+        json!({"enum": ["Blocked", "Power", "Current"]}),
+    );
+    defs.insert(
+        "DcConverter",
+        object(
+            vec![
+                ("bus_id", integer()),
+                ("bridges", integer()),
+                ("angle_max", number()),
+                ("angle_min", number()),
+                ("commutating_resistance", number()),
+                ("commutating_reactance", number()),
+                ("base_kv", number()),
+                ("transformer_ratio", number()),
+                ("tap", number()),
+                ("tap_max", number()),
+                ("tap_min", number()),
+                ("tap_step", number()),
+            ],
+            &[],
+        ),
+    );
+    defs.insert(
+        "DcLine",
+        object(
+            vec![
+                ("name", string()),
+                ("control", reference("DcControl")),
+                ("resistance", number()),
+                ("setpoint", number()),
+                ("scheduled_voltage", number()),
+                ("switch_voltage", number()),
+                ("compounding_resistance", number()),
+                ("current_margin", number()),
+                ("rectifier", reference("DcConverter")),
+                ("inverter", reference("DcConverter")),
+            ],
+            &[],
+        ),
+    );
+    defs.insert(
+        "DcBus",
+        object(
+            vec![
+                ("dc_bus_id", integer()),
+                ("name", string()),
+                ("voltage", number()),
+            ],
+            &[],
+        ),
+    );
+    defs.insert(
+        "DcBranch",
+        object(
+            vec![
+                ("from_bus", integer()),
+                ("to_bus", integer()),
+                ("name", string()),
+                ("status", boolean()),
+                ("resistance", number()),
+                ("flow", number()),
+            ],
+            &[],
+        ),
+    );
+    defs.insert("VscDcControl", json!({"enum": ["Power", "Voltage"]}));
+    defs.insert(
+        "VscAcControl",
+        json!({"enum": ["Voltage", "ReactivePower", "PowerFactor"]}),
+    );
+    defs.insert(
+        "VscConverter",
+        object(
+            vec![
+                ("name", string()),
+                ("ac_bus_id", integer()),
+                ("dc_bus_id", integer()),
+                ("status", boolean()),
+                ("dc_control", reference("VscDcControl")),
+                ("dc_setpoint", number()),
+                ("ac_control", reference("VscAcControl")),
+                ("ac_setpoint", number()),
+                ("loss_a", number()),
+                ("loss_b", number()),
+                ("loss_min", number()),
+                ("s_max", number()),
+                ("i_max", number()),
+                ("q_max", number()),
+                ("q_min", number()),
+                ("p_ac", number()),
+                ("q_ac", number()),
+            ],
+            &["q_ac"],
+        ),
+    );
+    defs.insert(
+        "SeriesControl",
+        json!({"enum": ["Flow", "Impedance", "Voltage", "Bypassed"]}),
+    );
+    defs.insert(
+        "SeriesLink",
+        object(
+            vec![
+                ("to_bus", integer()),
+                ("control", reference("SeriesControl")),
+                ("p_setpoint", number()),
+                ("q_setpoint", number()),
+                ("setpoint_real", number()),
+                ("setpoint_imag", number()),
+                ("rating", number()),
+                ("reactance", number()),
+                ("flow", number()),
+            ],
+            &["reactance"],
+        ),
+    );
+    defs.insert(
+        "FactsDevice",
+        object(
+            vec![
+                ("name", string()),
+                ("bus_id", integer()),
+                ("status", boolean()),
+                ("v_setpoint", number()),
+                ("q_max", number()),
+                ("q_min", number()),
+                (
+                    "series",
+                    json!({"oneOf": [reference("SeriesLink"), {"type": "null"}]}),
+                ),
+                ("q_shunt", number()),
+            ],
+            &["q_shunt"],
+        ),
+    );
+    defs.insert(
+        "ImpedanceTable",
+        object(
+            vec![
+                ("number", integer()),
+                (
+                    "points",
+                    array_of(json!({
+                        "type": "array",
+                        "prefixItems": [number(), number(), number()],
+                        "minItems": 3,
+                        "maxItems": 3,
+                    })),
+                ),
+            ],
+            &[],
+        ),
+    );
+    defs.insert(
+        "TableLink",
+        object(vec![("table", integer()), ("by_angle", boolean())], &[]),
+    );
+    defs.insert(
+        "LineGroup",
+        object(
+            vec![
+                ("from_bus", integer()),
+                ("to_bus", integer()),
+                ("id", string()),
+                ("metered_from", boolean()),
+                ("dummy_buses", array_of(integer())),
+            ],
+            &[],
+        ),
+    );
+    defs.insert(
+        "Network",
+        object(
+            vec![
+                ("case_name", string()),
+                ("s_base", number()),
+                ("frequency", number()),
+                ("buses", array_of(reference("Bus"))),
+                ("branches", array_of(reference("Branch"))),
+                ("loads", array_of(reference("Load"))),
+                ("generators", array_of(reference("Generator"))),
+                (
+                    "passthrough",
+                    json!({"type": "object", "additionalProperties": string()}),
+                ),
+                ("dc_lines", array_of(reference("DcLine"))),
+                ("dc_buses", array_of(reference("DcBus"))),
+                ("dc_branches", array_of(reference("DcBranch"))),
+                ("vsc_converters", array_of(reference("VscConverter"))),
+                ("facts_devices", array_of(reference("FactsDevice"))),
+                ("impedance_tables", array_of(reference("ImpedanceTable"))),
+                ("line_groups", array_of(reference("LineGroup"))),
+            ],
+            &[
+                "passthrough",
+                "dc_lines",
+                "dc_buses",
+                "dc_branches",
+                "vsc_converters",
+                "facts_devices",
+                "impedance_tables",
+                "line_groups",
+            ],
+        ),
+    );
+    defs.insert(
+        "Metadata",
+        object(
+            vec![
+                ("writer", string()),
+                ("created", integer()),
+                ("description", string()),
+            ],
+            &[],
+        ),
+    );
+
+    let mut schema = object(
+        vec![
+            ("format", json!({"const": FORMAT})),
+            ("schema_version", json!({"const": SCHEMA_VERSION})),
+            ("metadata", reference("Metadata")),
+            (
+                "checksum",
+                json!({"type": ["integer", "null"], "minimum": 0, "maximum": u32::MAX}),
+            ),
+            ("network", reference("Network")),
+        ],
+        &["metadata", "checksum"],
+    );
+    schema["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+    schema["title"] = json!(format!("mantis case, schema version {}", SCHEMA_VERSION));
+    schema["$defs"] = json!(defs);
+    schema
+}
+
+/// Writes `json_schema()` to disk.
+pub fn write_json_schema(path: &str) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(&json_schema())
+        .map_err(|e| format!("Error serializing: {}", e))?;
+    std::fs::write(path, text).map_err(|e| format!("Error writing file: {}", e))
+}
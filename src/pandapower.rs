@@ -0,0 +1,1216 @@
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+
+use crate::case::*;
+use crate::rawx::num;
+
+/// A pandas DataFrame stored in split orientation.
+struct Frame {
+    columns: Vec<String>,
+    index: Vec<Value>,
+    data: Vec<Vec<Value>>,
+}
+
+impl Frame {
+    /// Decodes a table of a pandapower network; the split JSON is usually
+    /// embedded as a string.
+    fn from_net(net: &Map<String, Value>, name: &str) -> Result<Option<Self>, String> {
+        let Some(table) = net.get(name) else {
+            return Ok(None);
+        };
+        let decoded;
+        let object = match table.get("_object") {
+            Some(Value::String(text)) => {
+                decoded = serde_json::from_str::<Value>(text)
+                    .map_err(|e| format!("Bad pandapower {} table: {}", name, e))?;
+                &decoded
+            }
+            Some(object) => object,
+            None => return Ok(None),
+        };
+        let strings = |key: &str| -> Vec<Value> {
+            object
+                .get(key)
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+        };
+        Ok(Some(Self {
+            columns: strings("columns")
+                .iter()
+                .map(|c| c.as_str().unwrap_or("").to_string())
+                .collect(),
+            index: strings("index"),
+            data: strings("data")
+                .into_iter()
+                .map(|row| row.as_array().cloned().unwrap_or_default())
+                .collect(),
+        }))
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (usize, &[Value])> {
+        self.index
+            .iter()
+            .zip(&self.data)
+            .map(|(i, row)| (i.as_u64().unwrap_or(0) as usize, row.as_slice()))
+    }
+
+    /// A non-null value of the named column.
+    fn value<'a>(&self, row: &'a [Value], column: &str) -> Option<&'a Value> {
+        let i = self.columns.iter().position(|c| c == column)?;
+        row.get(i).filter(|v| !v.is_null())
+    }
+
+    fn num(&self, row: &[Value], column: &str) -> Option<f32> {
+        match self.value(row, column)? {
+            Value::Number(n) => n.to_string().parse().ok(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn flag(&self, row: &[Value], column: &str, default: bool) -> bool {
+        self.value(row, column)
+            .and_then(Value::as_bool)
+            .unwrap_or(default)
+    }
+
+    fn text(&self, row: &[Value], column: &str) -> Option<String> {
+        match self.value(row, column)? {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Builds a DataFrame entry in the layout pandapower writes.
+fn frame(columns: &[(&str, &str)], index: Vec<usize>, data: Vec<Vec<Value>>) -> Value {
+    let names: Vec<&str> = columns.iter().map(|(name, _)| *name).collect();
+    let dtype: Map<String, Value> = columns
+        .iter()
+        .map(|(name, dtype)| (name.to_string(), Value::from(*dtype)))
+        .collect();
+    json!({
+        "_module": "pandas.core.frame",
+        "_class": "DataFrame",
+        "_object": json!({"columns": names, "index": index, "data": data}).to_string(),
+        "orient": "split",
+        "dtype": dtype,
+    })
+}
+
+/// Element name in the mantis "Bus{n}-{id}" form.
+fn element_name(bus_id: usize, name: Option<String>, ordinal: usize) -> String {
+    let prefix = format!("Bus{}-", bus_id);
+    match name.filter(|n| !n.is_empty()) {
+        Some(n) if n.starts_with(&prefix) => n,
+        Some(n) => format!("{}{}", prefix, n),
+        None => format!("{}{}", prefix, ordinal),
+    }
+}
+
+/// The bus a bus has been merged into by closed bus-bus switches.
+fn merged_bus(fused: &HashMap<usize, usize>, mut bus: usize) -> usize {
+    while let Some(&next) = fused.get(&bus) {
+        bus = next;
+    }
+    bus
+}
+
+/// Reads a pandapower JSON network into a Network.
+pub fn read_pandapower(path: &str) -> Result<Network, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_pandapower_str(&content)
+}
+
+/// Whether a JSON document is a pandapower network rather than a mantis one.
+pub fn is_pandapower(content: &str) -> bool {
+    content.contains("\"pandapowerNet\"")
+}
+
+/// Element tables mantis has no model for; a network using them is rejected.
+const UNSUPPORTED: [&str; 12] = [
+    "xward",
+    "storage",
+    "motor",
+    "asymmetric_load",
+    "asymmetric_sgen",
+    "svc",
+    "tcsc",
+    "ssc",
+    "vsc",
+    "bus_dc",
+    "line_dc",
+    "source_dc",
+];
+
+/// Parses a pandapower JSON network. Bus indices become bus ids; lines,
+/// impedances and transformers are converted from physical units to per unit
+/// on `sn_mva`, ext_grid and slack gens make slack buses, gens PV buses and
+/// sgens fixed injections. Transformer taps at `tap_pos` set the off-nominal
+/// ratio and three-winding transformers go through a star point bus. Closed
+/// bus-bus switches merge their buses, and open line or transformer switches
+/// take the element out of service. Wards become ZIP loads and DC lines a
+/// pair of generators, as pandapower models them.
+pub fn parse_pandapower_str(content: &str) -> Result<Network, String> {
+    let root: Value = serde_json::from_str(content).map_err(|e| format!("Bad JSON: {}", e))?;
+    let net = root
+        .get("_object")
+        .and_then(Value::as_object)
+        .ok_or("Not a pandapower network")?;
+    for name in UNSUPPORTED {
+        if Frame::from_net(net, name)?.is_some_and(|t| !t.data.is_empty()) {
+            return Err(format!("pandapower {} elements are not supported", name));
+        }
+    }
+    let scalar = |key: &str, default: f32| {
+        net.get(key)
+            .and_then(Value::as_f64)
+            .map(|v| v as f32)
+            .unwrap_or(default)
+    };
+    let name = net
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string();
+    let mut network = Network::new(name, scalar("sn_mva", 1.0), scalar("f_hz", 50.0));
+    let s_base = network.s_base;
+    let omega = 2.0 * PI * network.frequency;
+
+    let bus_table = Frame::from_net(net, "bus")?.ok_or("pandapower network without buses")?;
+    for (bus_id, row) in bus_table.rows() {
+        let mut bus = Bus::new(
+            bus_id,
+            bus_table.text(row, "name").unwrap_or_default(),
+            BusType::PQ,
+        );
+        bus.nom_voltage = bus_table.num(row, "vn_kv").unwrap_or(0.0);
+        if let Some(zone) = bus_table.num(row, "zone") {
+            bus.zone = zone as usize;
+        }
+        if let Some(v) = bus_table.num(row, "max_vm_pu") {
+            bus.v_max_operating = v;
+        }
+        if let Some(v) = bus_table.num(row, "min_vm_pu") {
+            bus.v_min_operating = v;
+        }
+        if !bus_table.flag(row, "in_service", true) {
+            bus.bus_status = false;
+            bus.bus_type = BusType::OUT;
+        }
+        network.buses.push(bus);
+    }
+
+    // Closed bus-bus switches join their buses into the first one listed;
+    // open element switches disconnect the element
+    let mut fused: HashMap<usize, usize> = HashMap::new();
+    let mut opened: Vec<(String, usize)> = Vec::new();
+    if let Some(t) = Frame::from_net(net, "switch")? {
+        for (i, row) in t.rows() {
+            let closed = t.flag(row, "closed", true);
+            let bus = t.num(row, "bus").map(|b| b as usize);
+            let element = t.num(row, "element").map(|e| e as usize);
+            let (Some(bus), Some(element)) = (bus, element) else {
+                return Err(format!("pandapower switch {} without bus or element", i));
+            };
+            match t.text(row, "et").as_deref() {
+                Some("b") if closed => {
+                    if t.num(row, "z_ohm").is_some_and(|z| z != 0.0) {
+                        return Err(format!(
+                            "pandapower switch {}: switches with an impedance are not supported",
+                            i
+                        ));
+                    }
+                    let position = |b: usize| network.buses.iter().position(|x| x.bus_id == b);
+                    let (a, b) = (merged_bus(&fused, bus), merged_bus(&fused, element));
+                    match (position(a), position(b)) {
+                        (Some(pa), Some(pb)) if pa < pb => {
+                            fused.insert(b, a);
+                        }
+                        (Some(_), Some(_)) if a != b => {
+                            fused.insert(a, b);
+                        }
+                        (Some(_), Some(_)) => {}
+                        _ => return Err(format!("pandapower switch {} at unknown bus", i)),
+                    }
+                }
+                Some("b") => {}
+                Some(kind @ ("l" | "t" | "t3")) => {
+                    if !closed {
+                        opened.push((kind.to_string(), element));
+                    }
+                }
+                other => {
+                    return Err(format!(
+                        "pandapower switch {}: unknown element type {:?}",
+                        i, other
+                    ));
+                }
+            }
+        }
+    }
+    network.buses.retain(|b| !fused.contains_key(&b.bus_id));
+    let is_open =
+        |kind: &str, element: usize| opened.iter().any(|(k, e)| k == kind && *e == element);
+
+    let index: HashMap<usize, usize> = network
+        .buses
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.bus_id, i))
+        .collect();
+    let bus_of = |table: &Frame, row: &[Value], column: &str| -> Result<usize, String> {
+        table
+            .num(row, column)
+            .map(|b| merged_bus(&fused, b as usize))
+            .filter(|b| index.contains_key(b))
+            .ok_or_else(|| format!("pandapower element at unknown {}", column))
+    };
+    let kv = |network: &Network, bus_id: usize| match network.buses[index[&bus_id]].nom_voltage {
+        v if v > 0.0 => v,
+        _ => 1.0,
+    };
+    let mut per_bus: HashMap<usize, usize> = HashMap::new();
+    let mut ordinal = |bus_id: usize| {
+        let count = per_bus.entry(bus_id).or_insert(0);
+        *count += 1;
+        *count
+    };
+
+    // Slack sources and voltage-controlling generators
+    for kind in ["ext_grid", "gen", "sgen"] {
+        let Some(t) = Frame::from_net(net, kind)? else {
+            continue;
+        };
+        for (_, row) in t.rows() {
+            let bus_id = bus_of(&t, row, "bus")?;
+            let n = ordinal(bus_id);
+            let mut generator = Generator::new(
+                network.generators.len(),
+                bus_id,
+                element_name(bus_id, t.text(row, "name"), n),
+            );
+            let scaling = t.num(row, "scaling").unwrap_or(1.0);
+            generator.gen_status = t.flag(row, "in_service", true);
+            generator.p_gen = t.num(row, "p_mw").unwrap_or(0.0) * scaling;
+            generator.q_gen = t.num(row, "q_mvar").unwrap_or(0.0) * scaling;
+            generator.p_max = t.num(row, "max_p_mw").unwrap_or(9999.0);
+            generator.p_min = t.num(row, "min_p_mw").unwrap_or(-9999.0);
+            generator.q_max = t.num(row, "max_q_mvar").unwrap_or(9999.0);
+            generator.q_min = t.num(row, "min_q_mvar").unwrap_or(-9999.0);
+
+            let bus = &mut network.buses[index[&bus_id]];
+            if kind != "sgen" {
+                let vm = t.num(row, "vm_pu").unwrap_or(1.0);
+                generator.v_setpoint = vm;
+                if bus.bus_status {
+                    bus.voltage = vm;
+                    if kind == "ext_grid" || t.flag(row, "slack", false) {
+                        bus.bus_type = BusType::Slack;
+                    } else if bus.bus_type != BusType::Slack {
+                        bus.bus_type = BusType::PV;
+                    }
+                }
+                if let Some(angle) = t.num(row, "va_degree") {
+                    bus.angle = angle;
+                }
+            }
+            network.generators.push(generator);
+        }
+    }
+
+    if let Some(t) = Frame::from_net(net, "load")? {
+        let mut per_bus: HashMap<usize, usize> = HashMap::new();
+        for (_, row) in t.rows() {
+            let bus_id = bus_of(&t, row, "bus")?;
+            let count = per_bus.entry(bus_id).or_insert(0);
+            *count += 1;
+            let scaling = t.num(row, "scaling").unwrap_or(1.0);
+            let p = t.num(row, "p_mw").unwrap_or(0.0) * scaling;
+            let q = t.num(row, "q_mvar").unwrap_or(0.0) * scaling;
+            // Percentages of p_mw and q_mvar at 1 pu that are constant
+            // impedance and constant current; the rest is constant power
+            let z = t.num(row, "const_z_percent").unwrap_or(0.0) / 100.0;
+            let i = t.num(row, "const_i_percent").unwrap_or(0.0) / 100.0;
+            let mut load = Load::new(
+                network.loads.len(),
+                bus_id,
+                element_name(bus_id, t.text(row, "name"), *count),
+                p * (1.0 - z - i),
+                q * (1.0 - z - i),
+            );
+            load.real_current = p * i;
+            load.imag_current = q * i;
+            load.real_admittance = p * z;
+            load.imag_admittance = q * z;
+            load.load_status = t.flag(row, "in_service", true);
+            network.loads.push(load);
+        }
+    }
+
+    if let Some(t) = Frame::from_net(net, "shunt")? {
+        for (_, row) in t.rows() {
+            let bus_id = bus_of(&t, row, "bus")?;
+            if !t.flag(row, "in_service", true) {
+                continue;
+            }
+            // Shunt power is consumed power at rated voltage
+            let step = t.num(row, "step").unwrap_or(1.0);
+            let bus_kv = kv(&network, bus_id);
+            let scale = match t.num(row, "vn_kv") {
+                Some(vn) if vn > 0.0 => (bus_kv / vn).powi(2),
+                _ => 1.0,
+            };
+            let bus = &mut network.buses[index[&bus_id]];
+            bus.real_shunt += t.num(row, "p_mw").unwrap_or(0.0) * step * scale;
+            bus.imag_shunt -= t.num(row, "q_mvar").unwrap_or(0.0) * step * scale;
+        }
+    }
+
+    // Wards are constant power plus constant impedance loads
+    if let Some(t) = Frame::from_net(net, "ward")? {
+        for (_, row) in t.rows() {
+            let bus_id = bus_of(&t, row, "bus")?;
+            let part = |column: &str| t.num(row, column).unwrap_or(0.0);
+            let mut load = Load::new(
+                network.loads.len(),
+                bus_id,
+                element_name(bus_id, t.text(row, "name"), ordinal(bus_id)),
+                part("ps_mw"),
+                part("qs_mvar"),
+            );
+            load.real_admittance = part("pz_mw");
+            load.imag_admittance = part("qz_mvar");
+            load.load_status = t.flag(row, "in_service", true);
+            network.loads.push(load);
+        }
+    }
+
+    // A DC line draws p_mw at its from end and delivers it less losses at
+    // its to end, with both ends holding their voltage
+    if let Some(t) = Frame::from_net(net, "dcline")? {
+        for (_, row) in t.rows() {
+            let value = |column: &str, default: f32| t.num(row, column).unwrap_or(default);
+            let p = value("p_mw", 0.0);
+            let delivered = p * (1.0 - value("loss_percent", 0.0) / 100.0) - value("loss_mw", 0.0);
+            for (end, p_gen, p_limit) in [
+                ("from", -p, -value("max_p_mw", 9999.0)),
+                ("to", delivered, value("max_p_mw", 9999.0)),
+            ] {
+                let bus_id = bus_of(&t, row, &format!("{}_bus", end))?;
+                let mut generator = Generator::new(
+                    network.generators.len(),
+                    bus_id,
+                    element_name(bus_id, t.text(row, "name"), ordinal(bus_id)),
+                );
+                generator.gen_status = t.flag(row, "in_service", true);
+                generator.p_gen = p_gen;
+                (generator.p_min, generator.p_max) = (p_limit.min(0.0), p_limit.max(0.0));
+                generator.q_min = value(&format!("min_q_{}_mvar", end), -9999.0);
+                generator.q_max = value(&format!("max_q_{}_mvar", end), 9999.0);
+                generator.v_setpoint = value(&format!("vm_{}_pu", end), 1.0);
+                let bus = &mut network.buses[index[&bus_id]];
+                if bus.bus_type == BusType::PQ && generator.gen_status {
+                    bus.voltage = generator.v_setpoint;
+                    bus.bus_type = BusType::PV;
+                }
+                network.generators.push(generator);
+            }
+        }
+    }
+
+    if let Some(t) = Frame::from_net(net, "line")? {
+        for (i, row) in t.rows() {
+            let from_bus = bus_of(&t, row, "from_bus")?;
+            let to_bus = bus_of(&t, row, "to_bus")?;
+            let bus_kv = kv(&network, from_bus);
+            let z_base = bus_kv * bus_kv / s_base;
+            let length = t.num(row, "length_km").unwrap_or(1.0);
+            let parallel = t.num(row, "parallel").unwrap_or(1.0).max(1.0);
+            let per_km = |column: &str| t.num(row, column).unwrap_or(0.0) * length;
+            let b = omega * per_km("c_nf_per_km") * 1e-9 * parallel * z_base;
+            let g = per_km("g_us_per_km") * 1e-6 * parallel * z_base;
+            let rating = 3f32.sqrt()
+                * bus_kv
+                * t.num(row, "max_i_ka").unwrap_or(0.0)
+                * parallel
+                * t.num(row, "df").unwrap_or(1.0);
+
+            network.branches.push(Branch {
+                branch_type: BranchType::Line,
+                id: network.branches.len(),
+                from_bus,
+                to_bus,
+                ckt: String::new(),
+                branch_name: t.text(row, "name").unwrap_or_else(|| i.to_string()),
+                branch_status: t.flag(row, "in_service", true) && !is_open("l", i),
+                resistance: per_km("r_ohm_per_km") / parallel / z_base,
+                reactance: per_km("x_ohm_per_km") / parallel / z_base,
+                from_shunt_conductance: g / 2.0,
+                from_shunt_susceptance: b / 2.0,
+                to_shunt_conductance: g / 2.0,
+                to_shunt_susceptance: b / 2.0,
+                tap_ratio: 1.0,
+                phase_shift: 0.0,
+                impedance_table: None,
+                operating_limit: rating,
+                contingency_limit: rating,
+                flow: 0.0,
+            });
+        }
+    }
+
+    if let Some(t) = Frame::from_net(net, "trafo")? {
+        for (i, row) in t.rows() {
+            let from_bus = bus_of(&t, row, "hv_bus")?;
+            let to_bus = bus_of(&t, row, "lv_bus")?;
+            let parallel = t.num(row, "parallel").unwrap_or(1.0).max(1.0);
+            let sn = t.num(row, "sn_mva").unwrap_or(s_base) * parallel;
+            let mut vn_hv = t.num(row, "vn_hv_kv").unwrap_or(kv(&network, from_bus));
+            let mut vn_lv = t.num(row, "vn_lv_kv").unwrap_or(kv(&network, to_bus));
+            let mut shift = t.num(row, "shift_degree").unwrap_or(0.0);
+
+            // Tap position away from neutral
+            if let (Some(pos), Some(neutral)) = (t.num(row, "tap_pos"), t.num(row, "tap_neutral")) {
+                let steps = pos - neutral;
+                let factor = 1.0 + steps * t.num(row, "tap_step_percent").unwrap_or(0.0) / 100.0;
+                match t.text(row, "tap_side").as_deref() {
+                    Some("hv") => vn_hv *= factor,
+                    Some("lv") => vn_lv *= factor,
+                    _ => {}
+                }
+                if t.flag(row, "tap_phase_shifter", false) {
+                    shift += steps * t.num(row, "tap_step_degree").unwrap_or(0.0);
+                }
+            }
+
+            // Short-circuit impedance referred to the low voltage side
+            let lv_scale = (vn_lv / kv(&network, to_bus)).powi(2) * s_base / sn;
+            let z = t.num(row, "vk_percent").unwrap_or(0.0) / 100.0 * lv_scale;
+            let r = t.num(row, "vkr_percent").unwrap_or(0.0) / 100.0 * lv_scale;
+            // Magnetizing admittance from no-load losses and current
+            let g = t.num(row, "pfe_kw").unwrap_or(0.0) / 1000.0 / s_base;
+            let y = t.num(row, "i0_percent").unwrap_or(0.0) / 100.0 * sn / s_base;
+
+            network.branches.push(Branch {
+                branch_type: BranchType::TwoWinding,
+                id: network.branches.len(),
+                from_bus,
+                to_bus,
+                ckt: String::new(),
+                branch_name: t.text(row, "name").unwrap_or_else(|| i.to_string()),
+                branch_status: t.flag(row, "in_service", true) && !is_open("t", i),
+                resistance: r,
+                // A negative vk_percent gives a capacitive reactance
+                reactance: z.signum() * (z * z - r * r).max(0.0).sqrt(),
+                from_shunt_conductance: g,
+                from_shunt_susceptance: -(y * y - g * g).max(0.0).sqrt(),
+                to_shunt_conductance: 0.0,
+                to_shunt_susceptance: 0.0,
+                tap_ratio: (vn_hv / kv(&network, from_bus)) / (vn_lv / kv(&network, to_bus)),
+                phase_shift: shift,
+                impedance_table: None,
+                operating_limit: sn,
+                contingency_limit: sn,
+                flow: 0.0,
+            });
+        }
+    }
+
+    // Impedances are in per unit on their own sn_mva and the bus voltages
+    if let Some(t) = Frame::from_net(net, "impedance")? {
+        for (i, row) in t.rows() {
+            let from_bus = bus_of(&t, row, "from_bus")?;
+            let to_bus = bus_of(&t, row, "to_bus")?;
+            let value = |column: &str| t.num(row, column).unwrap_or(0.0);
+            if value("rft_pu") != value("rtf_pu") || value("xft_pu") != value("xtf_pu") {
+                return Err(format!(
+                    "pandapower impedance {}: asymmetric impedances are not supported",
+                    i
+                ));
+            }
+            let to_system = s_base / t.num(row, "sn_mva").unwrap_or(s_base);
+            network.branches.push(Branch {
+                branch_type: BranchType::Line,
+                id: network.branches.len(),
+                from_bus,
+                to_bus,
+                ckt: String::new(),
+                branch_name: t.text(row, "name").unwrap_or_else(|| i.to_string()),
+                branch_status: t.flag(row, "in_service", true),
+                resistance: value("rft_pu") * to_system,
+                reactance: value("xft_pu") * to_system,
+                from_shunt_conductance: value("gf_pu") / to_system,
+                from_shunt_susceptance: value("bf_pu") / to_system,
+                to_shunt_conductance: value("gt_pu") / to_system,
+                to_shunt_susceptance: value("bt_pu") / to_system,
+                tap_ratio: 1.0,
+                phase_shift: 0.0,
+                impedance_table: None,
+                operating_limit: 0.0,
+                contingency_limit: 0.0,
+                flow: 0.0,
+            });
+        }
+    }
+
+    if let Some(t) = Frame::from_net(net, "trafo3w")? {
+        for (i, row) in t.rows() {
+            if t.flag(row, "tap_at_star_point", false) {
+                return Err(format!(
+                    "pandapower trafo3w {}: taps at the star point are not supported",
+                    i
+                ));
+            }
+            let value = |column: &str, default: f32| t.num(row, column).unwrap_or(default);
+            let mut windings = Vec::new();
+            for side in ["hv", "mv", "lv"] {
+                let bus_id = bus_of(&t, row, &format!("{}_bus", side))?;
+                let bus_kv = kv(&network, bus_id);
+                windings.push((
+                    bus_id,
+                    bus_kv,
+                    value(&format!("vn_{}_kv", side), bus_kv),
+                    value(&format!("sn_{}_mva", side), s_base),
+                ));
+            }
+
+            // Short-circuit impedances hv-mv, mv-lv and lv-hv on the smaller
+            // rating of each pair, then split into star impedances
+            let pair = |a: usize, b: usize, side: &str| {
+                let scale = s_base / windings[a].3.min(windings[b].3);
+                let z = value(&format!("vk_{}_percent", side), 0.0) / 100.0 * scale;
+                let r = value(&format!("vkr_{}_percent", side), 0.0) / 100.0 * scale;
+                (r, z.signum() * (z * z - r * r).max(0.0).sqrt())
+            };
+            let (hm, ml, lh) = (pair(0, 1, "hv"), pair(1, 2, "mv"), pair(2, 0, "lv"));
+            let half = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| {
+                ((a.0 + b.0 - c.0) / 2.0, (a.1 + b.1 - c.1) / 2.0)
+            };
+            let star_z = [half(hm, lh, ml), half(hm, ml, lh), half(ml, lh, hm)];
+
+            // The star point sits at the nominal high voltage
+            let star_kv = windings[0].2;
+            let mut vn: Vec<f32> = windings.iter().map(|w| w.2).collect();
+            let mut shift = [
+                0.0,
+                value("shift_mv_degree", 0.0),
+                value("shift_lv_degree", 0.0),
+            ];
+            if let (Some(pos), Some(neutral)) = (t.num(row, "tap_pos"), t.num(row, "tap_neutral")) {
+                let steps = pos - neutral;
+                let side = match t.text(row, "tap_side").as_deref() {
+                    Some("hv") => Some(0),
+                    Some("mv") => Some(1),
+                    Some("lv") => Some(2),
+                    _ => None,
+                };
+                if let Some(side) = side {
+                    vn[side] *= 1.0 + steps * value("tap_step_percent", 0.0) / 100.0;
+                    shift[side] += steps * value("tap_step_degree", 0.0);
+                }
+            }
+
+            let star_id = network.buses.iter().map(|b| b.bus_id).max().unwrap_or(0) + 1;
+            let name = t.text(row, "name").unwrap_or_else(|| i.to_string());
+            let status = t.flag(row, "in_service", true) && !is_open("t3", i);
+            let g = value("pfe_kw", 0.0) / 1000.0 / s_base;
+            let y = value("i0_percent", 0.0) / 100.0 * windings[0].3 / s_base;
+            for (w, (&(bus_id, bus_kv, _, sn), (r, x))) in windings.iter().zip(star_z).enumerate() {
+                let hv = w == 0;
+                network.branches.push(Branch {
+                    branch_type: BranchType::TwoWinding,
+                    id: network.branches.len(),
+                    from_bus: bus_id,
+                    to_bus: star_id,
+                    ckt: String::new(),
+                    branch_name: name.clone(),
+                    branch_status: status,
+                    resistance: r,
+                    reactance: x,
+                    // Magnetizing admittance at the high voltage winding
+                    from_shunt_conductance: if hv { g } else { 0.0 },
+                    from_shunt_susceptance: if hv {
+                        -(y * y - g * g).max(0.0).sqrt()
+                    } else {
+                        0.0
+                    },
+                    to_shunt_conductance: 0.0,
+                    to_shunt_susceptance: 0.0,
+                    tap_ratio: vn[w] / bus_kv,
+                    // Each winding lags the high voltage one by its shift
+                    phase_shift: shift[0] - shift[w],
+                    impedance_table: None,
+                    operating_limit: sn,
+                    contingency_limit: sn,
+                    flow: 0.0,
+                });
+            }
+            let mut star = Bus::new(star_id, name, BusType::PQ);
+            star.nom_voltage = star_kv;
+            network.buses.push(star);
+        }
+    }
+
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// Formats a Network as a pandapower JSON network. Slack generators become
+/// gens with `slack` set, generators at PQ buses sgens, and bus shunts shunt
+/// elements; lines are written with a 1 km length and transformers with their
+/// off-nominal ratio as one high voltage tap step. Star point buses fed by
+/// three transformer windings are written as trafo3w elements. Load ZIP
+/// percentages follow the active power split.
+pub fn format_pandapower(network: &Network) -> String {
+    let s_base = network.s_base;
+    let omega = 2.0 * PI * network.frequency;
+    let bus_kv: HashMap<usize, f32> = network
+        .buses
+        .iter()
+        .map(|b| {
+            (
+                b.bus_id,
+                if b.nom_voltage > 0.0 {
+                    b.nom_voltage
+                } else {
+                    1.0
+                },
+            )
+        })
+        .collect();
+    let kv = |bus_id: usize| bus_kv.get(&bus_id).copied().unwrap_or(1.0);
+    let bus_type: HashMap<usize, BusType> = network
+        .buses
+        .iter()
+        .map(|b| (b.bus_id, b.bus_type))
+        .collect();
+
+    let stars = three_winding_stars(network);
+    let buses: Vec<&Bus> = network
+        .buses
+        .iter()
+        .filter(|b| !stars.iter().any(|(star, _)| *star == b.bus_id))
+        .collect();
+    let buses_frame = frame(
+        &[
+            ("name", "object"),
+            ("vn_kv", "float64"),
+            ("type", "object"),
+            ("zone", "object"),
+            ("in_service", "bool"),
+            ("max_vm_pu", "float64"),
+            ("min_vm_pu", "float64"),
+        ],
+        buses.iter().map(|b| b.bus_id).collect(),
+        buses
+            .iter()
+            .map(|b| {
+                vec![
+                    Value::from(b.bus_name.clone()),
+                    num(b.nom_voltage),
+                    Value::from("b"),
+                    Value::from(b.zone),
+                    Value::from(b.bus_status),
+                    num(b.v_max_operating),
+                    num(b.v_min_operating),
+                ]
+            })
+            .collect(),
+    );
+
+    let loads = frame(
+        &[
+            ("name", "object"),
+            ("bus", "uint32"),
+            ("p_mw", "float64"),
+            ("q_mvar", "float64"),
+            ("const_z_percent", "float64"),
+            ("const_i_percent", "float64"),
+            ("sn_mva", "float64"),
+            ("scaling", "float64"),
+            ("in_service", "bool"),
+            ("type", "object"),
+        ],
+        (0..network.loads.len()).collect(),
+        network
+            .loads
+            .iter()
+            .map(|l| {
+                // pandapower splits P and Q alike, so the split follows P
+                // unless the load has none
+                let (p, q) = l.power_at(1.0);
+                let (total, current, admittance) = if p != 0.0 {
+                    (p, l.real_current, l.real_admittance)
+                } else {
+                    (q, l.imag_current, l.imag_admittance)
+                };
+                let percent = |part: f32| {
+                    if total == 0.0 {
+                        0.0
+                    } else {
+                        part / total * 100.0
+                    }
+                };
+                vec![
+                    Value::from(l.load_ident.as_str()),
+                    Value::from(l.bus_id),
+                    num(p),
+                    num(q),
+                    num(percent(admittance)),
+                    num(percent(current)),
+                    Value::Null,
+                    Value::from(1.0),
+                    Value::from(l.load_status),
+                    Value::from("wye"),
+                ]
+            })
+            .collect(),
+    );
+
+    let (controlled, fixed): (Vec<&Generator>, Vec<&Generator>) =
+        network.generators.iter().partition(|g| {
+            matches!(
+                bus_type.get(&g.gen_bus_id),
+                Some(BusType::Slack | BusType::PV)
+            )
+        });
+    let gens = frame(
+        &[
+            ("name", "object"),
+            ("bus", "uint32"),
+            ("p_mw", "float64"),
+            ("vm_pu", "float64"),
+            ("sn_mva", "float64"),
+            ("min_q_mvar", "float64"),
+            ("max_q_mvar", "float64"),
+            ("scaling", "float64"),
+            ("slack", "bool"),
+            ("in_service", "bool"),
+            ("type", "object"),
+            ("controllable", "bool"),
+            ("min_p_mw", "float64"),
+            ("max_p_mw", "float64"),
+        ],
+        (0..controlled.len()).collect(),
+        controlled
+            .iter()
+            .map(|g| {
+                vec![
+                    Value::from(g.machine_id.as_str()),
+                    Value::from(g.gen_bus_id),
+                    num(g.p_gen),
+                    num(g.v_setpoint),
+                    Value::Null,
+                    num(g.q_min),
+                    num(g.q_max),
+                    Value::from(1.0),
+                    Value::from(bus_type.get(&g.gen_bus_id) == Some(&BusType::Slack)),
+                    Value::from(g.gen_status),
+                    Value::Null,
+                    Value::from(true),
+                    num(g.p_min),
+                    num(g.p_max),
+                ]
+            })
+            .collect(),
+    );
+    let sgens = frame(
+        &[
+            ("name", "object"),
+            ("bus", "uint32"),
+            ("p_mw", "float64"),
+            ("q_mvar", "float64"),
+            ("sn_mva", "float64"),
+            ("scaling", "float64"),
+            ("in_service", "bool"),
+            ("type", "object"),
+            ("current_source", "bool"),
+            ("min_p_mw", "float64"),
+            ("max_p_mw", "float64"),
+            ("min_q_mvar", "float64"),
+            ("max_q_mvar", "float64"),
+        ],
+        (0..fixed.len()).collect(),
+        fixed
+            .iter()
+            .map(|g| {
+                vec![
+                    Value::from(g.machine_id.as_str()),
+                    Value::from(g.gen_bus_id),
+                    num(g.p_gen),
+                    num(g.q_gen),
+                    Value::Null,
+                    Value::from(1.0),
+                    Value::from(g.gen_status),
+                    Value::Null,
+                    Value::from(false),
+                    num(g.p_min),
+                    num(g.p_max),
+                    num(g.q_min),
+                    num(g.q_max),
+                ]
+            })
+            .collect(),
+    );
+
+    let shunt_buses: Vec<&Bus> = network
+        .buses
+        .iter()
+        .filter(|b| b.real_shunt != 0.0 || b.imag_shunt != 0.0)
+        .collect();
+    let shunts = frame(
+        &[
+            ("bus", "uint32"),
+            ("name", "object"),
+            ("q_mvar", "float64"),
+            ("p_mw", "float64"),
+            ("vn_kv", "float64"),
+            ("step", "uint32"),
+            ("max_step", "uint32"),
+            ("in_service", "bool"),
+        ],
+        (0..shunt_buses.len()).collect(),
+        shunt_buses
+            .iter()
+            .map(|b| {
+                vec![
+                    Value::from(b.bus_id),
+                    Value::Null,
+                    num(-b.imag_shunt),
+                    num(b.real_shunt),
+                    num(kv(b.bus_id)),
+                    Value::from(1),
+                    Value::from(1),
+                    Value::from(true),
+                ]
+            })
+            .collect(),
+    );
+
+    let legs: Vec<usize> = stars.iter().flat_map(|(_, legs)| *legs).collect();
+    let (lines, trafos): (Vec<&Branch>, Vec<&Branch>) = network
+        .branches
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !legs.contains(i))
+        .map(|(_, br)| br)
+        .partition(|br| br.branch_type == BranchType::Line);
+    let lines_frame = frame(
+        &[
+            ("name", "object"),
+            ("std_type", "object"),
+            ("from_bus", "uint32"),
+            ("to_bus", "uint32"),
+            ("length_km", "float64"),
+            ("r_ohm_per_km", "float64"),
+            ("x_ohm_per_km", "float64"),
+            ("c_nf_per_km", "float64"),
+            ("g_us_per_km", "float64"),
+            ("max_i_ka", "float64"),
+            ("df", "float64"),
+            ("parallel", "uint32"),
+            ("type", "object"),
+            ("in_service", "bool"),
+        ],
+        lines.iter().map(|br| br.id).collect(),
+        lines
+            .iter()
+            .map(|br| {
+                let bus_kv = kv(br.from_bus);
+                let z_base = bus_kv * bus_kv / s_base;
+                let b = br.from_shunt_susceptance + br.to_shunt_susceptance;
+                let g = br.from_shunt_conductance + br.to_shunt_conductance;
+                vec![
+                    Value::from(br.branch_name.trim()),
+                    Value::Null,
+                    Value::from(br.from_bus),
+                    Value::from(br.to_bus),
+                    Value::from(1.0),
+                    num(br.resistance * z_base),
+                    num(br.reactance * z_base),
+                    num(b / z_base / omega * 1e9),
+                    num(g / z_base * 1e6),
+                    num(br.operating_limit / (3f32.sqrt() * bus_kv)),
+                    Value::from(1.0),
+                    Value::from(1),
+                    Value::from("ol"),
+                    Value::from(br.branch_status),
+                ]
+            })
+            .collect(),
+    );
+
+    let trafos_frame = frame(
+        &[
+            ("name", "object"),
+            ("std_type", "object"),
+            ("hv_bus", "uint32"),
+            ("lv_bus", "uint32"),
+            ("sn_mva", "float64"),
+            ("vn_hv_kv", "float64"),
+            ("vn_lv_kv", "float64"),
+            ("vk_percent", "float64"),
+            ("vkr_percent", "float64"),
+            ("pfe_kw", "float64"),
+            ("i0_percent", "float64"),
+            ("shift_degree", "float64"),
+            ("tap_side", "object"),
+            ("tap_neutral", "float64"),
+            ("tap_min", "float64"),
+            ("tap_max", "float64"),
+            ("tap_step_percent", "float64"),
+            ("tap_step_degree", "float64"),
+            ("tap_pos", "float64"),
+            ("tap_phase_shifter", "bool"),
+            ("parallel", "uint32"),
+            ("df", "float64"),
+            ("in_service", "bool"),
+        ],
+        trafos.iter().map(|br| br.id).collect(),
+        trafos
+            .iter()
+            .map(|br| {
+                let sn = if br.operating_limit > 0.0 {
+                    br.operating_limit
+                } else {
+                    s_base
+                };
+                let to_base = sn / s_base;
+                let z = br.resistance.hypot(br.reactance).copysign(br.reactance);
+                let g = br.from_shunt_conductance;
+                let y = g.hypot(br.from_shunt_susceptance);
+                // An off-nominal ratio is one tap step on the high voltage side
+                let (side, neutral, min, max, step, pos) = if br.tap_ratio != 1.0 {
+                    (
+                        Value::from("hv"),
+                        Value::from(0.0),
+                        Value::from(-1.0),
+                        Value::from(1.0),
+                        num((br.tap_ratio - 1.0).abs() * 100.0),
+                        Value::from((br.tap_ratio - 1.0).signum()),
+                    )
+                } else {
+                    (
+                        Value::Null,
+                        Value::Null,
+                        Value::Null,
+                        Value::Null,
+                        Value::Null,
+                        Value::Null,
+                    )
+                };
+                vec![
+                    Value::from(br.branch_name.trim()),
+                    Value::Null,
+                    Value::from(br.from_bus),
+                    Value::from(br.to_bus),
+                    num(sn),
+                    num(kv(br.from_bus)),
+                    num(kv(br.to_bus)),
+                    num(z * 100.0 * to_base),
+                    num(br.resistance * 100.0 * to_base),
+                    num(g * s_base * 1000.0),
+                    num(y * 100.0 / to_base),
+                    num(br.phase_shift),
+                    side,
+                    neutral,
+                    min,
+                    max,
+                    step,
+                    Value::Null,
+                    pos,
+                    Value::from(false),
+                    Value::from(1),
+                    Value::from(1.0),
+                    Value::from(br.branch_status),
+                ]
+            })
+            .collect(),
+    );
+
+    let trafo3w_frame = frame(
+        &[
+            ("name", "object"),
+            ("std_type", "object"),
+            ("hv_bus", "uint32"),
+            ("mv_bus", "uint32"),
+            ("lv_bus", "uint32"),
+            ("sn_hv_mva", "float64"),
+            ("sn_mv_mva", "float64"),
+            ("sn_lv_mva", "float64"),
+            ("vn_hv_kv", "float64"),
+            ("vn_mv_kv", "float64"),
+            ("vn_lv_kv", "float64"),
+            ("vk_hv_percent", "float64"),
+            ("vk_mv_percent", "float64"),
+            ("vk_lv_percent", "float64"),
+            ("vkr_hv_percent", "float64"),
+            ("vkr_mv_percent", "float64"),
+            ("vkr_lv_percent", "float64"),
+            ("pfe_kw", "float64"),
+            ("i0_percent", "float64"),
+            ("shift_mv_degree", "float64"),
+            ("shift_lv_degree", "float64"),
+            ("tap_side", "object"),
+            ("tap_neutral", "float64"),
+            ("tap_min", "float64"),
+            ("tap_max", "float64"),
+            ("tap_step_percent", "float64"),
+            ("tap_step_degree", "float64"),
+            ("tap_pos", "float64"),
+            ("tap_at_star_point", "bool"),
+            ("in_service", "bool"),
+        ],
+        (0..stars.len()).collect(),
+        stars
+            .iter()
+            .map(|(star, legs)| {
+                let [hv, mv, lv] = legs.map(|i| &network.branches[i]);
+                let sn = |br: &Branch| {
+                    if br.operating_limit > 0.0 {
+                        br.operating_limit
+                    } else {
+                        s_base
+                    }
+                };
+                // Winding voltages keep the ratios between windings, with the
+                // high voltage one at the star point voltage
+                let scale = kv(*star) / (kv(hv.from_bus) * hv.tap_ratio);
+                let vn = |br: &Branch| kv(br.from_bus) * br.tap_ratio * scale;
+                // Short-circuit impedances of each pair of windings, in
+                // percent on the smaller rating of the pair
+                let pair = |a: &Branch, b: &Branch| {
+                    let to_base = sn(a).min(sn(b)) / s_base;
+                    let (r, x) = (a.resistance + b.resistance, a.reactance + b.reactance);
+                    (
+                        r.hypot(x).copysign(x) * 100.0 * to_base,
+                        r * 100.0 * to_base,
+                    )
+                };
+                let (hm, ml, lh) = (pair(hv, mv), pair(mv, lv), pair(lv, hv));
+                let g = hv.from_shunt_conductance;
+                let y = g.hypot(hv.from_shunt_susceptance);
+                vec![
+                    Value::from(hv.branch_name.trim()),
+                    Value::Null,
+                    Value::from(hv.from_bus),
+                    Value::from(mv.from_bus),
+                    Value::from(lv.from_bus),
+                    num(sn(hv)),
+                    num(sn(mv)),
+                    num(sn(lv)),
+                    num(vn(hv)),
+                    num(vn(mv)),
+                    num(vn(lv)),
+                    num(hm.0),
+                    num(ml.0),
+                    num(lh.0),
+                    num(hm.1),
+                    num(ml.1),
+                    num(lh.1),
+                    num(g * s_base * 1000.0),
+                    num(y * 100.0 * s_base / sn(hv)),
+                    num(hv.phase_shift - mv.phase_shift),
+                    num(hv.phase_shift - lv.phase_shift),
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::from(false),
+                    Value::from(hv.branch_status),
+                ]
+            })
+            .collect(),
+    );
+
+    let document = json!({
+        "_module": "pandapower.auxiliary",
+        "_class": "pandapowerNet",
+        "_object": {
+            "bus": buses_frame,
+            "load": loads,
+            "sgen": sgens,
+            "gen": gens,
+            "shunt": shunts,
+            "line": lines_frame,
+            "trafo": trafos_frame,
+            "trafo3w": trafo3w_frame,
+            "name": network.case_name,
+            "f_hz": num(network.frequency),
+            "sn_mva": num(network.s_base),
+            "version": "2.13.1",
+        }
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// Star point buses of three-winding transformers with their windings from
+/// the highest voltage down: in-service buses with nothing at them but three
+/// transformer branches towards them that share a status, with magnetizing
+/// admittance only at the highest voltage winding.
+fn three_winding_stars(network: &Network) -> Vec<(usize, [usize; 3])> {
+    let kv = |bus_id: usize| {
+        network
+            .buses
+            .iter()
+            .find(|b| b.bus_id == bus_id)
+            .map_or(0.0, |b| b.nom_voltage)
+    };
+    let mut stars = Vec::new();
+    for bus in &network.buses {
+        let id = bus.bus_id;
+        if !bus.bus_status
+            || bus.real_shunt != 0.0
+            || bus.imag_shunt != 0.0
+            || network.loads.iter().any(|l| l.bus_id == id)
+            || network.generators.iter().any(|g| g.gen_bus_id == id)
+        {
+            continue;
+        }
+        let mut legs: Vec<usize> = (0..network.branches.len())
+            .filter(|&i| {
+                let br = &network.branches[i];
+                br.from_bus == id || br.to_bus == id
+            })
+            .collect();
+        if legs.len() != 3 {
+            continue;
+        }
+        legs.sort_by(|&a, &b| {
+            kv(network.branches[b].from_bus).total_cmp(&kv(network.branches[a].from_bus))
+        });
+        let branch = |i: usize| &network.branches[i];
+        let star_legs = legs.iter().all(|&i| {
+            let br = branch(i);
+            br.branch_type == BranchType::TwoWinding
+                && br.to_bus == id
+                && br.from_bus != id
+                && br.branch_status == branch(legs[0]).branch_status
+                && br.to_shunt_conductance == 0.0
+                && br.to_shunt_susceptance == 0.0
+        });
+        let magnetizing_at_hv = legs[1..].iter().all(|&i| {
+            branch(i).from_shunt_conductance == 0.0 && branch(i).from_shunt_susceptance == 0.0
+        });
+        if star_legs && magnetizing_at_hv {
+            stars.push((id, [legs[0], legs[1], legs[2]]));
+        }
+    }
+    stars
+}
+
+/// Writes a Network to disk as a pandapower JSON network.
+pub fn write_pandapower(path: &str, network: &Network) -> Result<(), String> {
+    fs::write(path, format_pandapower(network))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
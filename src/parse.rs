@@ -1,65 +1,468 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, BufRead};
+use std::str::FromStr;
 
 use crate::case::*;
 
+/// Parses `fields[index]`, falling back to `default` when missing or malformed.
+pub(crate) fn parse_field<T: FromStr>(fields: &[&str], index: usize, default: T) -> T {
+    fields
+        .get(index)
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(default)
+}
+
 /// strip slashes or quotes from fields
 fn strip_extras(s: &str) -> String {
-    s.trim().trim_matches('\'').trim().to_string()
+    s.trim()
+        .trim_matches('\'')
+        .trim_matches('"')
+        .trim()
+        .to_string()
 }
 
-/// Parses a PSS/E RAW file into a Network.
-pub fn read_case_v33(path: &str) -> Network {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!("Failed to open {}: {}", path, e);
-            std::process::exit(1);
-        }
+/// Off-nominal turns ratio of a two-winding transformer. CW gives the units
+/// of WINDV: 1 is pu of the bus base kV, 2 is kV and 3 is pu of NOMV, where a
+/// NOMV of zero means the bus base kV. Each winding is (WINDV, NOMV, bus kV).
+pub(crate) fn winding_ratio(cw: u8, winding1: (f32, f32, f32), winding2: (f32, f32, f32)) -> f32 {
+    let pu = |(windv, nomv, base_kv): (f32, f32, f32)| match cw {
+        2 if base_kv > 0.0 => windv / base_kv,
+        3 if nomv > 0.0 && base_kv > 0.0 => windv * nomv / base_kv,
+        _ => windv,
     };
-    let content: String = io::BufReader::new(file)
+    let (t1, t2) = (pu(winding1), pu(winding2));
+    if t2 == 0.0 { t1 } else { t1 / t2 }
+}
+
+/// Reads a two-terminal DC converter record:
+/// IP, NB, ANMX, ANMN, RC, XC, EBAS, TR, TAP, TMX, TMN, STP, ...
+fn parse_converter(line: &str) -> DcConverter {
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    DcConverter {
+        bus_id: parse_field(&fields, 0, 0),
+        bridges: parse_field(&fields, 1, 0),
+        angle_max: parse_field(&fields, 2, 0.0),
+        angle_min: parse_field(&fields, 3, 0.0),
+        commutating_resistance: parse_field(&fields, 4, 0.0),
+        commutating_reactance: parse_field(&fields, 5, 0.0),
+        base_kv: parse_field(&fields, 6, 0.0),
+        transformer_ratio: parse_field(&fields, 7, 1.0),
+        tap: parse_field(&fields, 8, 1.0),
+        tap_max: parse_field(&fields, 9, 1.5),
+        tap_min: parse_field(&fields, 10, 0.51),
+        tap_step: parse_field(&fields, 11, 0.00625),
+    }
+}
+
+/// Series link of a FACTS record with MODE `mode` to bus `to_bus`, or None
+/// when J is 0. MODE 2 bypasses the link, 3 fixes its impedance at SET1 +
+/// jSET2, 4 fixes the series voltage at SET1 pu and SET2 degrees, and the
+/// IPFC modes 7 and 8 give it as SET1 + jSET2; the rest hold the flow at
+/// `setpoint` MW and MVAR. `rating` is IMX and `reactance` LINX.
+pub(crate) fn facts_series(
+    to_bus: usize,
+    mode: u8,
+    setpoint: (f32, f32),
+    set: (f32, f32),
+    rating: f32,
+    reactance: f32,
+) -> Option<SeriesLink> {
+    let (control, set) = match mode {
+        2 => (SeriesControl::Bypassed, set),
+        3 => (SeriesControl::Impedance, set),
+        4 => (SeriesControl::Voltage, set),
+        7 | 8 => (
+            SeriesControl::Voltage,
+            (set.0.hypot(set.1), set.1.atan2(set.0).to_degrees()),
+        ),
+        _ => (SeriesControl::Flow, set),
+    };
+    (to_bus != 0).then_some(SeriesLink {
+        to_bus,
+        control,
+        p_setpoint: setpoint.0,
+        q_setpoint: setpoint.1,
+        setpoint_real: set.0,
+        setpoint_imag: set.1,
+        rating,
+        reactance,
+        flow: 0.0,
+    })
+}
+
+/// Reads a VSC converter record:
+/// IBUS, TYPE, MODE, DCSET, ACSET, ALOSS, BLOSS, MINLOSS, SMAX, IMAX, PWF, MAXQ, MINQ, ...
+/// TYPE 0 is out of service, 1 DC voltage control (DCSET in kV) and 2 power
+/// control (DCSET in MW into the AC bus); MODE 1 is AC voltage control and 2
+/// power factor control.
+fn parse_vsc_converter(line: &str, name: &str, dc_bus_id: usize) -> VscConverter {
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    let kind: u8 = parse_field(&fields, 1, 0);
+    VscConverter {
+        name: name.to_string(),
+        ac_bus_id: parse_field(&fields, 0, 0),
+        dc_bus_id,
+        status: kind != 0,
+        dc_control: if kind == 1 {
+            VscDcControl::Voltage
+        } else {
+            VscDcControl::Power
+        },
+        dc_setpoint: parse_field(&fields, 3, 0.0),
+        ac_control: if parse_field(&fields, 2, 1u8) == 2 {
+            VscAcControl::PowerFactor
+        } else {
+            VscAcControl::Voltage
+        },
+        ac_setpoint: parse_field(&fields, 4, 1.0),
+        loss_a: parse_field(&fields, 5, 0.0),
+        loss_b: parse_field(&fields, 6, 0.0),
+        loss_min: parse_field(&fields, 7, 0.0),
+        s_max: parse_field(&fields, 8, 0.0),
+        i_max: parse_field(&fields, 9, 0.0),
+        q_max: parse_field(&fields, 11, 9999.0),
+        q_min: parse_field(&fields, 12, -9999.0),
+        p_ac: 0.0,
+        q_ac: 0.0,
+    }
+}
+
+/// Sections of a RAW file, named as in the "BEGIN ... DATA" terminator comments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    SystemWide,
+    Bus,
+    Load,
+    FixedShunt,
+    Generator,
+    Branch,
+    SwitchingDevice,
+    Transformer,
+    Area,
+    TwoTerminalDc,
+    VscDc,
+    ImpedanceCorrection,
+    MultiTerminalDc,
+    MultiSectionLine,
+    Zone,
+    InterArea,
+    Owner,
+    Facts,
+    SwitchedShunt,
+    /// Any section not read; skipped
+    Done,
+}
+
+impl Section {
+    /// Section named by a terminator comment such as "0 / END OF BUS DATA, BEGIN LOAD DATA".
+    fn from_terminator(line: &str) -> Option<Section> {
+        let upper = line.to_uppercase();
+        let label = upper.split("BEGIN").nth(1)?;
+        let label = label.split("DATA").next()?.trim();
+        Some(match label {
+            "SYSTEM-WIDE" => Section::SystemWide,
+            "BUS" => Section::Bus,
+            "LOAD" => Section::Load,
+            "FIXED SHUNT" => Section::FixedShunt,
+            "GENERATOR" => Section::Generator,
+            "BRANCH" | "NON-TRANSFORMER BRANCH" => Section::Branch,
+            "SYSTEM SWITCHING DEVICE" | "SWITCHING DEVICE" => Section::SwitchingDevice,
+            "TRANSFORMER" => Section::Transformer,
+            "AREA" => Section::Area,
+            "TWO-TERMINAL DC" => Section::TwoTerminalDc,
+            "VSC DC LINE" | "VSC DC" => Section::VscDc,
+            "IMPEDANCE CORRECTION" => Section::ImpedanceCorrection,
+            "MULTI-TERMINAL DC" => Section::MultiTerminalDc,
+            "MULTI-SECTION LINE" => Section::MultiSectionLine,
+            "ZONE" => Section::Zone,
+            "INTER-AREA TRANSFER" => Section::InterArea,
+            "OWNER" => Section::Owner,
+            "FACTS DEVICE" | "FACTS CONTROL DEVICE" => Section::Facts,
+            "SWITCHED SHUNT" => Section::SwitchedShunt,
+            _ => Section::Done,
+        })
+    }
+}
+
+/// Bus record layouts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BusRecord {
+    /// v30 and v31 carry the fixed shunt on the bus record
+    WithShunt,
+    /// v32 and later; limits follow VA from v33
+    Plain,
+}
+
+/// Section order and field positions that differ between RAW revisions.
+#[derive(Debug, Clone, Copy)]
+struct RawFormat {
+    /// Sections in file order, up to the last one read
+    sections: &'static [Section],
+    bus_record: BusRecord,
+    /// Index of STAT in the generator record; PT and PB are 2 and 3 further on
+    gen_status: usize,
+    /// Index of 'NAME' in the branch record, where there is one
+    branch_name: Option<usize>,
+    /// Index of RATEA in the branch record; RATEB follows
+    branch_rate: usize,
+    /// Index of GI in the branch record; BI, GJ, BJ and ST follow
+    branch_shunt: usize,
+    /// Indices of COD1 and TAB1 in the transformer winding 1 record
+    winding_control: usize,
+    winding_table: usize,
+    /// Impedance correction factors are complex, and tables span lines
+    complex_correction: bool,
+    /// Multi-section line records carry MET ahead of the dummy buses
+    section_metered: bool,
+}
+
+impl RawFormat {
+    /// Section that follows `section` when the terminator is unlabeled.
+    fn next_section(&self, section: Section) -> Section {
+        self.sections
+            .iter()
+            .position(|&s| s == section)
+            .and_then(|i| self.sections.get(i + 1))
+            .copied()
+            .unwrap_or(Section::Done)
+    }
+}
+
+/// v30 and v31 have no fixed shunt section; shunts are on the bus record.
+const RAW_V30: RawFormat = RawFormat {
+    sections: &[
+        Section::Bus,
+        Section::Load,
+        Section::Generator,
+        Section::Branch,
+        Section::Transformer,
+        Section::Area,
+        Section::TwoTerminalDc,
+        Section::VscDc,
+        Section::SwitchedShunt,
+        Section::ImpedanceCorrection,
+        Section::MultiTerminalDc,
+        Section::MultiSectionLine,
+        Section::Zone,
+        Section::InterArea,
+        Section::Owner,
+        Section::Facts,
+    ],
+    bus_record: BusRecord::WithShunt,
+    gen_status: 14,
+    branch_name: None,
+    branch_rate: 6,
+    branch_shunt: 9,
+    winding_control: 6,
+    winding_table: 13,
+    complex_correction: false,
+    section_metered: false,
+};
+
+/// v31 adds MET to the multi-section line record.
+const RAW_V31: RawFormat = RawFormat {
+    section_metered: true,
+    ..RAW_V30
+};
+
+/// v32 moves shunts to their own section and drops GL, BL from the bus record.
+const RAW_V32: RawFormat = RawFormat {
+    sections: &[
+        Section::Bus,
+        Section::Load,
+        Section::FixedShunt,
+        Section::Generator,
+        Section::Branch,
+        Section::Transformer,
+        Section::Area,
+        Section::TwoTerminalDc,
+        Section::VscDc,
+        Section::ImpedanceCorrection,
+        Section::MultiTerminalDc,
+        Section::MultiSectionLine,
+        Section::Zone,
+        Section::InterArea,
+        Section::Owner,
+        Section::Facts,
+        Section::SwitchedShunt,
+    ],
+    bus_record: BusRecord::Plain,
+    gen_status: 14,
+    branch_name: None,
+    branch_rate: 6,
+    branch_shunt: 9,
+    winding_control: 6,
+    winding_table: 13,
+    complex_correction: false,
+    section_metered: true,
+};
+
+const RAW_V33: RawFormat = RawFormat {
+    sections: &[
+        Section::Bus,
+        Section::Load,
+        Section::FixedShunt,
+        Section::Generator,
+        Section::Branch,
+        Section::Transformer,
+        Section::Area,
+        Section::TwoTerminalDc,
+        Section::VscDc,
+        Section::ImpedanceCorrection,
+        Section::MultiTerminalDc,
+        Section::MultiSectionLine,
+        Section::Zone,
+        Section::InterArea,
+        Section::Owner,
+        Section::Facts,
+        Section::SwitchedShunt,
+    ],
+    bus_record: BusRecord::Plain,
+    gen_status: 14,
+    branch_name: None,
+    branch_rate: 6,
+    branch_shunt: 9,
+    winding_control: 6,
+    winding_table: 13,
+    complex_correction: false,
+    section_metered: true,
+};
+
+/// v34 and v35 add system-wide data and switching devices, NREG on generators,
+/// a branch name with twelve ratings, twelve winding ratings and complex
+/// impedance correction factors.
+const RAW_V35: RawFormat = RawFormat {
+    sections: &[
+        Section::SystemWide,
+        Section::Bus,
+        Section::Load,
+        Section::FixedShunt,
+        Section::Generator,
+        Section::Branch,
+        Section::SwitchingDevice,
+        Section::Transformer,
+        Section::Area,
+        Section::TwoTerminalDc,
+        Section::VscDc,
+        Section::ImpedanceCorrection,
+        Section::MultiTerminalDc,
+        Section::MultiSectionLine,
+        Section::Zone,
+        Section::InterArea,
+        Section::Owner,
+        Section::Facts,
+        Section::SwitchedShunt,
+    ],
+    bus_record: BusRecord::Plain,
+    gen_status: 15,
+    branch_name: Some(6),
+    branch_rate: 7,
+    branch_shunt: 19,
+    winding_control: 15,
+    winding_table: 23,
+    complex_correction: true,
+    section_metered: true,
+};
+
+/// Reads the whole file into a string.
+fn read_raw_file(path: &str) -> Result<String, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    Ok(io::BufReader::new(file)
         .lines()
         .map_while(Result::ok)
         .collect::<Vec<String>>()
-        .join("\n");
+        .join("\n"))
+}
+
+/// Parses a PSS/E RAW file into a Network.
+pub fn read_case_v33(path: &str) -> Result<Network, String> {
+    parse_raw_str(&read_raw_file(path)?)
+}
 
-    parse_raw_str(&content)
+/// Parses a PSS/E RAW file of any supported revision into a Network.
+pub fn read_case(path: &str) -> Result<Network, String> {
+    parse_raw(&read_raw_file(path)?)
+}
+
+/// Lines of a RAW file without the "@!" field name comments v35 writes.
+fn data_lines(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("@!"))
+        .collect()
+}
+
+/// REV from the RAW header; a missing or zero REV means v33.
+pub fn raw_revision(content: &str) -> u32 {
+    let lines = data_lines(content);
+    let header = lines.first().copied().unwrap_or("");
+    let header_data = header.split('/').next().unwrap_or(header);
+    header_data
+        .split(',')
+        .nth(2)
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .filter(|&rev| rev != 0)
+        .unwrap_or(33)
+}
+
+/// Parses a PSS/E RAW string into a Network using the reader for its REV.
+pub fn parse_raw(content: &str) -> Result<Network, String> {
+    match raw_revision(content) {
+        30 => parse_raw_format(content, &RAW_V30),
+        31 => parse_raw_format(content, &RAW_V31),
+        32 => parse_raw_format(content, &RAW_V32),
+        33 => parse_raw_format(content, &RAW_V33),
+        34 | 35 => parse_raw_format(content, &RAW_V35),
+        rev => Err(format!("Unsupported RAW revision {}", rev)),
+    }
 }
 
 /// Parses a PSS/E v33 RAW string into a Network.
-pub fn parse_raw_str(content: &str) -> Network {
-    let lines: Vec<&str> = content.lines().collect();
+pub fn parse_raw_str(content: &str) -> Result<Network, String> {
+    parse_raw_format(content, &RAW_V33)
+}
+
+/// Parses a PSS/E v30 RAW string into a Network. v31 differs only in its
+/// multi-section line records; `parse_raw` picks the reader from the REV.
+pub fn parse_raw_v30_str(content: &str) -> Result<Network, String> {
+    parse_raw_format(content, &RAW_V30)
+}
+
+/// Parses a PSS/E v32 RAW string into a Network.
+pub fn parse_raw_v32_str(content: &str) -> Result<Network, String> {
+    parse_raw_format(content, &RAW_V32)
+}
+
+/// Parses a PSS/E v34 or v35 RAW string into a Network.
+pub fn parse_raw_v35_str(content: &str) -> Result<Network, String> {
+    parse_raw_format(content, &RAW_V35)
+}
+
+/// Parses RAW data laid out as `format` into a Network.
+fn parse_raw_format(content: &str, format: &RawFormat) -> Result<Network, String> {
+    let lines = data_lines(content);
 
     // Parse header line (line 1): IC, SBASE, REV, XFRRAT, NXFRAT, BASFRQ / comment
-    let header = lines[0];
+    let header = *lines.first().ok_or("Empty RAW file")?;
     let header_data = header.split('/').next().unwrap_or(header);
     let header_fields: Vec<&str> = header_data.split(',').collect();
 
-    let s_base: f32 = header_fields[1].trim().parse().unwrap_or(100.0);
-    let frequency: f32 = header_fields[5].trim().parse().unwrap_or(60.0);
+    let s_base: f32 = parse_field(&header_fields, 1, 100.0);
+    let frequency: f32 = header_fields
+        .get(5)
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(60.0);
 
     // Use the comment portion as the case name
     let case_name = header
-        .split('/')
-        .nth(1)
-        .map(|s| s.trim().to_string())
+        .split_once('/')
+        .map(|(_, s)| s.trim().to_string())
         .unwrap_or_default();
 
     let mut network = Network::new(case_name, s_base, frequency);
 
-    // Track which section we're in
-    #[derive(PartialEq)]
-    enum Section {
-        Bus,
-        Load,
-        FixedShunt,
-        Generator,
-        Branch,
-        Transformer,
-        Done,
-    }
-
-    let mut section = Section::Bus;
+    let mut section = format.sections[0];
 
     // skip 3 header lines
     let mut line_number = 3;
@@ -68,6 +471,10 @@ pub fn parse_raw_str(content: &str) -> Network {
     let mut branch_index: usize = 0;
     let mut gen_index: usize = 0;
     let mut load_index: usize = 0;
+    // Whether the last v35 impedance correction table has seen its 0, 0, 0 end
+    let mut table_closed = true;
+    // Bus base kV, filled when the transformers are reached
+    let mut bus_kv: HashMap<usize, f32> = HashMap::new();
 
     'lineloop: while line_number < lines.len() {
         let line = lines[line_number];
@@ -80,15 +487,8 @@ pub fn parse_raw_str(content: &str) -> Network {
 
         // Proceed to the next section (delimiter lines are "0 / ..." or "0 /...")
         if trimmed == "0" || trimmed.starts_with("0 /") || trimmed.starts_with("0/") {
-            section = match section {
-                Section::Bus => Section::Load,
-                Section::Load => Section::FixedShunt,
-                Section::FixedShunt => Section::Generator,
-                Section::Generator => Section::Branch,
-                Section::Branch => Section::Transformer,
-                Section::Transformer => Section::Done,
-                Section::Done => Section::Done,
-            };
+            section =
+                Section::from_terminator(trimmed).unwrap_or_else(|| format.next_section(section));
 
             // No data to parse in section headers; move on.
             line_number += 1;
@@ -96,7 +496,7 @@ pub fn parse_raw_str(content: &str) -> Network {
         }
 
         if section == Section::Done {
-            // Skip remaining sections (area, zone, owner, etc.)
+            // Skip sections past the last one read
             line_number += 1;
             continue;
         }
@@ -106,21 +506,60 @@ pub fn parse_raw_str(content: &str) -> Network {
             continue;
         }
 
+        // Files without system-wide data go straight to the buses
+        if section == Section::SystemWide
+            && trimmed
+                .split(',')
+                .next()
+                .is_some_and(|s| s.trim().parse::<usize>().is_ok())
+        {
+            section = Section::Bus;
+        }
+
         match section {
+            Section::SystemWide => {
+                // GENERAL, GAUSS, NEWTON, ADJUST, TYSL, SOLVER and RATING records are not modeled
+            }
+
             Section::Bus => {
-                // I, 'NAME', BASKV, IDE, AREA, ZONE, OWNER, VM, VA, NVHI, NVLO, EVHI, EVLO
+                // v30/v31: I, 'NAME', BASKV, IDE, GL, BL, AREA, ZONE, VM, VA, OWNER
+                // v32+:    I, 'NAME', BASKV, IDE, AREA, ZONE, OWNER, VM, VA, [NVHI, NVLO, EVHI, EVLO]
                 let fields: Vec<&str> = trimmed.split(',').collect();
-                if fields.len() >= 13 {
+                let min_fields = match format.bus_record {
+                    BusRecord::WithShunt => 11,
+                    BusRecord::Plain => 9,
+                };
+                if fields.len() >= min_fields {
                     let bus_id: usize = fields[0].trim().parse().unwrap_or(0);
                     let bus_name = strip_extras(fields[1]);
                     let nom_voltage: f32 = fields[2].trim().parse().unwrap_or(0.0);
                     let ide: u8 = fields[3].trim().parse().unwrap_or(1);
-                    let voltage: f32 = fields[7].trim().parse().unwrap_or(1.0);
-                    let angle: f32 = fields[8].trim().parse().unwrap_or(0.0);
-                    let v_max_operating: f32 = fields[9].trim().parse().unwrap_or(1.1);
-                    let v_min_operating: f32 = fields[10].trim().parse().unwrap_or(0.9);
-                    let v_max_contingency: f32 = fields[11].trim().parse().unwrap_or(1.1);
-                    let v_min_contingency: f32 = fields[12].trim().parse().unwrap_or(0.9);
+                    let (area, zone, owner, vm, shunt) = match format.bus_record {
+                        BusRecord::WithShunt => (6, 7, 10, 8, Some(4)),
+                        BusRecord::Plain => (4, 5, 6, 7, None),
+                    };
+                    let area: usize = parse_field(&fields, area, 1);
+                    let zone: usize = parse_field(&fields, zone, 1);
+                    let owner: usize = parse_field(&fields, owner, 1);
+                    let voltage: f32 = parse_field(&fields, vm, 1.0);
+                    let angle: f32 = parse_field(&fields, vm + 1, 0.0);
+                    let (real_shunt, imag_shunt): (f32, f32) = match shunt {
+                        Some(gl) => (
+                            parse_field(&fields, gl, 0.0),
+                            parse_field(&fields, gl + 1, 0.0),
+                        ),
+                        None => (0.0, 0.0),
+                    };
+                    // Voltage limits were added in v33; older records keep the defaults
+                    let limits = format.bus_record == BusRecord::Plain && fields.len() >= 13;
+                    let limit = |index: usize, default: f32| match limits {
+                        true => parse_field(&fields, index, default),
+                        false => default,
+                    };
+                    let v_max_operating = limit(9, 1.1);
+                    let v_min_operating = limit(10, 0.9);
+                    let v_max_contingency = limit(11, 1.1);
+                    let v_min_contingency = limit(12, 0.9);
 
                     let bus_type = match ide {
                         3 => BusType::Slack,
@@ -128,8 +567,7 @@ pub fn parse_raw_str(content: &str) -> Network {
                         1 => BusType::PQ,
                         4 => BusType::OUT,
                         _ => {
-                            eprintln!("Unknown bus type code: {}", ide);
-                            std::process::exit(1);
+                            return Err(format!("Bus {}: unknown bus type code {}", bus_id, ide));
                         }
                     };
 
@@ -138,12 +576,15 @@ pub fn parse_raw_str(content: &str) -> Network {
                         bus_name,
                         bus_type,
                         nom_voltage,
+                        area,
+                        zone,
+                        owner,
                         // bus is in service if the code is not 4
                         bus_status: ide != 4,
                         voltage,
                         angle,
-                        real_shunt: 0.0,
-                        imag_shunt: 0.0,
+                        real_shunt,
+                        imag_shunt,
                         v_min_operating,
                         v_min_contingency,
                         v_max_operating,
@@ -161,28 +602,51 @@ pub fn parse_raw_str(content: &str) -> Network {
                     let status: u8 = fields[2].trim().parse().unwrap_or(1);
                     let pl: f32 = fields[5].trim().parse().unwrap_or(0.0);
                     let ql: f32 = fields[6].trim().parse().unwrap_or(0.0);
+                    let zip = |i: usize| parse_field(&fields, i, 0.0f32);
 
-                    if status == 1 {
-                        network.loads.push(Load {
-                            load_id: load_index,
-                            bus_id,
-                            load_name: format!("Bus{}-{}", bus_id, name),
-                            real_load: pl,
-                            imag_load: ql,
-                        });
-                        load_index += 1;
-                    }
+                    network.loads.push(Load {
+                        load_id: load_index,
+                        bus_id,
+                        load_name: format!("Bus{}-{}", bus_id, name),
+                        load_ident: name,
+                        load_status: status == 1,
+                        real_load: pl,
+                        imag_load: ql,
+                        real_current: zip(7),
+                        imag_current: zip(8),
+                        real_admittance: zip(9),
+                        // RAW YQ is negative for an inductive load; subtracting
+                        // from 0.0 keeps a zero YQ from becoming -0
+                        imag_admittance: 0.0 - zip(10),
+                    });
+                    load_index += 1;
                 }
             }
 
             Section::FixedShunt => {
-                // Skip fixed shunt data for now
+                // I, 'ID', STATUS, GL, BL
+                let fields: Vec<&str> = trimmed.split(',').collect();
+                if fields.len() >= 5 {
+                    let bus_id: usize = fields[0].trim().parse().unwrap_or(0);
+                    let status: u8 = fields[2].trim().parse().unwrap_or(1);
+                    let gl: f32 = fields[3].trim().parse().unwrap_or(0.0);
+                    let bl: f32 = fields[4].trim().parse().unwrap_or(0.0);
+
+                    // In-service shunts are lumped onto the bus (MW / MVAR at 1 pu)
+                    if status == 1
+                        && let Some(bus) = network.buses.iter_mut().find(|b| b.bus_id == bus_id)
+                    {
+                        bus.real_shunt += gl;
+                        bus.imag_shunt += bl;
+                    }
+                }
             }
 
             Section::Generator => {
-                // I, 'ID', PG, QG, QT, QB, VS, IREG, MBASE, ...
+                // I, 'ID', PG, QG, QT, QB, VS, IREG, [NREG,] MBASE, ..., STAT, RMPCT, PT, PB, ...
                 let fields: Vec<&str> = trimmed.split(',').collect();
-                if fields.len() >= 17 {
+                let stat = format.gen_status;
+                if fields.len() > stat + 3 {
                     let bus_id: usize = fields[0].trim().parse().unwrap_or(0);
                     let name = strip_extras(fields[1]);
                     let pg: f32 = fields[2].trim().parse().unwrap_or(0.0);
@@ -190,14 +654,15 @@ pub fn parse_raw_str(content: &str) -> Network {
                     let qt: f32 = fields[4].trim().parse().unwrap_or(0.0);
                     let qb: f32 = fields[5].trim().parse().unwrap_or(0.0);
                     let vs: f32 = fields[6].trim().parse().unwrap_or(1.0);
-                    let status: u8 = fields[14].trim().parse().unwrap_or(1);
-                    let pt: f32 = fields[16].trim().parse().unwrap_or(0.0);
-                    let pb: f32 = fields[17].trim().parse().unwrap_or(0.0);
+                    let status: u8 = fields[stat].trim().parse().unwrap_or(1);
+                    let pt: f32 = fields[stat + 2].trim().parse().unwrap_or(0.0);
+                    let pb: f32 = fields[stat + 3].trim().parse().unwrap_or(0.0);
 
                     network.generators.push(Generator {
                         gen_id: gen_index,
                         gen_bus_id: bus_id,
                         gen_name: format!("Bus{}-{}", bus_id, name),
+                        machine_id: name,
                         gen_status: status == 1,
                         p_gen: pg,
                         q_gen: qg,
@@ -206,35 +671,45 @@ pub fn parse_raw_str(content: &str) -> Network {
                         p_max: pt,
                         q_min: qb,
                         q_max: qt,
+                        cost: None,
+                        capability: Vec::new(),
                     });
                     gen_index += 1;
                 }
             }
 
             Section::Branch => {
-                // I, J, 'CKT', R, X, B, RATEA, RATEB, RATEC, GI, BI, GJ, BJ, ST, ...
+                // v33: I, J, 'CKT', R, X, B, RATEA, RATEB, RATEC, GI, BI, GJ, BJ, ST, ...
+                // v35: I, J, 'CKT', R, X, B, 'NAME', RATE1, ..., RATE12, GI, BI, GJ, BJ, ST, ...
                 let fields: Vec<&str> = trimmed.split(',').collect();
-                if fields.len() >= 14 {
+                let rate = format.branch_rate;
+                let shunt = format.branch_shunt;
+                if fields.len() > shunt + 4 {
                     let from_bus: usize = fields[0].trim().parse().unwrap_or(0);
                     let to_bus: usize = fields[1].trim().parse().unwrap_or(0);
-                    // fields[2] = 'CKT' (circuit identifier, skip)
+                    let ckt = strip_extras(fields[2]);
                     let r: f32 = fields[3].trim().parse().unwrap_or(0.0);
                     let x: f32 = fields[4].trim().parse().unwrap_or(0.0);
                     let b: f32 = fields[5].trim().parse().unwrap_or(0.0);
-                    let rate_a: f32 = fields[6].trim().parse().unwrap_or(0.0);
-                    let rate_b: f32 = fields[7].trim().parse().unwrap_or(0.0);
-                    let gi: f32 = fields[9].trim().parse().unwrap_or(0.0);
-                    let bi: f32 = fields[10].trim().parse().unwrap_or(0.0);
-                    let gj: f32 = fields[11].trim().parse().unwrap_or(0.0);
-                    let bj: f32 = fields[12].trim().parse().unwrap_or(0.0);
-                    let status: u8 = fields[13].trim().parse().unwrap_or(1);
+                    let rate_a: f32 = fields[rate].trim().parse().unwrap_or(0.0);
+                    let rate_b: f32 = fields[rate + 1].trim().parse().unwrap_or(0.0);
+                    let gi: f32 = fields[shunt].trim().parse().unwrap_or(0.0);
+                    let bi: f32 = fields[shunt + 1].trim().parse().unwrap_or(0.0);
+                    let gj: f32 = fields[shunt + 2].trim().parse().unwrap_or(0.0);
+                    let bj: f32 = fields[shunt + 3].trim().parse().unwrap_or(0.0);
+                    let status: u8 = fields[shunt + 4].trim().parse().unwrap_or(1);
+                    let name = format
+                        .branch_name
+                        .map(|i| strip_extras(fields[i]))
+                        .unwrap_or_default();
 
                     network.branches.push(Branch {
                         branch_type: BranchType::Line,
                         id: branch_index,
                         from_bus,
                         to_bus,
-                        branch_name: String::from("            "),
+                        ckt,
+                        branch_name: name,
                         branch_status: status == 1,
                         resistance: r,
                         reactance: x,
@@ -244,6 +719,47 @@ pub fn parse_raw_str(content: &str) -> Network {
                         to_shunt_susceptance: bj + b / 2.0,
                         tap_ratio: 1.0,
                         phase_shift: 0.0,
+                        impedance_table: None,
+                        operating_limit: rate_a,
+                        contingency_limit: rate_b,
+                        flow: 0.0,
+                    });
+                    branch_index += 1;
+                }
+            }
+
+            Section::SwitchingDevice => {
+                // I, J, 'CKT', X, RATE1, ..., RATE12, STAT, NSTAT, MET, STYPE, 'NAME'
+                let fields: Vec<&str> = trimmed.split(',').collect();
+                if fields.len() >= 17 {
+                    let from_bus: usize = fields[0].trim().parse().unwrap_or(0);
+                    let to_bus: usize = fields[1].trim().parse().unwrap_or(0);
+                    let ckt = strip_extras(fields[2]);
+                    let x: f32 = fields[3].trim().parse().unwrap_or(0.0);
+                    let rate_a: f32 = fields[4].trim().parse().unwrap_or(0.0);
+                    let rate_b: f32 = fields[5].trim().parse().unwrap_or(0.0);
+                    let status: u8 = fields[16].trim().parse().unwrap_or(1);
+                    let name = fields.get(20).map(|f| strip_extras(f)).unwrap_or_default();
+
+                    // Breakers and switches are near-zero impedance lines; keep X
+                    // at the PSS/E zero impedance threshold so B' stays finite
+                    network.branches.push(Branch {
+                        branch_type: BranchType::Line,
+                        id: branch_index,
+                        from_bus,
+                        to_bus,
+                        ckt,
+                        branch_name: name,
+                        branch_status: status == 1,
+                        resistance: 0.0,
+                        reactance: if x.abs() < 1e-4 { 1e-4 } else { x },
+                        from_shunt_conductance: 0.0,
+                        from_shunt_susceptance: 0.0,
+                        to_shunt_conductance: 0.0,
+                        to_shunt_susceptance: 0.0,
+                        tap_ratio: 1.0,
+                        phase_shift: 0.0,
+                        impedance_table: None,
                         operating_limit: rate_a,
                         contingency_limit: rate_b,
                         flow: 0.0,
@@ -253,13 +769,20 @@ pub fn parse_raw_str(content: &str) -> Network {
             }
 
             Section::Transformer => {
+                if bus_kv.is_empty() {
+                    bus_kv = network
+                        .buses
+                        .iter()
+                        .map(|b| (b.bus_id, b.nom_voltage))
+                        .collect();
+                }
                 // Two-winding transformer: 4 lines per record
                 // Line 1: I, J, K, 'CKT', CW, CZ, CM, MAG1, MAG2, NMETR, 'NAME', STAT, ...
                 // Line 2: R1-2, X1-2, SBASE1-2
                 // Line 3: WINDV1, NOMV1, ANG1, RATA1, RATB1, RATC1, ...
                 // Line 4: WINDV2, NOMV2
                 let fields: Vec<&str> = trimmed.split(',').collect();
-                if fields.len() < 5 {
+                if fields.len() < 12 {
                     line_number += 1;
                     continue;
                 }
@@ -267,9 +790,17 @@ pub fn parse_raw_str(content: &str) -> Network {
                 let from_bus: usize = fields[0].trim().parse().unwrap_or(0);
                 let to_bus: usize = fields[1].trim().parse().unwrap_or(0);
                 let k: i32 = fields[2].trim().parse().unwrap_or(0);
-                // let name = strip_extras(fields[3]);
+                let ckt = strip_extras(fields[3]);
+                let cw: u8 = parse_field(&fields, 4, 1);
+                let name = strip_extras(fields[10]);
+                let cm: u8 = fields[6].trim().parse().unwrap_or(1);
+                let mag1: f32 = fields[7].trim().parse().unwrap_or(0.0);
+                let mag2: f32 = fields[8].trim().parse().unwrap_or(0.0);
                 let status: u8 = fields[11].trim().parse().unwrap_or(1);
 
+                // Magnetizing admittance in system base pu (CM=1) sits at the winding 1 bus
+                let (mag_g, mag_b) = if cm == 1 { (mag1, mag2) } else { (0.0, 0.0) };
+
                 let is_three_winding = k != 0;
 
                 // Line 2: impedance data
@@ -295,14 +826,17 @@ pub fn parse_raw_str(content: &str) -> Network {
                 }
                 let w1_line = lines[line_number].trim();
                 let w1_fields: Vec<&str> = w1_line.split(',').collect();
-                let tap_ratio: f32 = w1_fields
-                    .first()
-                    .and_then(|s| s.trim().parse().ok())
-                    .unwrap_or(1.0);
                 let angle: f32 = w1_fields
                     .get(2)
                     .and_then(|s| s.trim().parse().ok())
                     .unwrap_or(0.0);
+                // |COD1| = 3 is phase shift control, whose table is against the angle
+                let cod: i32 = parse_field(&w1_fields, format.winding_control, 0);
+                let table: usize = parse_field(&w1_fields, format.winding_table, 0);
+                let impedance_table = (table != 0).then_some(TableLink {
+                    table,
+                    by_angle: cod.abs() == 3,
+                });
                 let rate_a: f32 = w1_fields
                     .get(3)
                     .and_then(|s| s.trim().parse().ok())
@@ -317,6 +851,21 @@ pub fn parse_raw_str(content: &str) -> Network {
                 if line_number >= lines.len() {
                     break;
                 }
+                let w2_fields: Vec<&str> = lines[line_number].trim().split(',').collect();
+                let base_kv = |bus_id: usize| bus_kv.get(&bus_id).copied().unwrap_or(0.0);
+                let tap_ratio = winding_ratio(
+                    cw,
+                    (
+                        parse_field(&w1_fields, 0, 1.0),
+                        parse_field(&w1_fields, 1, 0.0),
+                        base_kv(from_bus),
+                    ),
+                    (
+                        parse_field(&w2_fields, 0, 1.0),
+                        parse_field(&w2_fields, 1, 0.0),
+                        base_kv(to_bus),
+                    ),
+                );
 
                 // For three-winding transformers, there's a 5th line (winding 3)
                 if is_three_winding {
@@ -331,16 +880,18 @@ pub fn parse_raw_str(content: &str) -> Network {
                     id: branch_index,
                     from_bus,
                     to_bus,
-                    branch_name: String::from("            "),
+                    ckt,
+                    branch_name: name,
                     branch_status: status == 1,
                     resistance: r,
                     reactance: x,
-                    from_shunt_conductance: 0.0,
-                    from_shunt_susceptance: 0.0,
+                    from_shunt_conductance: mag_g,
+                    from_shunt_susceptance: mag_b,
                     to_shunt_conductance: 0.0,
                     to_shunt_susceptance: 0.0,
                     tap_ratio,
                     phase_shift: angle,
+                    impedance_table,
                     operating_limit: rate_a,
                     contingency_limit: rate_b,
                     flow: 0.0,
@@ -348,14 +899,193 @@ pub fn parse_raw_str(content: &str) -> Network {
                 branch_index += 1;
             }
 
-            Section::Done => {}
+            Section::TwoTerminalDc => {
+                // Three lines per record
+                // Line 1: 'NAME', MDC, RDC, SETVL, VSCHD, VCMOD, RCOMP, DELTI, METER, DCVMIN, CCCITMX, CCCACC
+                // Line 2: IPR, NBR, ANMXR, ANMNR, RCR, XCR, EBASR, TRR, TAPR, TMXR, TMNR, STPR, ...
+                // Line 3: IPI, NBI, ANMXI, ANMNI, RCI, XCI, EBASI, TRI, TAPI, TMXI, TMNI, STPI, ...
+                // v30 numbers the line instead of naming it
+                let fields: Vec<&str> = trimmed.split(',').collect();
+                if line_number + 2 >= lines.len() {
+                    break;
+                }
+                let rectifier = parse_converter(lines[line_number + 1]);
+                let inverter = parse_converter(lines[line_number + 2]);
+                line_number += 2;
+
+                let control = match parse_field(&fields, 1, 0u8) {
+                    1 => DcControl::Power,
+                    2 => DcControl::Current,
+                    _ => DcControl::Blocked,
+                };
+                network.dc_lines.push(DcLine {
+                    name: strip_extras(fields[0]),
+                    control,
+                    resistance: parse_field(&fields, 2, 0.0),
+                    setpoint: parse_field(&fields, 3, 0.0),
+                    scheduled_voltage: parse_field(&fields, 4, 0.0),
+                    switch_voltage: parse_field(&fields, 5, 0.0),
+                    compounding_resistance: parse_field(&fields, 6, 0.0),
+                    current_margin: parse_field(&fields, 7, 0.0),
+                    rectifier,
+                    inverter,
+                });
+            }
+
+            Section::VscDc => {
+                // Three lines per record
+                // Line 1: 'NAME', MDC, RDC, O1, F1, ..., O4, F4
+                // Lines 2 and 3: IBUS, TYPE, MODE, DCSET, ACSET, ALOSS, BLOSS, MINLOSS, SMAX, IMAX, PWF, MAXQ, MINQ, ...
+                // Each line becomes two DC buses joined by a DC branch
+                let fields: Vec<&str> = trimmed.split(',').collect();
+                if line_number + 2 >= lines.len() {
+                    break;
+                }
+                let name = strip_extras(fields[0]);
+                let first = network.dc_buses.len() + 1;
+                for (end, dc_bus_id) in [(1, first), (2, first + 1)] {
+                    let conv = parse_vsc_converter(lines[line_number + end], &name, dc_bus_id);
+                    network.dc_buses.push(DcBus {
+                        dc_bus_id,
+                        name: format!("{}-{}", name, end),
+                        voltage: if conv.dc_control == VscDcControl::Voltage {
+                            conv.dc_setpoint
+                        } else {
+                            0.0
+                        },
+                    });
+                    network.vsc_converters.push(conv);
+                }
+                line_number += 2;
+                network.dc_branches.push(DcBranch {
+                    from_bus: first,
+                    to_bus: first + 1,
+                    name,
+                    status: parse_field(&fields, 1, 1u8) == 1,
+                    resistance: parse_field(&fields, 2, 0.0),
+                    flow: 0.0,
+                });
+            }
+
+            Section::ImpedanceCorrection => {
+                // v30-v33: I, T1, F1, T2, F2, ..., T11, F11 with unused pairs zero
+                // v35: I, T1, Re(F1), Im(F1), T2, ... over several lines, ended
+                // by a 0, 0, 0 triple
+                let fields: Vec<f32> = trimmed
+                    .split(',')
+                    .filter_map(|f| f.trim().parse().ok())
+                    .collect();
+                let width = if format.complex_correction { 3 } else { 2 };
+                let mut points = &fields[..];
+                if table_closed && let Some(&number) = fields.first() {
+                    network.impedance_tables.push(ImpedanceTable {
+                        number: number as usize,
+                        points: Vec::new(),
+                    });
+                    table_closed = !format.complex_correction;
+                    points = &fields[1..];
+                }
+                if let Some(table) = network.impedance_tables.last_mut() {
+                    for point in points.chunks(width) {
+                        if point.iter().all(|&v| v == 0.0) {
+                            table_closed = true;
+                            break;
+                        }
+                        // A zero factor marks an unused pair
+                        if point.len() == width && point[1..].iter().any(|&f| f != 0.0) {
+                            let im = point.get(2).copied().unwrap_or(0.0);
+                            table.points.push((point[0], point[1], im));
+                        }
+                    }
+                }
+            }
+
+            Section::Facts => {
+                // 'NAME', I, J, MODE, PDES, QDES, VSET, SHMX, TRMX, VTMN, VTMX, VSMX, IMX, LINX, RMPCT, OWNER, SET1, SET2, VSREF, ...
+                // J = 0 for a shunt-only device. MODE 0 is out of service, 1 normal
+                // (flow control), 2 series link bypassed, 3 constant series
+                // impedance, 4 constant series voltage, 5-8 IPFC by P/Q (5, 6) or
+                // series voltage (7, 8). VSREF 1 refers the series voltage to the
+                // line current, which is not modeled.
+                let fields: Vec<&str> = trimmed.split(',').collect();
+                if fields.len() >= 8 {
+                    let name = strip_extras(fields[0]);
+                    let mode: u8 = parse_field(&fields, 3, 1);
+                    if matches!(mode, 4 | 7 | 8) && parse_field(&fields, 18, 0u8) == 1 {
+                        return Err(format!(
+                            "FACTS device '{}': series voltage referred to the line current is not supported",
+                            name
+                        ));
+                    }
+                    // SHMX is the shunt current limit in MVA at unity voltage
+                    let shmx: f32 = parse_field(&fields, 7, 0.0);
+                    network.facts_devices.push(FactsDevice {
+                        name,
+                        bus_id: parse_field(&fields, 1, 0),
+                        status: mode != 0,
+                        v_setpoint: parse_field(&fields, 6, 1.0),
+                        q_max: shmx,
+                        q_min: -shmx,
+                        series: facts_series(
+                            parse_field(&fields, 2, 0),
+                            mode,
+                            (parse_field(&fields, 4, 0.0), parse_field(&fields, 5, 0.0)),
+                            (parse_field(&fields, 16, 0.0), parse_field(&fields, 17, 0.0)),
+                            parse_field(&fields, 12, 0.0),
+                            parse_field(&fields, 13, 0.05),
+                        ),
+                        q_shunt: 0.0,
+                    });
+                }
+            }
+
+            Section::MultiSectionLine => {
+                // v31 on: I, J, 'ID', MET, DUM1, DUM2, ..., DUM9
+                // v30: I, J, 'ID', DUM1, ...
+                // MET 1 meters at I, 2 at J; unused dummy buses are zero
+                let fields: Vec<&str> = trimmed.split(',').collect();
+                if fields.len() >= 3 {
+                    let (metered_from, first) = if format.section_metered {
+                        (parse_field(&fields, 3, 1u8) != 2, 4)
+                    } else {
+                        (true, 3)
+                    };
+                    network.line_groups.push(LineGroup {
+                        from_bus: parse_field(&fields, 0, 0),
+                        to_bus: parse_field(&fields, 1, 0),
+                        id: strip_extras(fields[2]),
+                        metered_from,
+                        dummy_buses: (first..fields.len())
+                            .map(|i| parse_field(&fields, i, 0))
+                            .filter(|&bus| bus != 0)
+                            .collect(),
+                    });
+                }
+            }
+
+            Section::MultiTerminalDc => {
+                // Converters, DC buses and DC links of an LCC grid; not modeled,
+                // and dropping them would change the AC flows
+                return Err(format!(
+                    "Line {}: multi-terminal DC records are not supported",
+                    line_number + 1
+                ));
+            }
+
+            Section::Area
+            | Section::Zone
+            | Section::InterArea
+            | Section::Owner
+            | Section::SwitchedShunt
+            | Section::Done => {}
         }
 
         line_number += 1;
     }
 
+    network.number_circuits();
     // Build bus_map: bus_id -> matrix index (excluding slack)
     network.rebuild_bus_map();
 
-    network
+    Ok(network)
 }
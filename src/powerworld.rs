@@ -0,0 +1,658 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::capability::set_curve;
+use crate::case::*;
+use crate::contingency::{ChangeMode, ChangeUnit, Contingency, ContingencyAction, tokenize};
+use crate::monitor::{Interface, InterfaceBranch};
+
+/// One record of an AUX DATA block, with any SUBDATA sections that follow it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuxRecord {
+    pub values: Vec<String>,
+    /// (SUBDATA type, raw lines)
+    pub subdata: Vec<(String, Vec<String>)>,
+}
+
+/// A DATA (ObjectType, [fields]) block of a PowerWorld AUX file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuxBlock {
+    pub object_type: String,
+    pub fields: Vec<String>,
+    pub records: Vec<AuxRecord>,
+}
+
+impl AuxBlock {
+    /// Value of the first of `names` present in the field list, matched
+    /// without regard to case.
+    fn value<'a>(&self, record: &'a AuxRecord, names: &[&str]) -> Option<&'a str> {
+        names.iter().find_map(|name| {
+            let index = self
+                .fields
+                .iter()
+                .position(|f| f.eq_ignore_ascii_case(name))?;
+            record.values.get(index).map(String::as_str)
+        })
+    }
+
+    fn number<T: std::str::FromStr>(&self, record: &AuxRecord, names: &[&str]) -> Option<T> {
+        self.value(record, names)?.trim().parse().ok()
+    }
+
+    fn subdata<'a>(&self, record: &'a AuxRecord, kind: &str) -> impl Iterator<Item = &'a String> {
+        let kind = kind.to_string();
+        record
+            .subdata
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(&kind))
+            .flat_map(|(_, lines)| lines)
+    }
+}
+
+/// Contingencies and interfaces carried by an AUX file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuxData {
+    pub contingencies: Vec<Contingency>,
+    pub interfaces: Vec<Interface>,
+}
+
+/// Splits the header of a DATA block into its object type and field list.
+fn parse_header(header: &str) -> Result<(String, Vec<String>), String> {
+    let bad = || format!("Bad AUX DATA header: {}", header.trim());
+    let open = header.find('(').ok_or_else(bad)?;
+    let (list_start, list_end) = (
+        header.find('[').ok_or_else(bad)?,
+        header.find(']').ok_or_else(bad)?,
+    );
+    let object_type = header[open + 1..list_start]
+        .trim()
+        .trim_end_matches(',')
+        .trim()
+        .to_string();
+    let fields = header[list_start + 1..list_end]
+        .split(',')
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    Ok((object_type, fields))
+}
+
+/// Reads the DATA blocks of a PowerWorld AUX file.
+pub fn read_aux(path: &str) -> Result<Vec<AuxBlock>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_aux_str(&content)
+}
+
+/// Parses the DATA blocks of a PowerWorld AUX file. Records may continue over
+/// several lines until every field has a value; SCRIPT blocks are skipped.
+pub fn parse_aux_str(content: &str) -> Result<Vec<AuxBlock>, String> {
+    let mut blocks = Vec::new();
+    let mut lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"));
+
+    while let Some(line) = lines.next() {
+        let keyword = line
+            .split(|c: char| c.is_whitespace() || c == '(')
+            .next()
+            .unwrap_or("")
+            .to_uppercase();
+
+        if keyword == "SCRIPT" {
+            let mut depth = 0;
+            for l in std::iter::once(line).chain(lines.by_ref()) {
+                depth += l.matches('{').count() as i32 - l.matches('}').count() as i32;
+                if depth <= 0 && l.contains('}') {
+                    break;
+                }
+            }
+            continue;
+        }
+        if keyword != "DATA" {
+            return Err(format!("Unexpected AUX line: {}", line));
+        }
+
+        // The header may span lines up to the closing parenthesis
+        let mut header = line.to_string();
+        while !header
+            .split_once(']')
+            .is_some_and(|(_, rest)| rest.contains(')'))
+        {
+            header.push(' ');
+            header.push_str(lines.next().ok_or("Unterminated AUX DATA header")?);
+        }
+        let (object_type, fields) = parse_header(&header)?;
+        match lines.next() {
+            Some("{") => {}
+            other => {
+                return Err(format!(
+                    "Expected '{{' after {} header, found {:?}",
+                    object_type, other
+                ));
+            }
+        }
+
+        let mut records: Vec<AuxRecord> = Vec::new();
+        let mut pending: Vec<String> = Vec::new();
+        loop {
+            let line = lines
+                .next()
+                .ok_or_else(|| format!("Unterminated AUX DATA block {}", object_type))?;
+            if line == "}" {
+                break;
+            }
+            if let Some(kind) = line
+                .strip_prefix("<SUBDATA")
+                .or_else(|| line.strip_prefix("<subdata"))
+            {
+                let kind = kind.trim().trim_end_matches('>').trim().to_string();
+                let mut sub = Vec::new();
+                for l in lines.by_ref() {
+                    if l.eq_ignore_ascii_case("</SUBDATA>") {
+                        break;
+                    }
+                    sub.push(l.to_string());
+                }
+                if !pending.is_empty() {
+                    records.push(AuxRecord {
+                        values: std::mem::take(&mut pending),
+                        subdata: Vec::new(),
+                    });
+                }
+                let record = records
+                    .last_mut()
+                    .ok_or_else(|| format!("SUBDATA {} before any {} record", kind, object_type))?;
+                record.subdata.push((kind, sub));
+                continue;
+            }
+            pending.extend(tokenize(line));
+            if pending.len() >= fields.len() {
+                records.push(AuxRecord {
+                    values: std::mem::take(&mut pending),
+                    subdata: Vec::new(),
+                });
+            }
+        }
+        if !pending.is_empty() {
+            records.push(AuxRecord {
+                values: pending,
+                subdata: Vec::new(),
+            });
+        }
+
+        blocks.push(AuxBlock {
+            object_type,
+            fields,
+            records,
+        });
+    }
+    Ok(blocks)
+}
+
+/// Whether a status value means in service.
+fn closed(value: &str) -> bool {
+    ["closed", "connected", "yes", "1"]
+        .iter()
+        .any(|v| value.trim().eq_ignore_ascii_case(v))
+}
+
+/// Splits one SUBDATA line into the words of its leading object string and
+/// any trailing values, e.g. `"BRANCH 1 2 1 OPEN" "CHECK"`.
+fn element_words(line: &str) -> Vec<String> {
+    let tokens = tokenize(line);
+    let words: Vec<&str> = match tokens.first() {
+        Some(first) if first.contains(char::is_whitespace) => first.split_whitespace().collect(),
+        _ => tokens.iter().map(String::as_str).collect(),
+    };
+    words
+        .iter()
+        .map(|w| w.trim_matches('\'').to_uppercase())
+        .collect()
+}
+
+/// Parses a CTGElement action such as `BRANCH 1 2 1 OPEN`, `GEN 5 1 OPEN`
+/// or `LOAD 7 1 CHANGEBY -10 PERCENT`.
+fn parse_ctg_element(line: &str) -> Result<ContingencyAction, String> {
+    let words = element_words(line);
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let bus = |word: &str| {
+        word.parse::<usize>()
+            .map_err(|_| format!("Invalid bus number '{}' in: {}", word, line))
+    };
+    let change = |verb: &str, value: &str, unit: Option<&&str>| -> Result<_, String> {
+        let mode = match verb {
+            "SETTO" => ChangeMode::To,
+            "CHANGEBY" => ChangeMode::By,
+            _ => return Err(format!("Unsupported contingency action: {}", line)),
+        };
+        let value: f32 = value
+            .parse()
+            .map_err(|_| format!("Invalid value '{}' in: {}", value, line))?;
+        let unit = match unit {
+            Some(&"PERCENT") => ChangeUnit::Percent,
+            _ => ChangeUnit::Mw,
+        };
+        Ok((mode, value, unit))
+    };
+
+    match words.as_slice() {
+        ["BRANCH", from, to, ckt, "OPEN"] => Ok(ContingencyAction::OpenBranch {
+            from_bus: bus(from)?,
+            to_bus: bus(to)?,
+            ckt: ckt.to_string(),
+        }),
+        ["GEN", bus_id, id, "OPEN"] => Ok(ContingencyAction::TripGenerator {
+            bus_id: bus(bus_id)?,
+            machine_id: id.to_string(),
+        }),
+        ["GEN", bus_id, id, verb, value, unit @ ..] => {
+            let (mode, value, unit) = change(verb, value, unit.first())?;
+            Ok(ContingencyAction::DispatchChange {
+                bus_id: bus(bus_id)?,
+                machine_id: id.to_string(),
+                mode,
+                value,
+                unit,
+            })
+        }
+        // Load changes act on the bus total, as in .con files
+        ["LOAD", bus_id, _, "OPEN"] => Ok(ContingencyAction::LoadChange {
+            bus_id: bus(bus_id)?,
+            mode: ChangeMode::To,
+            value: 0.0,
+            unit: ChangeUnit::Mw,
+        }),
+        ["LOAD", bus_id, _, verb, value, unit @ ..] => {
+            let (mode, value, unit) = change(verb, value, unit.first())?;
+            Ok(ContingencyAction::LoadChange {
+                bus_id: bus(bus_id)?,
+                mode,
+                value,
+                unit,
+            })
+        }
+        _ => Err(format!("Unsupported contingency element: {}", line)),
+    }
+}
+
+/// Adds the branches of an InterfaceElement such as `BRANCH 1 2 1` or
+/// `AREA 1 2` (every tie from area 1 to area 2) to an interface.
+fn add_interface_element(
+    network: &Network,
+    interface: &mut Interface,
+    line: &str,
+) -> Result<(), String> {
+    let words = element_words(line);
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let number = |word: &str| {
+        word.parse::<usize>()
+            .map_err(|_| format!("Invalid number '{}' in: {}", word, line))
+    };
+
+    match words.as_slice() {
+        ["BRANCH", from, to, rest @ ..] => interface.branches.push(InterfaceBranch {
+            from_bus: number(from)?,
+            to_bus: number(to)?,
+            ckt: rest.first().unwrap_or(&"1").to_string(),
+        }),
+        [kind @ ("AREA" | "ZONE"), a, b] => {
+            let (a, b) = (number(a)?, number(b)?);
+            let group: HashMap<usize, usize> = network
+                .buses
+                .iter()
+                .map(|bus| {
+                    (
+                        bus.bus_id,
+                        if *kind == "AREA" { bus.area } else { bus.zone },
+                    )
+                })
+                .collect();
+            for br in &network.branches {
+                let ends = (group.get(&br.from_bus), group.get(&br.to_bus));
+                let (from_bus, to_bus) = match ends {
+                    (Some(&f), Some(&t)) if f == a && t == b => (br.from_bus, br.to_bus),
+                    (Some(&f), Some(&t)) if f == b && t == a => (br.to_bus, br.from_bus),
+                    _ => continue,
+                };
+                interface.branches.push(InterfaceBranch {
+                    from_bus,
+                    to_bus,
+                    ckt: br.ckt.clone(),
+                });
+            }
+        }
+        _ => return Err(format!("Unsupported interface element: {}", line)),
+    }
+    Ok(())
+}
+
+/// Applies AUX data to a network: Bus, Branch, Gen, Load and Shunt records
+/// update the matching element or add a new one, and Contingency and
+/// Interface records are returned. Blocks are applied buses first, whatever
+/// their order in the file. The in-service Shunt records at a bus replace its
+/// shunt, summed when there are several. Reactive capability curves come from
+/// ReactiveCapability records or Gen SUBDATA.
+pub fn apply_aux(network: &mut Network, blocks: &[AuxBlock]) -> Result<AuxData, String> {
+    let of_type = |kind: &'static str| {
+        blocks
+            .iter()
+            .filter(move |b| b.object_type.eq_ignore_ascii_case(kind))
+            .flat_map(|b| b.records.iter().map(move |r| (b, r)))
+    };
+    let bus_number = |block: &AuxBlock, record: &AuxRecord, names: &[&str]| {
+        block
+            .number::<usize>(record, names)
+            .ok_or_else(|| format!("{} record without a bus number", block.object_type))
+    };
+    let bus_index = |network: &Network, bus_id: usize| {
+        network
+            .buses
+            .iter()
+            .position(|b| b.bus_id == bus_id)
+            .ok_or_else(|| format!("AUX record for unknown bus {}", bus_id))
+    };
+
+    for (block, record) in of_type("Bus") {
+        let bus_id = bus_number(block, record, &["BusNum"])?;
+        let index = match network.buses.iter().position(|b| b.bus_id == bus_id) {
+            Some(i) => i,
+            None => {
+                network
+                    .buses
+                    .push(Bus::new(bus_id, String::new(), BusType::PQ));
+                network.buses.len() - 1
+            }
+        };
+        let bus = &mut network.buses[index];
+        if let Some(name) = block.value(record, &["BusName"]) {
+            bus.bus_name = name.to_string();
+        }
+        if let Some(kv) = block.number(record, &["BusNomVolt"]) {
+            bus.nom_voltage = kv;
+        }
+        if let Some(area) = block.number(record, &["AreaNum"]) {
+            bus.area = area;
+        }
+        if let Some(zone) = block.number(record, &["ZoneNum"]) {
+            bus.zone = zone;
+        }
+        if let Some(owner) = block.number(record, &["OwnerNum", "BusOwnerNum"]) {
+            bus.owner = owner;
+        }
+        if let Some(v) = block.number(record, &["BusPUVolt"]) {
+            bus.voltage = v;
+        }
+        if let Some(angle) = block.number(record, &["BusAngle"]) {
+            bus.angle = angle;
+        }
+        if let Some(slack) = block.value(record, &["BusSlack"]) {
+            if closed(slack) {
+                bus.bus_type = BusType::Slack;
+            } else if bus.bus_type == BusType::Slack {
+                bus.bus_type = BusType::PQ;
+            }
+        }
+        if let Some(status) = block.value(record, &["BusStatus"]) {
+            bus.bus_status = closed(status);
+            if !bus.bus_status {
+                bus.bus_type = BusType::OUT;
+            }
+        }
+    }
+
+    for (block, record) in of_type("Branch") {
+        let from_bus = bus_number(block, record, &["BusNum"])?;
+        let to_bus = bus_number(block, record, &["BusNum:1"])?;
+        bus_index(network, from_bus)?;
+        bus_index(network, to_bus)?;
+        let ckt = block.value(record, &["LineCircuit"]).unwrap_or("1");
+        let index = match network.find_branch(from_bus, to_bus, ckt) {
+            Some(i) => i,
+            None => {
+                let id = network.branches.len();
+                let mut branch = Branch::new(id, from_bus, to_bus, BranchType::Line, 0.0, 0.0);
+                branch.ckt = ckt.trim().to_string();
+                network.branches.push(branch);
+                id
+            }
+        };
+        let br = &mut network.branches[index];
+        let device = block
+            .value(record, &["BranchDeviceType"])
+            .unwrap_or("")
+            .to_lowercase();
+        if device == "transformer" || block.value(record, &["LineXfmr"]).is_some_and(closed) {
+            br.branch_type = BranchType::TwoWinding;
+        }
+        if let Some(status) = block.value(record, &["LineStatus"]) {
+            br.branch_status = closed(status);
+        }
+        if let Some(r) = block.number(record, &["LineR"]) {
+            br.resistance = r;
+        }
+        if let Some(x) = block.number::<f32>(record, &["LineX"]) {
+            br.reactance = x;
+        }
+        // Breakers and disconnects have no impedance
+        if br.reactance.abs() < 1e-4 {
+            br.reactance = 1e-4;
+        }
+        if let Some(b) = block.number::<f32>(record, &["LineC"]) {
+            br.from_shunt_susceptance = b / 2.0;
+            br.to_shunt_susceptance = b / 2.0;
+        }
+        if let Some(g) = block.number::<f32>(record, &["LineG"]) {
+            br.from_shunt_conductance = g / 2.0;
+            br.to_shunt_conductance = g / 2.0;
+        }
+        if let Some(tap) = block.number::<f32>(record, &["LineTap"]) {
+            br.tap_ratio = if tap == 0.0 { 1.0 } else { tap };
+        }
+        if let Some(shift) = block.number(record, &["LinePhase"]) {
+            br.phase_shift = shift;
+        }
+        if let Some(rating) = block.number(record, &["LineAMVA"]) {
+            br.operating_limit = rating;
+        }
+        if let Some(rating) = block.number(record, &["LineAMVA:1"]) {
+            br.contingency_limit = rating;
+        }
+        if let Some(name) = block.value(record, &["LineName", "BranchName"]) {
+            br.branch_name = name.to_string();
+        }
+    }
+
+    for (block, record) in of_type("Gen") {
+        let bus_id = bus_number(block, record, &["BusNum"])?;
+        let bus = bus_index(network, bus_id)?;
+        let id = block.value(record, &["GenID"]).unwrap_or("1").trim();
+        let index = match network.find_generator(bus_id, id) {
+            Some(i) => i,
+            None => {
+                let name = format!("Bus{}-{}", bus_id, id);
+                let mut generator = Generator::new(network.generators.len(), bus_id, name);
+                generator.p_max = 9999.0;
+                generator.p_min = -9999.0;
+                network.generators.push(generator);
+                network.generators.len() - 1
+            }
+        };
+        let generator = &mut network.generators[index];
+        if let Some(status) = block.value(record, &["GenStatus"]) {
+            generator.gen_status = closed(status);
+        }
+        if let Some(p) = block.number(record, &["GenMW"]) {
+            generator.p_gen = p;
+        }
+        if let Some(q) = block.number(record, &["GenMvr", "GenMVR"]) {
+            generator.q_gen = q;
+        }
+        if let Some(p) = block.number(record, &["GenMWMax"]) {
+            generator.p_max = p;
+        }
+        if let Some(p) = block.number(record, &["GenMWMin"]) {
+            generator.p_min = p;
+        }
+        if let Some(q) = block.number(record, &["GenMvrMax", "GenMVRMax"]) {
+            generator.q_max = q;
+        }
+        if let Some(q) = block.number(record, &["GenMvrMin", "GenMVRMin"]) {
+            generator.q_min = q;
+        }
+        if let Some(v) = block.number(record, &["GenVoltSet"]) {
+            generator.v_setpoint = v;
+        }
+        // An in-service unit regulates its bus
+        if generator.gen_status && network.buses[bus].bus_type == BusType::PQ {
+            network.buses[bus].bus_type = BusType::PV;
+        }
+        // SUBDATA ReactiveCapability lines are MW, MinMVR, MaxMVR
+        let curve: Vec<CapabilityPoint> = block
+            .subdata(record, "ReactiveCapability")
+            .filter_map(|line| {
+                let v: Vec<f32> = element_words(line)
+                    .iter()
+                    .filter_map(|w| w.parse().ok())
+                    .collect();
+                (v.len() >= 3).then(|| CapabilityPoint {
+                    p: v[0],
+                    q_min: v[1],
+                    q_max: v[2],
+                })
+            })
+            .collect();
+        if !curve.is_empty() {
+            set_curve(network, bus_id, id, curve)?;
+        }
+    }
+
+    let mut curves: Vec<((usize, String), Vec<CapabilityPoint>)> = Vec::new();
+    for (block, record) in of_type("ReactiveCapability") {
+        let bus_id = bus_number(block, record, &["BusNum"])?;
+        let id = block.value(record, &["GenID"]).unwrap_or("1").trim();
+        let key = (bus_id, id.to_string());
+        let point = CapabilityPoint {
+            p: block.number(record, &["GenMW"]).unwrap_or(0.0),
+            q_min: block
+                .number(record, &["GenMVRMin", "GenMvrMin"])
+                .unwrap_or(0.0),
+            q_max: block
+                .number(record, &["GenMVRMax", "GenMvrMax"])
+                .unwrap_or(0.0),
+        };
+        match curves.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, points)) => points.push(point),
+            None => curves.push((key, vec![point])),
+        }
+    }
+    for ((bus_id, id), points) in curves {
+        set_curve(network, bus_id, &id, points)?;
+    }
+
+    for (block, record) in of_type("Load") {
+        let bus_id = bus_number(block, record, &["BusNum"])?;
+        bus_index(network, bus_id)?;
+        let id = block.value(record, &["LoadID"]).unwrap_or("1").trim();
+        let name = format!("Bus{}-{}", bus_id, id);
+        let in_service = block.value(record, &["LoadStatus"]).is_none_or(closed);
+        let index = match network.find_load(bus_id, id) {
+            Some(i) => i,
+            None => {
+                network
+                    .loads
+                    .push(Load::new(network.loads.len(), bus_id, name, 0.0, 0.0));
+                network.loads.len() - 1
+            }
+        };
+        let load = &mut network.loads[index];
+        load.load_status = in_service;
+        // Constant power, current and impedance parts at 1 pu voltage
+        let parts: [(&str, &mut f32); 6] = [
+            ("LoadSMW", &mut load.real_load),
+            ("LoadSMVR", &mut load.imag_load),
+            ("LoadIMW", &mut load.real_current),
+            ("LoadIMVR", &mut load.imag_current),
+            ("LoadZMW", &mut load.real_admittance),
+            ("LoadZMVR", &mut load.imag_admittance),
+        ];
+        for (field, value) in parts {
+            if let Some(v) = block.number(record, &[field]) {
+                *value = v;
+            }
+        }
+    }
+
+    // Shunt records replace the bus shunt, so clear every bus they name first
+    let mut shunt_buses = Vec::new();
+    for (block, record) in of_type("Shunt") {
+        let bus = bus_index(network, bus_number(block, record, &["BusNum"])?)?;
+        if !shunt_buses.contains(&bus) {
+            network.buses[bus].real_shunt = 0.0;
+            network.buses[bus].imag_shunt = 0.0;
+            shunt_buses.push(bus);
+        }
+    }
+    for (block, record) in of_type("Shunt") {
+        let bus_id = bus_number(block, record, &["BusNum"])?;
+        let bus = bus_index(network, bus_id)?;
+        if !block
+            .value(record, &["SSStatus", "ShuntStatus"])
+            .is_none_or(closed)
+        {
+            continue;
+        }
+        network.buses[bus].real_shunt += block.number(record, &["SSNMW"]).unwrap_or(0.0);
+        network.buses[bus].imag_shunt += block.number(record, &["SSNMVR"]).unwrap_or(0.0);
+    }
+
+    let mut data = AuxData::default();
+    for (block, record) in of_type("Contingency") {
+        let name = block
+            .value(record, &["CTGLabel", "Name"])
+            .ok_or("Contingency record without a name")?;
+        let mut contingency = Contingency::new(name.to_string());
+        for line in block.subdata(record, "CTGElement") {
+            contingency
+                .actions
+                .push(parse_ctg_element(line).map_err(|e| format!("{}: {}", name, e))?);
+        }
+        data.contingencies.push(contingency);
+    }
+
+    for (block, record) in of_type("Interface") {
+        let name = block
+            .value(record, &["IntName", "Name"])
+            .ok_or("Interface record without a name")?;
+        let mut interface = Interface::new(name.to_string());
+        interface.operating_limit = block.number(record, &["IntLimA"]).unwrap_or(0.0);
+        interface.contingency_limit = block
+            .number(record, &["IntLimB"])
+            .unwrap_or(interface.operating_limit);
+        for line in block.subdata(record, "InterfaceElement") {
+            add_interface_element(network, &mut interface, line)
+                .map_err(|e| format!("{}: {}", name, e))?;
+        }
+        data.interfaces.push(interface);
+    }
+
+    network.rebuild_bus_map();
+    Ok(data)
+}
+
+/// Builds a network from an AUX file alone, named after the file.
+pub fn read_aux_case(path: &str) -> Result<(Network, AuxData), String> {
+    let blocks = read_aux(path)?;
+    let name = Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut network = Network::new(name, 100.0, 60.0);
+    let data = apply_aux(&mut network, &blocks)?;
+    if network.buses.is_empty() {
+        return Err(format!("No bus data in {}", path));
+    }
+    Ok((network, data))
+}
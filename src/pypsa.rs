@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::case::*;
+
+/// A CSV file read into its header and rows.
+#[derive(Default)]
+pub(crate) struct CsvTable {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// A non-empty value of the named column.
+    pub fn get<'a>(&self, row: &'a [String], column: &str) -> Option<&'a str> {
+        let i = self.header.iter().position(|h| h == column)?;
+        row.get(i).map(|v| v.trim()).filter(|v| !v.is_empty())
+    }
+
+    pub fn num(&self, row: &[String], column: &str) -> Option<f32> {
+        self.get(row, column)?.parse().ok()
+    }
+
+    /// A boolean column as written by pandas (True / False).
+    pub fn flag(&self, row: &[String], column: &str, default: bool) -> bool {
+        match self.get(row, column) {
+            Some(v) => v.eq_ignore_ascii_case("true") || v == "1",
+            None => default,
+        }
+    }
+}
+
+/// Splits CSV text into records, honouring quoted fields with embedded
+/// commas, doubled quotes and line breaks.
+pub(crate) fn parse_csv(content: &str) -> CsvTable {
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| r.iter().any(|f| !f.trim().is_empty()));
+
+    let mut records = records.into_iter();
+    CsvTable {
+        header: records
+            .next()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|h| h.trim().to_string())
+            .collect(),
+        rows: records.collect(),
+    }
+}
+
+/// Formats one CSV record, quoting fields that need it.
+pub(crate) fn csv_line(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.clone()
+            }
+        })
+        .collect();
+    quoted.join(",") + "\n"
+}
+
+/// Reads a PyPSA CSV folder into a Network.
+pub fn read_pypsa(dir: &str) -> Result<Network, String> {
+    let read = |file: &str| -> Result<Option<CsvTable>, String> {
+        let path = Path::new(dir).join(file);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&path)
+            .map(|content| Some(parse_csv(&content)))
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+    };
+    let table = |file: &str| read(file).map(Option::unwrap_or_default);
+
+    let buses = read("buses.csv")?.ok_or_else(|| format!("No buses.csv in {}", dir))?;
+    let name = match read("network.csv")? {
+        Some(t) => t
+            .rows
+            .first()
+            .and_then(|r| t.get(r, "name"))
+            .unwrap_or("")
+            .to_string(),
+        None => String::new(),
+    };
+    build_network(
+        name,
+        &buses,
+        &table("lines.csv")?,
+        &table("transformers.csv")?,
+        &table("generators.csv")?,
+        &table("loads.csv")?,
+        &table("shunt_impedances.csv")?,
+    )
+}
+
+/// Builds a Network from PyPSA component tables. Buses named by integers
+/// keep them as bus ids, other names are numbered 1..n in file order and kept
+/// as bus names. Line impedances in ohms and siemens are converted on the
+/// bus0 nominal voltage, transformer ones from per unit on `s_nom`; the
+/// generator `control` attribute sets the bus type.
+fn build_network(
+    name: String,
+    buses: &CsvTable,
+    lines: &CsvTable,
+    transformers: &CsvTable,
+    generators: &CsvTable,
+    loads: &CsvTable,
+    shunts: &CsvTable,
+) -> Result<Network, String> {
+    let mut network = Network::new(name, 100.0, 50.0);
+    let s_base = network.s_base;
+
+    let numeric = buses.rows.iter().all(|r| {
+        buses
+            .get(r, "name")
+            .is_some_and(|n| n.parse::<usize>().is_ok())
+    });
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    for (i, row) in buses.rows.iter().enumerate() {
+        let name = buses
+            .get(row, "name")
+            .ok_or("Bus without a name in buses.csv")?;
+        let bus_id = if numeric {
+            name.parse().unwrap_or(0)
+        } else {
+            i + 1
+        };
+        let mut bus = Bus::new(
+            bus_id,
+            if numeric {
+                String::new()
+            } else {
+                name.to_string()
+            },
+            BusType::PQ,
+        );
+        bus.nom_voltage = buses.num(row, "v_nom").unwrap_or(1.0);
+        bus.voltage = buses.num(row, "v_mag_pu_set").unwrap_or(1.0);
+        if let Some(v) = buses.num(row, "v_mag_pu_min") {
+            bus.v_min_operating = v;
+        }
+        if let Some(v) = buses.num(row, "v_mag_pu_max").filter(|v| v.is_finite()) {
+            bus.v_max_operating = v;
+        }
+        ids.insert(name.to_string(), bus_id);
+        network.buses.push(bus);
+    }
+    let index: HashMap<usize, usize> = network
+        .buses
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.bus_id, i))
+        .collect();
+    let bus_of = |table: &CsvTable, row: &[String], column: &str| -> Result<usize, String> {
+        let name = table.get(row, column).unwrap_or("");
+        ids.get(name)
+            .copied()
+            .ok_or_else(|| format!("Unknown bus '{}' in {}", name, column))
+    };
+    let kv = |network: &Network, bus_id: usize| match network.buses[index[&bus_id]].nom_voltage {
+        v if v > 0.0 => v,
+        _ => 1.0,
+    };
+    let element_name = |bus_id: usize, name: Option<&str>| {
+        let prefix = format!("Bus{}-", bus_id);
+        match name {
+            Some(n) if n.starts_with(&prefix) => n.to_string(),
+            Some(n) => format!("{}{}", prefix, n),
+            None => format!("{}1", prefix),
+        }
+    };
+
+    for row in &generators.rows {
+        let bus_id = bus_of(generators, row, "bus")?;
+        let mut generator = Generator::new(
+            network.generators.len(),
+            bus_id,
+            element_name(bus_id, generators.get(row, "name")),
+        );
+        let p_nom = generators.num(row, "p_nom").unwrap_or(0.0);
+        generator.gen_status = generators.flag(row, "active", true);
+        generator.p_gen = generators.num(row, "p_set").unwrap_or(0.0);
+        generator.q_gen = generators.num(row, "q_set").unwrap_or(0.0);
+        generator.p_max = p_nom * generators.num(row, "p_max_pu").unwrap_or(1.0);
+        generator.p_min = p_nom * generators.num(row, "p_min_pu").unwrap_or(0.0);
+
+        let bus = &mut network.buses[index[&bus_id]];
+        generator.v_setpoint = bus.voltage;
+        match generators.get(row, "control") {
+            Some("Slack") => bus.bus_type = BusType::Slack,
+            Some("PV") if bus.bus_type == BusType::PQ => bus.bus_type = BusType::PV,
+            _ => {}
+        }
+        network.generators.push(generator);
+    }
+
+    for row in &loads.rows {
+        let bus_id = bus_of(loads, row, "bus")?;
+        let mut load = Load::new(
+            network.loads.len(),
+            bus_id,
+            element_name(bus_id, loads.get(row, "name")),
+            loads.num(row, "p_set").unwrap_or(0.0),
+            loads.num(row, "q_set").unwrap_or(0.0),
+        );
+        load.load_status = loads.flag(row, "active", true);
+        network.loads.push(load);
+    }
+
+    for row in &shunts.rows {
+        let bus_id = bus_of(shunts, row, "bus")?;
+        let v2 = kv(&network, bus_id).powi(2);
+        let bus = &mut network.buses[index[&bus_id]];
+        bus.real_shunt += shunts.num(row, "g").unwrap_or(0.0) * v2;
+        bus.imag_shunt += shunts.num(row, "b").unwrap_or(0.0) * v2;
+    }
+
+    for row in &lines.rows {
+        let from_bus = bus_of(lines, row, "bus0")?;
+        let to_bus = bus_of(lines, row, "bus1")?;
+        let z_base = kv(&network, from_bus).powi(2) / s_base;
+        let value = |column: &str| lines.num(row, column).unwrap_or(0.0);
+        let rating = value("s_nom");
+        network.branches.push(Branch {
+            branch_type: BranchType::Line,
+            id: network.branches.len(),
+            from_bus,
+            to_bus,
+            ckt: String::new(),
+            branch_name: lines.get(row, "name").unwrap_or("").to_string(),
+            branch_status: lines.flag(row, "active", true),
+            resistance: value("r") / z_base,
+            reactance: value("x") / z_base,
+            from_shunt_conductance: value("g") * z_base / 2.0,
+            from_shunt_susceptance: value("b") * z_base / 2.0,
+            to_shunt_conductance: value("g") * z_base / 2.0,
+            to_shunt_susceptance: value("b") * z_base / 2.0,
+            tap_ratio: 1.0,
+            phase_shift: 0.0,
+            impedance_table: None,
+            operating_limit: rating,
+            contingency_limit: rating,
+            flow: 0.0,
+        });
+    }
+
+    for row in &transformers.rows {
+        let from_bus = bus_of(transformers, row, "bus0")?;
+        let to_bus = bus_of(transformers, row, "bus1")?;
+        let value = |column: &str, default: f32| transformers.num(row, column).unwrap_or(default);
+        let s_nom = match value("s_nom", 0.0) {
+            s if s > 0.0 => s,
+            _ => s_base,
+        };
+        let to_system = s_base / s_nom;
+        let tap = match value("tap_ratio", 1.0) {
+            t if t > 0.0 => t,
+            _ => 1.0,
+        };
+        network.branches.push(Branch {
+            branch_type: BranchType::TwoWinding,
+            id: network.branches.len(),
+            from_bus,
+            to_bus,
+            ckt: String::new(),
+            branch_name: transformers.get(row, "name").unwrap_or("").to_string(),
+            branch_status: transformers.flag(row, "active", true),
+            resistance: value("r", 0.0) * to_system,
+            reactance: value("x", 0.0) * to_system,
+            from_shunt_conductance: value("g", 0.0) / to_system,
+            from_shunt_susceptance: value("b", 0.0) / to_system,
+            to_shunt_conductance: 0.0,
+            to_shunt_susceptance: 0.0,
+            // A tap on the bus1 side is the inverse ratio seen from bus0
+            tap_ratio: if value("tap_side", 0.0) == 1.0 {
+                1.0 / tap
+            } else {
+                tap
+            },
+            phase_shift: value("phase_shift", 0.0),
+            impedance_table: None,
+            operating_limit: value("s_nom", 0.0),
+            contingency_limit: value("s_nom", 0.0),
+            flow: 0.0,
+        });
+    }
+
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// Writes a Network as a PyPSA CSV folder (network.csv, buses.csv,
+/// lines.csv, transformers.csv, generators.csv, loads.csv and, when any bus
+/// has a shunt, shunt_impedances.csv). Bus ids are used as bus names and
+/// branch ids as line and transformer names.
+pub fn write_pypsa(dir: &str, network: &Network) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir, e))?;
+    let s_base = network.s_base;
+    let bus_kv: HashMap<usize, f32> = network
+        .buses
+        .iter()
+        .map(|b| {
+            (
+                b.bus_id,
+                if b.nom_voltage > 0.0 {
+                    b.nom_voltage
+                } else {
+                    1.0
+                },
+            )
+        })
+        .collect();
+    let kv = |bus_id: usize| bus_kv.get(&bus_id).copied().unwrap_or(1.0);
+    let bus_type: HashMap<usize, BusType> = network
+        .buses
+        .iter()
+        .map(|b| (b.bus_id, b.bus_type))
+        .collect();
+
+    let mut files: Vec<(&str, String)> = Vec::new();
+    let mut table = |file: &'static str, header: &[&str], rows: Vec<Vec<String>>| {
+        let mut out = csv_line(&header.iter().map(|h| h.to_string()).collect::<Vec<_>>());
+        for row in rows {
+            out.push_str(&csv_line(&row));
+        }
+        files.push((file, out));
+    };
+
+    table(
+        "network.csv",
+        &["name"],
+        vec![vec![network.case_name.clone()]],
+    );
+
+    table(
+        "buses.csv",
+        &[
+            "name",
+            "v_nom",
+            "carrier",
+            "v_mag_pu_set",
+            "v_mag_pu_min",
+            "v_mag_pu_max",
+        ],
+        network
+            .buses
+            .iter()
+            .map(|b| {
+                // Regulated buses hold their generators' setpoint
+                let setpoint = network
+                    .generators
+                    .iter()
+                    .find(|g| g.gen_bus_id == b.bus_id && b.bus_type != BusType::PQ)
+                    .map_or(b.voltage, |g| g.v_setpoint);
+                vec![
+                    b.bus_id.to_string(),
+                    b.nom_voltage.to_string(),
+                    "AC".to_string(),
+                    setpoint.to_string(),
+                    b.v_min_operating.to_string(),
+                    b.v_max_operating.to_string(),
+                ]
+            })
+            .collect(),
+    );
+
+    table(
+        "generators.csv",
+        &[
+            "name", "bus", "control", "p_nom", "p_set", "q_set", "p_max_pu", "p_min_pu", "active",
+        ],
+        network
+            .generators
+            .iter()
+            .map(|g| {
+                let control = match bus_type.get(&g.gen_bus_id) {
+                    Some(BusType::Slack) => "Slack",
+                    Some(BusType::PV) => "PV",
+                    _ => "PQ",
+                };
+                let p_nom = g.p_max.abs().max(g.p_min.abs()).max(1.0);
+                vec![
+                    g.gen_name.clone(),
+                    g.gen_bus_id.to_string(),
+                    control.to_string(),
+                    p_nom.to_string(),
+                    g.p_gen.to_string(),
+                    g.q_gen.to_string(),
+                    (g.p_max / p_nom).to_string(),
+                    (g.p_min / p_nom).to_string(),
+                    pandas_bool(g.gen_status),
+                ]
+            })
+            .collect(),
+    );
+
+    table(
+        "loads.csv",
+        &["name", "bus", "p_set", "q_set", "active"],
+        network
+            .loads
+            .iter()
+            .map(|l| {
+                // PyPSA loads are constant power, so ZIP loads are taken at 1 pu
+                let (p, q) = l.power_at(1.0);
+                vec![
+                    l.load_name.clone(),
+                    l.bus_id.to_string(),
+                    p.to_string(),
+                    q.to_string(),
+                    pandas_bool(l.load_status),
+                ]
+            })
+            .collect(),
+    );
+
+    let (lines, transformers): (Vec<&Branch>, Vec<&Branch>) = network
+        .branches
+        .iter()
+        .partition(|br| br.branch_type == BranchType::Line);
+    table(
+        "lines.csv",
+        &[
+            "name", "bus0", "bus1", "r", "x", "g", "b", "s_nom", "active",
+        ],
+        lines
+            .iter()
+            .map(|br| {
+                let z_base = kv(br.from_bus).powi(2) / s_base;
+                vec![
+                    br.id.to_string(),
+                    br.from_bus.to_string(),
+                    br.to_bus.to_string(),
+                    (br.resistance * z_base).to_string(),
+                    (br.reactance * z_base).to_string(),
+                    ((br.from_shunt_conductance + br.to_shunt_conductance) / z_base).to_string(),
+                    ((br.from_shunt_susceptance + br.to_shunt_susceptance) / z_base).to_string(),
+                    br.operating_limit.to_string(),
+                    pandas_bool(br.branch_status),
+                ]
+            })
+            .collect(),
+    );
+    table(
+        "transformers.csv",
+        &[
+            "name",
+            "bus0",
+            "bus1",
+            "r",
+            "x",
+            "g",
+            "b",
+            "s_nom",
+            "tap_ratio",
+            "tap_side",
+            "phase_shift",
+            "active",
+        ],
+        transformers
+            .iter()
+            .map(|br| {
+                let s_nom = if br.operating_limit > 0.0 {
+                    br.operating_limit
+                } else {
+                    s_base
+                };
+                let to_nominal = s_nom / s_base;
+                vec![
+                    br.id.to_string(),
+                    br.from_bus.to_string(),
+                    br.to_bus.to_string(),
+                    (br.resistance * to_nominal).to_string(),
+                    (br.reactance * to_nominal).to_string(),
+                    ((br.from_shunt_conductance + br.to_shunt_conductance) / to_nominal)
+                        .to_string(),
+                    ((br.from_shunt_susceptance + br.to_shunt_susceptance) / to_nominal)
+                        .to_string(),
+                    s_nom.to_string(),
+                    br.tap_ratio.to_string(),
+                    "0".to_string(),
+                    br.phase_shift.to_string(),
+                    pandas_bool(br.branch_status),
+                ]
+            })
+            .collect(),
+    );
+
+    let shunt_buses: Vec<&Bus> = network
+        .buses
+        .iter()
+        .filter(|b| b.real_shunt != 0.0 || b.imag_shunt != 0.0)
+        .collect();
+    if !shunt_buses.is_empty() {
+        table(
+            "shunt_impedances.csv",
+            &["name", "bus", "g", "b"],
+            shunt_buses
+                .iter()
+                .map(|b| {
+                    let v2 = kv(b.bus_id).powi(2);
+                    vec![
+                        format!("Bus{}-shunt", b.bus_id),
+                        b.bus_id.to_string(),
+                        (b.real_shunt / v2).to_string(),
+                        (b.imag_shunt / v2).to_string(),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    for (file, content) in files {
+        let path = Path::new(dir).join(file);
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// A boolean as pandas writes it.
+fn pandas_bool(value: bool) -> String {
+    if value { "True" } else { "False" }.to_string()
+}
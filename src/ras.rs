@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::case::*;
+use crate::contingency::*;
+use crate::monitor::ViolationFilter;
+
+/// Condition that arms a remedial action scheme.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RasCondition {
+    /// |flow| in MW above `limit` on the branch FROM BUS i TO BUS j CIRCUIT ckt
+    FlowAbove {
+        from_bus: usize,
+        to_bus: usize,
+        ckt: String,
+        limit: f32,
+    },
+    /// Bus voltage magnitude in per unit below `limit`
+    VoltageBelow { bus_id: usize, limit: f32 },
+}
+
+impl RasCondition {
+    /// Check the condition against a solved network.
+    pub fn is_met(&self, network: &Network) -> bool {
+        match self {
+            RasCondition::FlowAbove {
+                from_bus,
+                to_bus,
+                ckt,
+                limit,
+            } => network
+                .find_branches(*from_bus, *to_bus, ckt)
+                .iter()
+                .map(|&i| &network.branches[i])
+                .any(|br| br.branch_status && br.flow.abs() > *limit),
+            // Only meaningful against a solution that updates voltage magnitudes
+            RasCondition::VoltageBelow { bus_id, limit } => network
+                .buses
+                .iter()
+                .any(|b| b.bus_id == *bus_id && b.bus_status && b.voltage < *limit),
+        }
+    }
+}
+
+/// RasCondition display implementation.
+impl fmt::Display for RasCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RasCondition::FlowAbove {
+                from_bus,
+                to_bus,
+                ckt,
+                limit,
+            } => write!(f, "FLOW {}-{}({}) > {:.1} MW", from_bus, to_bus, ckt, limit),
+            RasCondition::VoltageBelow { bus_id, limit } => {
+                write!(f, "V {} < {:.4} pu", bus_id, limit)
+            }
+        }
+    }
+}
+
+/// A remedial action scheme: when the condition is met after a contingency,
+/// its actions are applied and the case is solved again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemedialAction {
+    pub name: String,
+    pub condition: RasCondition,
+    pub actions: Vec<ContingencyAction>,
+}
+
+impl RemedialAction {
+    pub fn new(name: String, condition: RasCondition) -> Self {
+        Self {
+            name,
+            condition,
+            actions: Vec::new(),
+        }
+    }
+}
+
+/// Contingency result before and after remedial actions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RasResult {
+    pub pre_ras: ContingencyResult,
+    /// Solution after the schemes fired; None when no scheme was armed.
+    pub post_ras: Option<ContingencyResult>,
+    /// Names of the schemes that fired, in firing order.
+    pub fired: Vec<String>,
+}
+
+/// RasResult display implementation.
+impl fmt::Display for RasResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pre_ras)?;
+        if let Some(post) = &self.post_ras {
+            write!(f, "\n  after RAS [{}]: {}", self.fired.join(", "), post)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rejects schemes the DC contingency engine cannot evaluate: the DC solution
+/// leaves voltage magnitudes at their initial values, so a voltage condition
+/// would never fire.
+pub fn check_schemes(schemes: &[RemedialAction]) -> Result<(), String> {
+    match schemes
+        .iter()
+        .find(|s| matches!(s.condition, RasCondition::VoltageBelow { .. }))
+    {
+        Some(scheme) => Err(format!(
+            "{}: voltage conditions are not evaluated by the DC load flow",
+            scheme.name
+        )),
+        None => Ok(()),
+    }
+}
+
+impl Network {
+    /// Runs a contingency, then fires every scheme whose condition is met and
+    /// solves again. Schemes armed by the post-RAS solution fire in later
+    /// passes; each scheme fires at most once.
+    pub fn run_contingency_with_ras(
+        &self,
+        contingency: &Contingency,
+        schemes: &[RemedialAction],
+    ) -> RasResult {
+        self.run_contingency_with_ras_filtered(contingency, schemes, &ViolationFilter::default())
+    }
+
+    /// Like `run_contingency_with_ras`, reporting only filtered elements.
+    pub fn run_contingency_with_ras_filtered(
+        &self,
+        contingency: &Contingency,
+        schemes: &[RemedialAction],
+        filter: &ViolationFilter,
+    ) -> RasResult {
+        let mut post = self.clone();
+        if let Err(e) = check_schemes(schemes).and_then(|_| post.apply_contingency(contingency)) {
+            return RasResult {
+                pre_ras: ContingencyResult::failed(&contingency.name, e),
+                post_ras: None,
+                fired: Vec::new(),
+            };
+        }
+        let mut solved = post.dc_approximation();
+        let pre_ras = ContingencyResult::from_solution(&contingency.name, &post, solved, filter);
+
+        // Schemes are tracked by index; names need not be unique
+        let mut fired: Vec<usize> = Vec::new();
+        let mut error: Option<String> = None;
+        while solved && error.is_none() {
+            let armed: Vec<usize> = (0..schemes.len())
+                .filter(|i| !fired.contains(i) && schemes[*i].condition.is_met(&post))
+                .collect();
+            if armed.is_empty() {
+                break;
+            }
+            for i in armed {
+                fired.push(i);
+                let scheme = &schemes[i];
+                for action in &scheme.actions {
+                    if let Err(e) = post.apply_action(action) {
+                        error = Some(format!("{}: {}", scheme.name, e));
+                    }
+                }
+            }
+            solved = post.dc_approximation();
+        }
+
+        let post_ras = if let Some(e) = error {
+            Some(ContingencyResult::failed(&contingency.name, e))
+        } else if fired.is_empty() {
+            None
+        } else {
+            Some(ContingencyResult::from_solution(
+                &contingency.name,
+                &post,
+                solved,
+                filter,
+            ))
+        };
+
+        RasResult {
+            pre_ras,
+            post_ras,
+            fired: fired.iter().map(|&i| schemes[i].name.clone()).collect(),
+        }
+    }
+
+    /// Runs each contingency independently with the given schemes in effect.
+    pub fn run_contingencies_with_ras(
+        &self,
+        contingencies: &[Contingency],
+        schemes: &[RemedialAction],
+    ) -> Vec<RasResult> {
+        self.run_contingencies_with_ras_filtered(
+            contingencies,
+            schemes,
+            &ViolationFilter::default(),
+        )
+    }
+
+    /// Runs each contingency with the schemes in effect, reporting only
+    /// filtered elements.
+    pub fn run_contingencies_with_ras_filtered(
+        &self,
+        contingencies: &[Contingency],
+        schemes: &[RemedialAction],
+        filter: &ViolationFilter,
+    ) -> Vec<RasResult> {
+        contingencies
+            .iter()
+            .map(|c| self.run_contingency_with_ras_filtered(c, schemes, filter))
+            .collect()
+    }
+}
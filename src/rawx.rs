@@ -0,0 +1,942 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+
+use crate::case::*;
+use crate::export::{bus_code, dc_mode, facts_mode, line_charging, status, vsc_codes, vsc_links};
+use crate::parse::{facts_series, winding_ratio};
+
+/// Record types mapped onto the Network; everything else is passed through.
+const MODELED: [&str; 13] = [
+    "caseid",
+    "bus",
+    "load",
+    "fixshunt",
+    "generator",
+    "acline",
+    "sysswd",
+    "transformer",
+    "twotermdc",
+    "vscdc",
+    "impcor",
+    "msline",
+    "facts",
+];
+
+/// One RAWX record table with its field names resolved to positions.
+struct Table<'a> {
+    fields: HashMap<String, usize>,
+    rows: Vec<&'a [Value]>,
+}
+
+impl<'a> Table<'a> {
+    /// Looks up `name` in the network object. Single-record tables such as
+    /// caseid hold one flat data array; the rest hold an array of rows.
+    fn new(network: &'a Map<String, Value>, name: &str) -> Option<Self> {
+        let table = network.get(name)?;
+        let fields = table
+            .get("fields")?
+            .as_array()?
+            .iter()
+            .enumerate()
+            .filter_map(|(i, f)| Some((f.as_str()?.to_lowercase(), i)))
+            .collect();
+        let data = table.get("data")?.as_array()?;
+        let rows = if data.iter().all(Value::is_array) {
+            data.iter()
+                .filter_map(|r| r.as_array().map(Vec::as_slice))
+                .collect()
+        } else {
+            vec![data.as_slice()]
+        };
+        Some(Self { fields, rows })
+    }
+
+    fn value<'r>(&self, row: &'r [Value], name: &str) -> Option<&'r Value> {
+        self.fields.get(name).and_then(|&i| row.get(i))
+    }
+
+    /// Numeric field, accepting numbers written as strings.
+    fn num(&self, row: &[Value], name: &str, default: f32) -> f32 {
+        match self.value(row, name) {
+            Some(Value::Number(n)) => n.to_string().parse().unwrap_or(default),
+            Some(Value::String(s)) => s.trim().parse().unwrap_or(default),
+            _ => default,
+        }
+    }
+
+    fn int(&self, row: &[Value], name: &str, default: usize) -> usize {
+        match self.value(row, name) {
+            Some(Value::Number(n)) => n.as_u64().map(|v| v as usize).unwrap_or(default),
+            Some(Value::String(s)) => s.trim().parse().unwrap_or(default),
+            _ => default,
+        }
+    }
+
+    fn text(&self, row: &[Value], name: &str) -> String {
+        match self.value(row, name) {
+            Some(Value::String(s)) => s.trim().to_string(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Reads a PSS/E RAWX file into a Network.
+pub fn read_rawx(path: &str) -> Result<Network, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_rawx_str(&content)
+}
+
+/// Parses a PSS/E RAWX JSON string into a Network. Record types mantis does
+/// not model are kept in `Network::passthrough`.
+pub fn parse_rawx_str(content: &str) -> Result<Network, String> {
+    let root: Value =
+        serde_json::from_str(content).map_err(|e| format!("Error parsing RAWX: {}", e))?;
+    let network_obj = root
+        .get("network")
+        .and_then(Value::as_object)
+        .ok_or("RAWX has no network object")?;
+
+    let (case_name, s_base, frequency) = match Table::new(network_obj, "caseid") {
+        Some(t) => {
+            let row = t.rows.first().copied().unwrap_or(&[]);
+            (
+                t.text(row, "title1"),
+                t.num(row, "sbase", 100.0),
+                t.num(row, "basfrq", 60.0),
+            )
+        }
+        None => (String::new(), 100.0, 60.0),
+    };
+    let mut network = Network::new(case_name, s_base, frequency);
+
+    if let Some(t) = Table::new(network_obj, "bus") {
+        for row in &t.rows {
+            let ide = t.int(row, "ide", 1);
+            let bus_type = match ide {
+                3 => BusType::Slack,
+                2 => BusType::PV,
+                1 => BusType::PQ,
+                4 => BusType::OUT,
+                _ => return Err(format!("Unknown bus type code: {}", ide)),
+            };
+            network.buses.push(Bus {
+                bus_id: t.int(row, "ibus", 0),
+                bus_name: t.text(row, "name"),
+                bus_type,
+                nom_voltage: t.num(row, "baskv", 0.0),
+                area: t.int(row, "area", 1),
+                zone: t.int(row, "zone", 1),
+                owner: t.int(row, "owner", 1),
+                bus_status: ide != 4,
+                voltage: t.num(row, "vm", 1.0),
+                angle: t.num(row, "va", 0.0),
+                real_shunt: 0.0,
+                imag_shunt: 0.0,
+                v_min_operating: t.num(row, "nvlo", 0.9),
+                v_min_contingency: t.num(row, "evlo", 0.9),
+                v_max_operating: t.num(row, "nvhi", 1.1),
+                v_max_contingency: t.num(row, "evhi", 1.1),
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "load") {
+        for row in &t.rows {
+            let bus_id = t.int(row, "ibus", 0);
+            network.loads.push(Load {
+                load_id: network.loads.len(),
+                bus_id,
+                load_ident: t.text(row, "loadid"),
+                load_name: format!("Bus{}-{}", bus_id, t.text(row, "loadid")),
+                load_status: t.int(row, "stat", 1) == 1,
+                real_load: t.num(row, "pl", 0.0),
+                imag_load: t.num(row, "ql", 0.0),
+                real_current: t.num(row, "ip", 0.0),
+                imag_current: t.num(row, "iq", 0.0),
+                real_admittance: t.num(row, "yp", 0.0),
+                // YQ is negative for an inductive load
+                imag_admittance: 0.0 - t.num(row, "yq", 0.0),
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "fixshunt") {
+        for row in t.rows.iter().filter(|row| t.int(row, "stat", 1) == 1) {
+            let bus_id = t.int(row, "ibus", 0);
+            if let Some(bus) = network.buses.iter_mut().find(|b| b.bus_id == bus_id) {
+                bus.real_shunt += t.num(row, "gl", 0.0);
+                bus.imag_shunt += t.num(row, "bl", 0.0);
+            }
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "generator") {
+        for row in &t.rows {
+            let bus_id = t.int(row, "ibus", 0);
+            network.generators.push(Generator {
+                gen_id: network.generators.len(),
+                gen_bus_id: bus_id,
+                machine_id: t.text(row, "machid"),
+                gen_name: format!("Bus{}-{}", bus_id, t.text(row, "machid")),
+                gen_status: t.int(row, "stat", 1) == 1,
+                p_gen: t.num(row, "pg", 0.0),
+                q_gen: t.num(row, "qg", 0.0),
+                v_setpoint: t.num(row, "vs", 1.0),
+                p_min: t.num(row, "pb", 0.0),
+                p_max: t.num(row, "pt", 0.0),
+                q_min: t.num(row, "qb", 0.0),
+                q_max: t.num(row, "qt", 0.0),
+                cost: None,
+                capability: Vec::new(),
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "acline") {
+        for row in &t.rows {
+            let b = t.num(row, "bpu", 0.0);
+            network.branches.push(Branch {
+                branch_type: BranchType::Line,
+                id: network.branches.len(),
+                from_bus: t.int(row, "ibus", 0),
+                to_bus: t.int(row, "jbus", 0),
+                ckt: t.text(row, "ckt"),
+                branch_name: t.text(row, "name"),
+                branch_status: t.int(row, "stat", 1) == 1,
+                resistance: t.num(row, "rpu", 0.0),
+                reactance: t.num(row, "xpu", 0.0),
+                from_shunt_conductance: t.num(row, "gi", 0.0),
+                from_shunt_susceptance: t.num(row, "bi", 0.0) + b / 2.0,
+                to_shunt_conductance: t.num(row, "gj", 0.0),
+                to_shunt_susceptance: t.num(row, "bj", 0.0) + b / 2.0,
+                tap_ratio: 1.0,
+                phase_shift: 0.0,
+                impedance_table: None,
+                operating_limit: t.num(row, "rate1", 0.0),
+                contingency_limit: t.num(row, "rate2", 0.0),
+                flow: 0.0,
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "sysswd") {
+        for row in &t.rows {
+            let x = t.num(row, "xpu", 0.0);
+            network.branches.push(Branch {
+                branch_type: BranchType::Line,
+                id: network.branches.len(),
+                from_bus: t.int(row, "ibus", 0),
+                to_bus: t.int(row, "jbus", 0),
+                ckt: t.text(row, "ckt"),
+                branch_name: t.text(row, "name"),
+                branch_status: t.int(row, "stat", 1) == 1,
+                resistance: 0.0,
+                // Same zero impedance threshold as the RAW reader
+                reactance: if x.abs() < 1e-4 { 1e-4 } else { x },
+                from_shunt_conductance: 0.0,
+                from_shunt_susceptance: 0.0,
+                to_shunt_conductance: 0.0,
+                to_shunt_susceptance: 0.0,
+                tap_ratio: 1.0,
+                phase_shift: 0.0,
+                impedance_table: None,
+                operating_limit: t.num(row, "rate1", 0.0),
+                contingency_limit: t.num(row, "rate2", 0.0),
+                flow: 0.0,
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "transformer") {
+        let bus_kv: HashMap<usize, f32> = network
+            .buses
+            .iter()
+            .map(|b| (b.bus_id, b.nom_voltage))
+            .collect();
+        let base_kv = |bus_id: usize| bus_kv.get(&bus_id).copied().unwrap_or(0.0);
+        for row in &t.rows {
+            let (from_bus, to_bus) = (t.int(row, "ibus", 0), t.int(row, "jbus", 0));
+            let tap_ratio = winding_ratio(
+                t.int(row, "cw", 1) as u8,
+                (
+                    t.num(row, "windv1", 1.0),
+                    t.num(row, "nomv1", 0.0),
+                    base_kv(from_bus),
+                ),
+                (
+                    t.num(row, "windv2", 1.0),
+                    t.num(row, "nomv2", 0.0),
+                    base_kv(to_bus),
+                ),
+            );
+            // Magnetizing admittance in system base pu (CM=1) sits at the winding 1 bus
+            let (mag_g, mag_b) = if t.int(row, "cm", 1) == 1 {
+                (t.num(row, "mag1", 0.0), t.num(row, "mag2", 0.0))
+            } else {
+                (0.0, 0.0)
+            };
+            // |COD1| = 3 is phase shift control, whose table is against the angle
+            let table = t.int(row, "tab1", 0);
+            let cod = t.num(row, "cod1", 0.0) as i32;
+            let impedance_table = (table != 0).then_some(TableLink {
+                table,
+                by_angle: cod.abs() == 3,
+            });
+            network.branches.push(Branch {
+                branch_type: BranchType::TwoWinding,
+                id: network.branches.len(),
+                from_bus,
+                to_bus,
+                ckt: t.text(row, "ckt"),
+                branch_name: t.text(row, "name"),
+                branch_status: t.int(row, "stat", 1) == 1,
+                resistance: t.num(row, "r1_2", 0.0),
+                reactance: t.num(row, "x1_2", 0.0),
+                from_shunt_conductance: mag_g,
+                from_shunt_susceptance: mag_b,
+                to_shunt_conductance: 0.0,
+                to_shunt_susceptance: 0.0,
+                tap_ratio,
+                phase_shift: t.num(row, "ang1", 0.0),
+                impedance_table,
+                operating_limit: t.num(row, "wdg1rate1", 0.0),
+                contingency_limit: t.num(row, "wdg1rate2", 0.0),
+                flow: 0.0,
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "twotermdc") {
+        for row in &t.rows {
+            // Rectifier fields end in r, inverter fields in i
+            let converter = |end: &str| {
+                let field = |name: &str| format!("{}{}", name, end);
+                DcConverter {
+                    bus_id: t.int(row, &field("ip"), 0),
+                    bridges: t.int(row, &field("nb"), 0) as u32,
+                    angle_max: t.num(row, &field("anmx"), 0.0),
+                    angle_min: t.num(row, &field("anmn"), 0.0),
+                    commutating_resistance: t.num(row, &field("rc"), 0.0),
+                    commutating_reactance: t.num(row, &field("xc"), 0.0),
+                    base_kv: t.num(row, &field("ebas"), 0.0),
+                    transformer_ratio: t.num(row, &field("tr"), 1.0),
+                    tap: t.num(row, &field("tap"), 1.0),
+                    tap_max: t.num(row, &field("tmx"), 1.5),
+                    tap_min: t.num(row, &field("tmn"), 0.51),
+                    tap_step: t.num(row, &field("stp"), 0.00625),
+                }
+            };
+            network.dc_lines.push(DcLine {
+                name: t.text(row, "name"),
+                control: match t.int(row, "mdc", 0) {
+                    1 => DcControl::Power,
+                    2 => DcControl::Current,
+                    _ => DcControl::Blocked,
+                },
+                resistance: t.num(row, "rdc", 0.0),
+                setpoint: t.num(row, "setvl", 0.0),
+                scheduled_voltage: t.num(row, "vschd", 0.0),
+                switch_voltage: t.num(row, "vcmod", 0.0),
+                compounding_resistance: t.num(row, "rcomp", 0.0),
+                current_margin: t.num(row, "delti", 0.0),
+                rectifier: converter("r"),
+                inverter: converter("i"),
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "vscdc") {
+        // Each line becomes two DC buses joined by a DC branch; converter
+        // fields end in 1 or 2
+        for row in &t.rows {
+            let name = t.text(row, "name");
+            let first = network.dc_buses.len() + 1;
+            for (end, dc_bus_id) in [(1, first), (2, first + 1)] {
+                let field = |name: &str| format!("{}{}", name, end);
+                let kind = t.int(row, &field("type"), 0);
+                let conv = VscConverter {
+                    name: name.clone(),
+                    ac_bus_id: t.int(row, &field("ibus"), 0),
+                    dc_bus_id,
+                    status: kind != 0,
+                    dc_control: if kind == 1 {
+                        VscDcControl::Voltage
+                    } else {
+                        VscDcControl::Power
+                    },
+                    dc_setpoint: t.num(row, &field("dcset"), 0.0),
+                    ac_control: if t.int(row, &field("mode"), 1) == 2 {
+                        VscAcControl::PowerFactor
+                    } else {
+                        VscAcControl::Voltage
+                    },
+                    ac_setpoint: t.num(row, &field("acset"), 1.0),
+                    loss_a: t.num(row, &field("aloss"), 0.0),
+                    loss_b: t.num(row, &field("bloss"), 0.0),
+                    loss_min: t.num(row, &field("minloss"), 0.0),
+                    s_max: t.num(row, &field("smax"), 0.0),
+                    i_max: t.num(row, &field("imax"), 0.0),
+                    q_max: t.num(row, &field("maxq"), 9999.0),
+                    q_min: t.num(row, &field("minq"), -9999.0),
+                    p_ac: 0.0,
+                    q_ac: 0.0,
+                };
+                network.dc_buses.push(DcBus {
+                    dc_bus_id,
+                    name: format!("{}-{}", name, end),
+                    voltage: if conv.dc_control == VscDcControl::Voltage {
+                        conv.dc_setpoint
+                    } else {
+                        0.0
+                    },
+                });
+                network.vsc_converters.push(conv);
+            }
+            network.dc_branches.push(DcBranch {
+                from_bus: first,
+                to_bus: first + 1,
+                name,
+                status: t.int(row, "mdc", 1) == 1,
+                resistance: t.num(row, "rdc", 0.0),
+                flow: 0.0,
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "impcor") {
+        // One row per point; a table's points are consecutive rows
+        for row in &t.rows {
+            let number = t.int(row, "itable", 0);
+            if network.impedance_tables.last().map(|table| table.number) != Some(number) {
+                network.impedance_tables.push(ImpedanceTable {
+                    number,
+                    points: Vec::new(),
+                });
+            }
+            if let Some(table) = network.impedance_tables.last_mut() {
+                table.points.push((
+                    t.num(row, "tap", 0.0),
+                    t.num(row, "refact", 1.0),
+                    t.num(row, "imfact", 0.0),
+                ));
+            }
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "msline") {
+        // MET 1 meters at IBUS, 2 at JBUS; unused dummy buses are zero
+        for row in &t.rows {
+            network.line_groups.push(LineGroup {
+                from_bus: t.int(row, "ibus", 0),
+                to_bus: t.int(row, "jbus", 0),
+                id: t.text(row, "mslid"),
+                metered_from: t.int(row, "met", 1) != 2,
+                dummy_buses: (1..=9)
+                    .map(|k| t.int(row, &format!("dum{}", k), 0))
+                    .filter(|&bus| bus != 0)
+                    .collect(),
+            });
+        }
+    }
+
+    if let Some(t) = Table::new(network_obj, "facts") {
+        // MODE and VSREF as in RAW
+        for row in &t.rows {
+            let name = t.text(row, "name");
+            let mode = t.int(row, "mode", 1) as u8;
+            if matches!(mode, 4 | 7 | 8) && t.int(row, "vsref", 0) == 1 {
+                return Err(format!(
+                    "FACTS device '{}': series voltage referred to the line current is not supported",
+                    name
+                ));
+            }
+            let shmx = t.num(row, "shmx", 0.0);
+            network.facts_devices.push(FactsDevice {
+                name,
+                bus_id: t.int(row, "ibus", 0),
+                status: mode != 0,
+                v_setpoint: t.num(row, "vset", 1.0),
+                q_max: shmx,
+                q_min: -shmx,
+                series: facts_series(
+                    t.int(row, "jbus", 0),
+                    mode,
+                    (t.num(row, "pdes", 0.0), t.num(row, "qdes", 0.0)),
+                    (t.num(row, "set1", 0.0), t.num(row, "set2", 0.0)),
+                    t.num(row, "imx", 0.0),
+                    t.num(row, "linx", 0.05),
+                ),
+                q_shunt: 0.0,
+            });
+        }
+    }
+
+    // Multi-terminal LCC grids are not modeled; carrying them through would
+    // leave their converters out of every solve
+    if let Some(t) = Table::new(network_obj, "ntermdc")
+        && !t.rows.is_empty()
+    {
+        return Err("Multi-terminal DC records are not supported".to_string());
+    }
+
+    for (name, table) in network_obj {
+        if !MODELED.contains(&name.as_str()) {
+            network.passthrough.insert(name.clone(), table.to_string());
+        }
+    }
+
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// f32 as a JSON number with its shortest decimal form rather than the
+/// widened f64 digits.
+pub(crate) fn num(x: f32) -> Value {
+    x.to_string()
+        .parse::<f64>()
+        .map(Value::from)
+        .unwrap_or(Value::Null)
+}
+
+/// Appends `"name":{"fields":[...],"data":[...]}` with one row per line.
+fn write_table(out: &mut String, name: &str, fields: &[&str], rows: &[Vec<Value>]) {
+    let _ = writeln!(out, "\"{}\":{{", name);
+    let _ = writeln!(out, "\"fields\":{},", Value::from(fields.to_vec()));
+    let _ = write!(out, "\"data\":[");
+    for (i, row) in rows.iter().enumerate() {
+        let sep = if i + 1 < rows.len() { "," } else { "" };
+        let _ = write!(out, "\n{}{}", Value::from(row.clone()), sep);
+    }
+    let _ = writeln!(out, "\n]");
+    let _ = write!(out, "}}");
+}
+
+/// Formats a Network as a PSS/E RAWX document. Pass-through records are
+/// written back after the modeled ones. DC grids that do not fit VSC DC line
+/// records are an error.
+pub fn format_rawx(network: &Network) -> Result<String, String> {
+    let mut tables: Vec<String> = Vec::new();
+    let mut table = |name: &str, fields: &[&str], rows: Vec<Vec<Value>>| {
+        let mut out = String::new();
+        write_table(&mut out, name, fields, &rows);
+        tables.push(out);
+    };
+
+    // caseid is a single record, written as a flat data array
+    let mut caseid = String::new();
+    let _ = writeln!(caseid, "\"caseid\":{{");
+    let _ = writeln!(
+        caseid,
+        "\"fields\":[\"ic\",\"sbase\",\"rev\",\"xfrrat\",\"nxfrat\",\"basfrq\",\"title1\",\"title2\"],"
+    );
+    let _ = writeln!(
+        caseid,
+        "\"data\":[0,{},35,0,1,{},{},\"\"]",
+        num(network.s_base),
+        num(network.frequency),
+        Value::from(network.case_name.clone())
+    );
+    let _ = write!(caseid, "}}");
+
+    table(
+        "bus",
+        &[
+            "ibus", "name", "baskv", "ide", "area", "zone", "owner", "vm", "va", "nvhi", "nvlo",
+            "evhi", "evlo",
+        ],
+        network
+            .buses
+            .iter()
+            .map(|b| {
+                vec![
+                    Value::from(b.bus_id),
+                    Value::from(b.bus_name.clone()),
+                    num(b.nom_voltage),
+                    Value::from(bus_code(b.bus_type)),
+                    Value::from(b.area),
+                    Value::from(b.zone),
+                    Value::from(b.owner),
+                    num(b.voltage),
+                    num(b.angle),
+                    num(b.v_max_operating),
+                    num(b.v_min_operating),
+                    num(b.v_max_contingency),
+                    num(b.v_min_contingency),
+                ]
+            })
+            .collect(),
+    );
+
+    let area_zone = |bus_id: usize| {
+        network
+            .buses
+            .iter()
+            .find(|b| b.bus_id == bus_id)
+            .map(|b| (b.area, b.zone, b.owner))
+            .unwrap_or((1, 1, 1))
+    };
+
+    table(
+        "load",
+        &[
+            "ibus", "loadid", "stat", "area", "zone", "pl", "ql", "ip", "iq", "yp", "yq", "owner",
+        ],
+        network
+            .loads
+            .iter()
+            .map(|l| {
+                let (area, zone, owner) = area_zone(l.bus_id);
+                vec![
+                    Value::from(l.bus_id),
+                    Value::from(l.load_ident.as_str()),
+                    Value::from(status(l.load_status)),
+                    Value::from(area),
+                    Value::from(zone),
+                    num(l.real_load),
+                    num(l.imag_load),
+                    num(l.real_current),
+                    num(l.imag_current),
+                    num(l.real_admittance),
+                    // YQ is negative for an inductive load
+                    num(0.0 - l.imag_admittance),
+                    Value::from(owner),
+                ]
+            })
+            .collect(),
+    );
+
+    table(
+        "fixshunt",
+        &["ibus", "shntid", "stat", "gl", "bl"],
+        network
+            .buses
+            .iter()
+            .filter(|b| b.real_shunt != 0.0 || b.imag_shunt != 0.0)
+            .map(|b| {
+                vec![
+                    Value::from(b.bus_id),
+                    Value::from("1"),
+                    Value::from(1),
+                    num(b.real_shunt),
+                    num(b.imag_shunt),
+                ]
+            })
+            .collect(),
+    );
+
+    table(
+        "generator",
+        &[
+            "ibus", "machid", "pg", "qg", "qt", "qb", "vs", "mbase", "stat", "pt", "pb",
+        ],
+        network
+            .generators
+            .iter()
+            .map(|g| {
+                vec![
+                    Value::from(g.gen_bus_id),
+                    Value::from(g.machine_id.as_str()),
+                    num(g.p_gen),
+                    num(g.q_gen),
+                    num(g.q_max),
+                    num(g.q_min),
+                    num(g.v_setpoint),
+                    num(network.s_base),
+                    Value::from(status(g.gen_status)),
+                    num(g.p_max),
+                    num(g.p_min),
+                ]
+            })
+            .collect(),
+    );
+
+    let circuits = network.circuit_ids();
+    let branches = |branch_type: BranchType| {
+        network
+            .branches
+            .iter()
+            .zip(&circuits)
+            .filter(move |(br, _)| br.branch_type == branch_type)
+    };
+
+    table(
+        "acline",
+        &[
+            "ibus", "jbus", "ckt", "rpu", "xpu", "bpu", "name", "rate1", "rate2", "gi", "bi", "gj",
+            "bj", "stat",
+        ],
+        branches(BranchType::Line)
+            .map(|(br, ckt)| {
+                let (b, bi, bj) = line_charging(br);
+                vec![
+                    Value::from(br.from_bus),
+                    Value::from(br.to_bus),
+                    Value::from(ckt.as_str()),
+                    num(br.resistance),
+                    num(br.reactance),
+                    num(b),
+                    Value::from(br.branch_name.trim()),
+                    num(br.operating_limit),
+                    num(br.contingency_limit),
+                    num(br.from_shunt_conductance),
+                    num(bi),
+                    num(br.to_shunt_conductance),
+                    num(bj),
+                    Value::from(status(br.branch_status)),
+                ]
+            })
+            .collect(),
+    );
+
+    table(
+        "transformer",
+        &[
+            "ibus",
+            "jbus",
+            "kbus",
+            "ckt",
+            "cw",
+            "cz",
+            "cm",
+            "mag1",
+            "mag2",
+            "name",
+            "stat",
+            "r1_2",
+            "x1_2",
+            "sbase1_2",
+            "windv1",
+            "ang1",
+            "wdg1rate1",
+            "wdg1rate2",
+            "windv2",
+            "cod1",
+            "tab1",
+        ],
+        branches(BranchType::TwoWinding)
+            .map(|(br, ckt)| {
+                vec![
+                    Value::from(br.from_bus),
+                    Value::from(br.to_bus),
+                    Value::from(0),
+                    Value::from(ckt.as_str()),
+                    Value::from(1),
+                    Value::from(1),
+                    Value::from(1),
+                    num(br.from_shunt_conductance),
+                    num(br.from_shunt_susceptance),
+                    Value::from(br.branch_name.trim()),
+                    Value::from(status(br.branch_status)),
+                    num(br.resistance),
+                    num(br.reactance),
+                    num(network.s_base),
+                    num(br.tap_ratio),
+                    num(br.phase_shift),
+                    num(br.operating_limit),
+                    num(br.contingency_limit),
+                    Value::from(1.0),
+                    // COD1 3 (phase shift control) marks a table against the angle
+                    Value::from(match br.impedance_table {
+                        Some(link) if link.by_angle => 3,
+                        _ => 0,
+                    }),
+                    Value::from(br.impedance_table.map_or(0, |link| link.table)),
+                ]
+            })
+            .collect(),
+    );
+
+    let converter_fields = |end: char| {
+        [
+            "ip", "nb", "anmx", "anmn", "rc", "xc", "ebas", "tr", "tap", "tmx", "tmn", "stp",
+        ]
+        .map(|name| format!("{}{}", name, end))
+    };
+    let mut dc_fields: Vec<String> = [
+        "name", "mdc", "rdc", "setvl", "vschd", "vcmod", "rcomp", "delti", "met",
+    ]
+    .map(String::from)
+    .to_vec();
+    dc_fields.extend(converter_fields('r'));
+    dc_fields.extend(converter_fields('i'));
+    table(
+        "twotermdc",
+        &dc_fields.iter().map(String::as_str).collect::<Vec<_>>(),
+        network
+            .dc_lines
+            .iter()
+            .map(|line| {
+                let mut row = vec![
+                    Value::from(line.name.as_str()),
+                    Value::from(dc_mode(line.control)),
+                    num(line.resistance),
+                    num(line.setpoint),
+                    num(line.scheduled_voltage),
+                    num(line.switch_voltage),
+                    num(line.compounding_resistance),
+                    num(line.current_margin),
+                    Value::from("I"),
+                ];
+                for c in [&line.rectifier, &line.inverter] {
+                    row.extend([
+                        Value::from(c.bus_id),
+                        Value::from(c.bridges),
+                        num(c.angle_max),
+                        num(c.angle_min),
+                        num(c.commutating_resistance),
+                        num(c.commutating_reactance),
+                        num(c.base_kv),
+                        num(c.transformer_ratio),
+                        num(c.tap),
+                        num(c.tap_max),
+                        num(c.tap_min),
+                        num(c.tap_step),
+                    ]);
+                }
+                row
+            })
+            .collect(),
+    );
+
+    // Each DC branch needs one converter at each end and nothing else, as in
+    // RAW
+    let mut vsc_fields: Vec<String> = ["name", "mdc", "rdc"].map(String::from).to_vec();
+    for end in ['1', '2'] {
+        vsc_fields.extend(
+            [
+                "ibus", "type", "mode", "dcset", "acset", "aloss", "bloss", "minloss", "smax",
+                "imax", "pwf", "maxq", "minq",
+            ]
+            .map(|name| format!("{}{}", name, end)),
+        );
+    }
+    table(
+        "vscdc",
+        &vsc_fields.iter().map(String::as_str).collect::<Vec<_>>(),
+        vsc_links(network)?
+            .into_iter()
+            .map(|(br, converters)| {
+                let mut row = vec![
+                    Value::from(br.name.as_str()),
+                    Value::from(status(br.status)),
+                    num(br.resistance),
+                ];
+                for c in converters {
+                    let (kind, mode, acset) = vsc_codes(c);
+                    row.extend([
+                        Value::from(c.ac_bus_id),
+                        Value::from(kind),
+                        Value::from(mode),
+                        num(c.dc_setpoint),
+                        num(acset),
+                        num(c.loss_a),
+                        num(c.loss_b),
+                        num(c.loss_min),
+                        num(c.s_max),
+                        num(c.i_max),
+                        Value::from(1),
+                        num(c.q_max),
+                        num(c.q_min),
+                    ]);
+                }
+                row
+            })
+            .collect(),
+    );
+
+    table(
+        "impcor",
+        &["itable", "tap", "refact", "imfact"],
+        network
+            .impedance_tables
+            .iter()
+            .flat_map(|t| {
+                t.points
+                    .iter()
+                    .map(|&(x, re, im)| vec![Value::from(t.number), num(x), num(re), num(im)])
+            })
+            .collect(),
+    );
+
+    let dummy_fields = (1..=9).map(|k| format!("dum{}", k));
+    let msline_fields: Vec<String> = ["ibus", "jbus", "mslid", "met"]
+        .map(String::from)
+        .into_iter()
+        .chain(dummy_fields)
+        .collect();
+    table(
+        "msline",
+        &msline_fields.iter().map(String::as_str).collect::<Vec<_>>(),
+        network
+            .line_groups
+            .iter()
+            .map(|group| {
+                let mut row = vec![
+                    Value::from(group.from_bus),
+                    Value::from(group.to_bus),
+                    Value::from(group.id.as_str()),
+                    Value::from(if group.metered_from { 1 } else { 2 }),
+                ];
+                row.extend(
+                    (0..9).map(|k| Value::from(group.dummy_buses.get(k).copied().unwrap_or(0))),
+                );
+                row
+            })
+            .collect(),
+    );
+
+    // RAW limits the shunt link symmetrically, so SHMX is the larger limit
+    table(
+        "facts",
+        &[
+            "name", "ibus", "jbus", "mode", "pdes", "qdes", "vset", "shmx", "imx", "linx", "set1",
+            "set2",
+        ],
+        network
+            .facts_devices
+            .iter()
+            .map(|device| {
+                let (to_bus, mode) = facts_mode(device);
+                let link = device.series.as_ref();
+                vec![
+                    Value::from(device.name.as_str()),
+                    Value::from(device.bus_id),
+                    Value::from(to_bus),
+                    Value::from(mode),
+                    num(link.map_or(0.0, |l| l.p_setpoint)),
+                    num(link.map_or(0.0, |l| l.q_setpoint)),
+                    num(device.v_setpoint),
+                    num(device.q_max.max(-device.q_min)),
+                    num(link.map_or(0.0, |l| l.rating)),
+                    num(link.map_or(0.05, |l| l.reactance)),
+                    num(link.map_or(0.0, |l| l.setpoint_real)),
+                    num(link.map_or(0.0, |l| l.setpoint_imag)),
+                ]
+            })
+            .collect(),
+    );
+
+    // Tables once passed through and now modeled are written from the model
+    for (name, table) in &network.passthrough {
+        if MODELED.contains(&name.as_str()) {
+            continue;
+        }
+        tables.push(format!("{}:{}", Value::from(name.as_str()), table));
+    }
+
+    Ok(format!(
+        "{{\n\"network\":{{\n{},\n{}\n}}\n}}\n",
+        caseid,
+        tables.join(",\n")
+    ))
+}
+
+/// Writes a Network to disk as a PSS/E RAWX document.
+pub fn write_rawx(path: &str, network: &Network) -> Result<(), String> {
+    fs::write(path, format_rawx(network)?).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
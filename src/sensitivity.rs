@@ -0,0 +1,184 @@
+use crate::case::*;
+use rsparse::data::{Nmrc, Symb};
+
+/// LU factors of B' that can be reused for many right-hand sides.
+struct BPrimeFactor {
+    symbolic: Symb,
+    numeric: Nmrc<f64>,
+    n: usize,
+}
+
+impl BPrimeFactor {
+    /// Solves B' * x = b in place.
+    fn solve(&self, b: &mut [f64]) {
+        let mut x = vec![0.0f64; self.n];
+
+        // x = P*b
+        for (k, &value) in b.iter().enumerate() {
+            match &self.numeric.pinv {
+                Some(pinv) => x[pinv[k] as usize] = value,
+                None => x[k] = value,
+            }
+        }
+        rsparse::lsolve(&self.numeric.l, &mut x);
+        rsparse::usolve(&self.numeric.u, &mut x);
+
+        // b = Q*x
+        for (k, &value) in x.iter().enumerate() {
+            match &self.symbolic.q {
+                Some(q) => b[q[k] as usize] = value,
+                None => b[k] = value,
+            }
+        }
+    }
+}
+
+impl Network {
+    /// Factor B' once for repeated sensitivity solves.
+    fn factor_b_prime(&mut self) -> Option<BPrimeFactor> {
+        self.rebuild_bus_map();
+        let n = self.bus_map.len();
+        if n == 0 {
+            return None;
+        }
+
+        let csc = self.b_prime().to_sprs();
+        let mut symbolic = rsparse::sqr(&csc, 0, false);
+        let numeric = rsparse::lu(&csc, &mut symbolic, 1e-6).ok()?;
+        Some(BPrimeFactor {
+            symbolic,
+            numeric,
+            n,
+        })
+    }
+
+    /// Flow change on every branch (per unit of the transfer) for injecting at
+    /// `from_bus` and withdrawing at `to_bus`, indexed like `branches`.
+    /// `reactances` are the branch reactances from `effective_reactances`.
+    fn transfer_factors(
+        &self,
+        factor: &BPrimeFactor,
+        reactances: &[f32],
+        from_bus: usize,
+        to_bus: usize,
+    ) -> Vec<f64> {
+        let mut theta = vec![0.0f64; factor.n];
+        if let Some(&i) = self.bus_map.get(&from_bus) {
+            theta[i] += 1.0;
+        }
+        if let Some(&j) = self.bus_map.get(&to_bus) {
+            theta[j] -= 1.0;
+        }
+        factor.solve(&mut theta);
+
+        let angle = |bus_id: usize| self.bus_map.get(&bus_id).map(|&i| theta[i]).unwrap_or(0.0);
+        self.branches
+            .iter()
+            .zip(reactances)
+            .map(|(br, &x)| {
+                if !br.branch_status || x == 0.0 {
+                    0.0
+                } else {
+                    (angle(br.from_bus) - angle(br.to_bus)) / x as f64
+                }
+            })
+            .collect()
+    }
+
+    /// Power transfer distribution factors for a transfer from `from_bus` to `to_bus`.
+    /// Entry `i` is the fraction of the transfer that flows on `branches[i]`.
+    pub fn ptdf(&mut self, from_bus: usize, to_bus: usize) -> Option<Vec<f64>> {
+        let factor = self.factor_b_prime()?;
+        let reactances = self.effective_reactances();
+        Some(self.transfer_factors(&factor, &reactances, from_bus, to_bus))
+    }
+
+    /// Line outage distribution factors, `lodf[l][k]` being the fraction of the
+    /// pre-outage flow on branch `k` that shifts onto branch `l` when `k` opens.
+    /// Columns of branches whose outage islands the system are NaN.
+    pub fn lodf_matrix(&mut self) -> Option<Vec<Vec<f64>>> {
+        let factor = self.factor_b_prime()?;
+        let reactances = self.effective_reactances();
+        let m = self.branches.len();
+        let mut lodf = vec![vec![0.0f64; m]; m];
+
+        for k in 0..m {
+            let br = &self.branches[k];
+            if !br.branch_status || reactances[k] == 0.0 {
+                continue;
+            }
+            let ptdf = self.transfer_factors(&factor, &reactances, br.from_bus, br.to_bus);
+            let denominator = 1.0 - ptdf[k];
+
+            for (l, row) in lodf.iter_mut().enumerate() {
+                row[k] = if l == k {
+                    -1.0
+                } else if denominator.abs() < 1e-6 {
+                    f64::NAN
+                } else {
+                    ptdf[l] / denominator
+                };
+            }
+        }
+
+        Some(lodf)
+    }
+}
+
+/// Estimates post-outage branch flows for the simultaneous loss of `outaged`
+/// branches from the base case flows and the LODF matrix.
+/// Returns None when the outage set islands the system.
+pub fn multi_outage_flows(lodf: &[Vec<f64>], flows: &[f64], outaged: &[usize]) -> Option<Vec<f64>> {
+    let size = outaged.len();
+
+    // Solve (I - L_KK) * f_K = f0_K for the effective flows on the outaged set
+    let mut a: Vec<Vec<f64>> = outaged
+        .iter()
+        .map(|&r| {
+            outaged
+                .iter()
+                .map(|&c| if r == c { 1.0 } else { -lodf[r][c] })
+                .collect()
+        })
+        .collect();
+    let mut rhs: Vec<f64> = outaged.iter().map(|&k| flows[k]).collect();
+
+    // Gaussian elimination with partial pivoting; the system is tiny
+    for col in 0..size {
+        let pivot = (col..size).max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))?;
+        if !a[pivot][col].is_finite() || a[pivot][col].abs() < 1e-6 {
+            return None;
+        }
+        a.swap(col, pivot);
+        rhs.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..size {
+            let ratio = a[row][col] / pivot_row[col];
+            for (value, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= ratio * p;
+            }
+            rhs[row] -= ratio * rhs[col];
+        }
+    }
+    let mut effective = vec![0.0f64; size];
+    for row in (0..size).rev() {
+        let tail: f64 = (row + 1..size).map(|c| a[row][c] * effective[c]).sum();
+        effective[row] = (rhs[row] - tail) / a[row][row];
+    }
+
+    let mut post = flows.to_vec();
+    for (l, flow) in post.iter_mut().enumerate() {
+        if outaged.contains(&l) {
+            *flow = 0.0;
+            continue;
+        }
+        for (index, &k) in outaged.iter().enumerate() {
+            let factor = lodf[l][k];
+            if !factor.is_finite() {
+                return None;
+            }
+            *flow += factor * effective[index];
+        }
+    }
+    Some(post)
+}
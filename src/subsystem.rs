@@ -0,0 +1,558 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+
+use crate::case::*;
+use crate::contingency::tokenize;
+
+/// One set of bus criteria. A bus matches when it passes every criterion
+/// that is given; an empty list or range places no restriction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BusSelection {
+    /// Inclusive (first, last) ranges; a single BUS, AREA, ZONE or OWNER
+    /// is a range of one
+    pub buses: Vec<(usize, usize)>,
+    pub areas: Vec<(usize, usize)>,
+    pub zones: Vec<(usize, usize)>,
+    pub owners: Vec<(usize, usize)>,
+    /// Inclusive nominal kV range
+    pub kv_range: Option<(f32, f32)>,
+}
+
+/// Whether `value` lies in one of `ranges`, or `ranges` is empty.
+fn in_ranges(ranges: &[(usize, usize)], value: usize) -> bool {
+    ranges.is_empty() || ranges.iter().any(|&(lo, hi)| lo <= value && value <= hi)
+}
+
+/// One statement per range: the singular keyword for a range of one.
+fn range_statements(
+    lines: &mut Vec<String>,
+    single: &str,
+    plural: &str,
+    ranges: &[(usize, usize)],
+) {
+    lines.extend(ranges.iter().map(|&(lo, hi)| match lo == hi {
+        true => format!("{} {}", single, lo),
+        false => format!("{} {} {}", plural, lo, hi),
+    }));
+}
+
+impl BusSelection {
+    pub fn matches(&self, bus: &Bus) -> bool {
+        in_ranges(&self.buses, bus.bus_id)
+            && in_ranges(&self.areas, bus.area)
+            && in_ranges(&self.zones, bus.zone)
+            && in_ranges(&self.owners, bus.owner)
+            && self
+                .kv_range
+                .is_none_or(|(lo, hi)| bus.nom_voltage >= lo && bus.nom_voltage <= hi)
+    }
+
+    /// Number of criteria kinds in use.
+    fn criteria(&self) -> usize {
+        [
+            !self.buses.is_empty(),
+            !self.areas.is_empty(),
+            !self.zones.is_empty(),
+            !self.owners.is_empty(),
+            self.kv_range.is_some(),
+        ]
+        .iter()
+        .filter(|&&used| used)
+        .count()
+    }
+
+    /// Statement lines for this selection, without indentation.
+    fn statements(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        range_statements(&mut lines, "BUS", "BUSES", &self.buses);
+        range_statements(&mut lines, "AREA", "AREAS", &self.areas);
+        range_statements(&mut lines, "ZONE", "ZONES", &self.zones);
+        range_statements(&mut lines, "OWNER", "OWNERS", &self.owners);
+        if let Some((lo, hi)) = self.kv_range {
+            lines.push(format!("KVRANGE {} {}", lo, hi));
+        }
+        lines
+    }
+}
+
+/// A named subset of buses, as in a PSS/E .sub file. A bus belongs to the
+/// subsystem when it matches any of the selections.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subsystem {
+    pub name: String,
+    pub selections: Vec<BusSelection>,
+}
+
+impl Subsystem {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            selections: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, bus: &Bus) -> bool {
+        self.selections.iter().any(|s| s.matches(bus))
+    }
+
+    /// Bus ids of the network that belong to the subsystem.
+    pub fn bus_ids(&self, network: &Network) -> HashSet<usize> {
+        network
+            .buses
+            .iter()
+            .filter(|b| self.contains(b))
+            .map(|b| b.bus_id)
+            .collect()
+    }
+}
+
+/// Display the subsystem as a SUBSYSTEM ... END block.
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "SUBSYSTEM '{}'", self.name)?;
+        for (index, selection) in self.selections.iter().enumerate() {
+            if selection.criteria() > 1 {
+                writeln!(f, " JOIN 'GROUP{}'", index + 1)?;
+                for line in selection.statements() {
+                    writeln!(f, "  {}", line)?;
+                }
+                writeln!(f, " END")?;
+            } else {
+                for line in selection.statements() {
+                    writeln!(f, " {}", line)?;
+                }
+            }
+        }
+        write!(f, "END")
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(
+    tokens: &[String],
+    index: usize,
+    line: usize,
+) -> Result<T, String> {
+    let token = tokens
+        .get(index)
+        .ok_or_else(|| format!("Line {}: missing value", line))?;
+    token
+        .parse()
+        .map_err(|_| format!("Line {}: invalid value '{}'", line, token))
+}
+
+/// Inclusive range from a plural statement such as AREAS 1 5.
+fn parse_range(tokens: &[String], line: usize) -> Result<(usize, usize), String> {
+    let lo: usize = parse_number(tokens, 1, line)?;
+    let hi: usize = parse_number(tokens, 2, line)?;
+    if lo > hi {
+        return Err(format!(
+            "Line {}: {} {} {} runs backwards",
+            line, tokens[0], lo, hi
+        ));
+    }
+    Ok((lo, hi))
+}
+
+/// Range of one from a singular statement such as AREA 3.
+fn parse_single(tokens: &[String], line: usize) -> Result<(usize, usize), String> {
+    let value: usize = parse_number(tokens, 1, line)?;
+    Ok((value, value))
+}
+
+/// Add one subsystem statement to a selection. Returns false if the keyword
+/// is not a selection statement.
+fn parse_statement(
+    selection: &mut BusSelection,
+    tokens: &[String],
+    line: usize,
+) -> Result<bool, String> {
+    match tokens[0].to_uppercase().as_str() {
+        "BUS" => selection.buses.push(parse_single(tokens, line)?),
+        "BUSES" => selection.buses.push(parse_range(tokens, line)?),
+        "AREA" => selection.areas.push(parse_single(tokens, line)?),
+        "AREAS" => selection.areas.push(parse_range(tokens, line)?),
+        "ZONE" => selection.zones.push(parse_single(tokens, line)?),
+        "ZONES" => selection.zones.push(parse_range(tokens, line)?),
+        "OWNER" => selection.owners.push(parse_single(tokens, line)?),
+        "OWNERS" => selection.owners.push(parse_range(tokens, line)?),
+        "KVRANGE" => {
+            selection.kv_range = Some((
+                parse_number(tokens, 1, line)?,
+                parse_number(tokens, 2, line)?,
+            ))
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Parses the contents of a PSS/E .sub file. Statements at the top level of a
+/// SUBSYSTEM block are combined as a union; statements inside a JOIN ... END
+/// group must all hold.
+pub fn parse_sub_str(content: &str) -> Result<Vec<Subsystem>, String> {
+    let mut subsystems = Vec::new();
+    let mut current: Option<Subsystem> = None;
+    let mut join: Option<BusSelection> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.to_uppercase().starts_with("COM") {
+            continue;
+        }
+        let tokens = tokenize(trimmed);
+        let Some(first) = tokens.first() else {
+            continue;
+        };
+
+        match (first.to_uppercase().as_str(), current.as_mut()) {
+            ("SUBSYSTEM", None) => {
+                let name = tokens
+                    .get(1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("SUB{}", subsystems.len() + 1));
+                current = Some(Subsystem::new(name));
+            }
+            ("JOIN", Some(_)) if join.is_none() => join = Some(BusSelection::default()),
+            ("END", Some(subsystem)) => match join.take() {
+                // An empty group would select every bus
+                Some(selection) if selection.criteria() == 0 => {
+                    return Err(format!(
+                        "Line {}: JOIN group has no statements",
+                        line_number
+                    ));
+                }
+                Some(selection) => subsystem.selections.push(selection),
+                None => subsystems.extend(current.take()),
+            },
+            // A bare END closes the file
+            ("END", None) => break,
+            (_, Some(subsystem)) => {
+                if let Some(selection) = join.as_mut() {
+                    if !parse_statement(selection, &tokens, line_number)? {
+                        return Err(format!(
+                            "Line {}: unsupported statement '{}'",
+                            line_number, first
+                        ));
+                    }
+                    continue;
+                }
+                let mut selection = BusSelection::default();
+                if !parse_statement(&mut selection, &tokens, line_number)? {
+                    return Err(format!(
+                        "Line {}: unsupported statement '{}'",
+                        line_number, first
+                    ));
+                }
+                // Consecutive statements of one kind share a selection
+                match subsystem.selections.last_mut() {
+                    Some(last)
+                        if last.criteria() == 1
+                            && selection_kind(last)
+                                .is_some_and(|kind| selection_kind(&selection) == Some(kind)) =>
+                    {
+                        last.buses.extend(selection.buses);
+                        last.areas.extend(selection.areas);
+                        last.zones.extend(selection.zones);
+                        last.owners.extend(selection.owners);
+                    }
+                    _ => subsystem.selections.push(selection),
+                }
+            }
+            (other, _) => {
+                return Err(format!("Line {}: unexpected '{}'", line_number, other));
+            }
+        }
+    }
+
+    if let Some(subsystem) = current {
+        return Err(format!("Subsystem '{}' is missing its END", subsystem.name));
+    }
+    Ok(subsystems)
+}
+
+/// Which single list a one-criterion selection uses; ranges never merge.
+fn selection_kind(selection: &BusSelection) -> Option<usize> {
+    if selection.kv_range.is_some() {
+        None
+    } else if !selection.buses.is_empty() {
+        Some(0)
+    } else if !selection.areas.is_empty() {
+        Some(1)
+    } else if !selection.zones.is_empty() {
+        Some(2)
+    } else if !selection.owners.is_empty() {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Reads a PSS/E .sub file from disk.
+pub fn read_sub(path: &str) -> Result<Vec<Subsystem>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_sub_str(&content)
+}
+
+/// Formats subsystems as the contents of a PSS/E .sub file.
+pub fn format_sub(subsystems: &[Subsystem]) -> String {
+    let mut out = String::new();
+    for subsystem in subsystems {
+        out.push_str(&subsystem.to_string());
+        out.push('\n');
+    }
+    out.push_str("END\n");
+    out
+}
+
+/// Writes subsystems to a PSS/E .sub file.
+pub fn write_sub(path: &str, subsystems: &[Subsystem]) -> Result<(), String> {
+    fs::write(path, format_sub(subsystems)).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// A record of a PSS/E .mon file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MonitorRecord {
+    /// MONITOR BRANCHES IN SUBSYSTEM 'name'
+    BranchesIn(String),
+    /// MONITOR TIES FROM SUBSYSTEM 'name'
+    TiesFrom(String),
+    /// MONITOR BRANCH FROM BUS i TO BUS j CIRCUIT ckt
+    Branch {
+        from_bus: usize,
+        to_bus: usize,
+        ckt: String,
+    },
+    /// MONITOR VOLTAGE RANGE SUBSYSTEM 'name' vmin vmax
+    VoltageRangeIn {
+        subsystem: String,
+        v_min: f32,
+        v_max: f32,
+    },
+    /// MONITOR VOLTAGE RANGE BUS i vmin vmax
+    VoltageRangeBus {
+        bus_id: usize,
+        v_min: f32,
+        v_max: f32,
+    },
+}
+
+/// Display the record as a .mon line.
+impl fmt::Display for MonitorRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorRecord::BranchesIn(name) => {
+                write!(f, "MONITOR BRANCHES IN SUBSYSTEM '{}'", name)
+            }
+            MonitorRecord::TiesFrom(name) => write!(f, "MONITOR TIES FROM SUBSYSTEM '{}'", name),
+            MonitorRecord::Branch {
+                from_bus,
+                to_bus,
+                ckt,
+            } => write!(
+                f,
+                "MONITOR BRANCH FROM BUS {} TO BUS {} CIRCUIT '{}'",
+                from_bus, to_bus, ckt
+            ),
+            MonitorRecord::VoltageRangeIn {
+                subsystem,
+                v_min,
+                v_max,
+            } => write!(
+                f,
+                "MONITOR VOLTAGE RANGE SUBSYSTEM '{}' {} {}",
+                subsystem, v_min, v_max
+            ),
+            MonitorRecord::VoltageRangeBus {
+                bus_id,
+                v_min,
+                v_max,
+            } => write!(
+                f,
+                "MONITOR VOLTAGE RANGE BUS {} {} {}",
+                bus_id, v_min, v_max
+            ),
+        }
+    }
+}
+
+/// Parse one MONITOR line.
+fn parse_monitor(tokens: &[String], line: usize) -> Result<MonitorRecord, String> {
+    let upper: Vec<String> = tokens.iter().map(|t| t.to_uppercase()).collect();
+    let words: Vec<&str> = upper.iter().map(String::as_str).collect();
+    let name = |index: usize| {
+        tokens
+            .get(index)
+            .cloned()
+            .ok_or_else(|| format!("Line {}: missing subsystem name", line))
+    };
+
+    match words.as_slice() {
+        ["MONITOR", "BRANCHES" | "LINES", "IN", "SUBSYSTEM", ..] => {
+            Ok(MonitorRecord::BranchesIn(name(4)?))
+        }
+        ["MONITOR", "TIES", "FROM", "SUBSYSTEM", ..] => Ok(MonitorRecord::TiesFrom(name(4)?)),
+        [
+            "MONITOR",
+            "BRANCH" | "LINE",
+            "FROM",
+            "BUS",
+            _,
+            "TO",
+            "BUS",
+            _,
+            rest @ ..,
+        ] => {
+            let ckt = match rest {
+                [] => String::from("1"),
+                ["CIRCUIT" | "CKT", _] => tokens[9].clone(),
+                _ => return Err(format!("Line {}: unexpected '{}'", line, rest[0])),
+            };
+            Ok(MonitorRecord::Branch {
+                from_bus: parse_number(tokens, 4, line)?,
+                to_bus: parse_number(tokens, 7, line)?,
+                ckt,
+            })
+        }
+        ["MONITOR", "VOLTAGE", "RANGE", "SUBSYSTEM", _, _, _] => {
+            Ok(MonitorRecord::VoltageRangeIn {
+                subsystem: name(4)?,
+                v_min: parse_number(tokens, 5, line)?,
+                v_max: parse_number(tokens, 6, line)?,
+            })
+        }
+        ["MONITOR", "VOLTAGE", "RANGE", "BUS", _, _, _] => Ok(MonitorRecord::VoltageRangeBus {
+            bus_id: parse_number(tokens, 4, line)?,
+            v_min: parse_number(tokens, 5, line)?,
+            v_max: parse_number(tokens, 6, line)?,
+        }),
+        _ => Err(format!(
+            "Line {}: unsupported record '{}'",
+            line,
+            tokens.join(" ")
+        )),
+    }
+}
+
+/// Parses the contents of a PSS/E .mon file.
+pub fn parse_mon_str(content: &str) -> Result<Vec<MonitorRecord>, String> {
+    let mut records = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.to_uppercase().starts_with("COM") {
+            continue;
+        }
+        let tokens = tokenize(trimmed);
+        match tokens.first().map(|t| t.to_uppercase()).as_deref() {
+            None => continue,
+            Some("END") => break,
+            Some(_) => records.push(parse_monitor(&tokens, index + 1)?),
+        }
+    }
+    Ok(records)
+}
+
+/// Reads a PSS/E .mon file from disk.
+pub fn read_mon(path: &str) -> Result<Vec<MonitorRecord>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_mon_str(&content)
+}
+
+/// Formats monitor records as the contents of a PSS/E .mon file.
+pub fn format_mon(records: &[MonitorRecord]) -> String {
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&record.to_string());
+        out.push('\n');
+    }
+    out.push_str("END\n");
+    out
+}
+
+/// Writes monitor records to a PSS/E .mon file.
+pub fn write_mon(path: &str, records: &[MonitorRecord]) -> Result<(), String> {
+    fs::write(path, format_mon(records)).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Monitored elements of a specific network, resolved from .mon records.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonitorSet {
+    /// Indices into `Network::branches`
+    pub branches: HashSet<usize>,
+    /// Monitored bus ids, with a (v_min, v_max) that replaces the bus limits
+    pub buses: HashMap<usize, (f32, f32)>,
+}
+
+impl MonitorSet {
+    /// Resolves .mon records against a network and the subsystems they name.
+    pub fn resolve(
+        network: &Network,
+        records: &[MonitorRecord],
+        subsystems: &[Subsystem],
+    ) -> Result<Self, String> {
+        let lookup = |name: &str| {
+            subsystems
+                .iter()
+                .find(|s| s.name.eq_ignore_ascii_case(name))
+                .map(|s| s.bus_ids(network))
+                .ok_or_else(|| format!("Unknown subsystem '{}'", name))
+        };
+
+        let mut set = MonitorSet::default();
+        for record in records {
+            match record {
+                MonitorRecord::BranchesIn(name) => {
+                    let buses = lookup(name)?;
+                    set.branches
+                        .extend(network.branches.iter().enumerate().filter_map(|(i, br)| {
+                            (buses.contains(&br.from_bus) && buses.contains(&br.to_bus))
+                                .then_some(i)
+                        }));
+                }
+                MonitorRecord::TiesFrom(name) => {
+                    let buses = lookup(name)?;
+                    set.branches
+                        .extend(network.branches.iter().enumerate().filter_map(|(i, br)| {
+                            (buses.contains(&br.from_bus) != buses.contains(&br.to_bus))
+                                .then_some(i)
+                        }));
+                }
+                MonitorRecord::Branch {
+                    from_bus,
+                    to_bus,
+                    ckt,
+                } => {
+                    let found = network.find_branches(*from_bus, *to_bus, ckt);
+                    if found.is_empty() {
+                        return Err(format!(
+                            "No branch from {} to {} circuit {}",
+                            from_bus, to_bus, ckt
+                        ));
+                    }
+                    set.branches.extend(found);
+                }
+                MonitorRecord::VoltageRangeIn {
+                    subsystem,
+                    v_min,
+                    v_max,
+                } => {
+                    for bus_id in lookup(subsystem)? {
+                        set.buses.insert(bus_id, (*v_min, *v_max));
+                    }
+                }
+                MonitorRecord::VoltageRangeBus {
+                    bus_id,
+                    v_min,
+                    v_max,
+                } => {
+                    set.buses.insert(*bus_id, (*v_min, *v_max));
+                }
+            }
+        }
+        Ok(set)
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::case::*;
+use crate::cdf::columns;
+
+/// Nominal voltage (kV) from the voltage level code, the seventh character of
+/// a UCTE node code.
+fn nominal_kv(code: &str) -> f32 {
+    match code.chars().nth(6) {
+        Some('0') => 750.0,
+        Some('1') => 380.0,
+        Some('2') => 220.0,
+        Some('3') => 150.0,
+        Some('4') => 120.0,
+        Some('5') => 110.0,
+        Some('6') => 70.0,
+        Some('7') => 27.0,
+        Some('8') => 330.0,
+        Some('9') => 500.0,
+        _ => 0.0,
+    }
+}
+
+/// A numeric field, None when blank or malformed.
+fn number(line: &str, start: usize, end: usize) -> Option<f32> {
+    columns(line, start, end).parse().ok()
+}
+
+/// A transformer waiting for its regulation record.
+struct Transformer {
+    branch: usize,
+    rated_kv1: f32,
+    rated_kv2: f32,
+    base_kv1: f32,
+    base_kv2: f32,
+}
+
+/// Reads a UCTE-DEF exchange file into a Network.
+pub fn read_ucte(path: &str) -> Result<Network, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    parse_ucte_str(&content)
+}
+
+/// Parses a UCTE-DEF case into a Network. Nodes become buses numbered 1..n in
+/// file order and named by their node code, with one area per country block.
+/// Node demand becomes a load and generation (given with load sign) a
+/// generator. Impedances in ohms are converted on the node nominal voltage for
+/// lines and on rated voltage 1 for transformers; regulation records set the
+/// transformer ratio and phase shift at the current tap.
+pub fn parse_ucte_str(content: &str) -> Result<Network, String> {
+    let mut network = Network::new(String::new(), 100.0, 50.0);
+    let s_base = network.s_base;
+
+    #[derive(PartialEq)]
+    enum Block {
+        Comment,
+        Node,
+        Line,
+        Transformer,
+        Regulation,
+        Other,
+    }
+    let mut block = Block::Other;
+    let mut area = 0;
+    let mut bus_ids: HashMap<String, usize> = HashMap::new();
+    // Transformers by (node 1, node 2, order code)
+    let mut transformers: HashMap<(String, String, String), Transformer> = HashMap::new();
+
+    let bus_of = |bus_ids: &HashMap<String, usize>, code: &str, line: &str| {
+        bus_ids
+            .get(code)
+            .copied()
+            .ok_or_else(|| format!("Unknown UCTE node {} in: {}", code, line))
+    };
+
+    for line in content.lines() {
+        if let Some(tag) = line.strip_prefix("##") {
+            block = match tag.chars().next() {
+                Some('C') => Block::Comment,
+                Some('N') => Block::Node,
+                Some('Z') => {
+                    area += 1;
+                    Block::Node
+                }
+                Some('L') => Block::Line,
+                Some('T') if tag.starts_with("TT") => Block::Other,
+                Some('T') => Block::Transformer,
+                Some('R') => Block::Regulation,
+                _ => Block::Other,
+            };
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match block {
+            Block::Comment => {
+                if network.case_name.is_empty() {
+                    network.case_name = line.trim().to_string();
+                }
+            }
+
+            Block::Node => {
+                // Code (1-8), name (10-21), status (23), type (25), voltage (27-32),
+                // PL, QL, PG, QG, min PG, max PG, min QG, max QG in 8-column fields
+                let code = columns(line, 0, 8).to_string();
+                if code.is_empty() {
+                    return Err(format!("Bad UCTE node record: {}", line));
+                }
+                let bus_id = network.buses.len() + 1;
+                let bus_type = match columns(line, 24, 25) {
+                    "3" => BusType::Slack,
+                    "2" => BusType::PV,
+                    _ => BusType::PQ,
+                };
+                let nom_voltage = nominal_kv(&code);
+                let voltage = match number(line, 26, 32) {
+                    Some(kv) if kv > 0.0 && nom_voltage > 0.0 => kv / nom_voltage,
+                    _ => 1.0,
+                };
+
+                let mut bus = Bus::new(bus_id, code.clone(), bus_type);
+                bus.area = area.max(1);
+                bus.nom_voltage = nom_voltage;
+                bus.voltage = voltage;
+                network.buses.push(bus);
+                bus_ids.insert(code, bus_id);
+
+                let field = |start: usize| number(line, start, start + 7).unwrap_or(0.0);
+                let (pl, ql, pg, qg) = (field(33), field(41), field(49), field(57));
+                if pl != 0.0 || ql != 0.0 {
+                    network.loads.push(Load::new(
+                        network.loads.len(),
+                        bus_id,
+                        format!("Bus{}-1", bus_id),
+                        pl,
+                        ql,
+                    ));
+                }
+                if bus_type != BusType::PQ || pg != 0.0 || qg != 0.0 {
+                    let mut generator = Generator::new(
+                        network.generators.len(),
+                        bus_id,
+                        format!("Bus{}-1", bus_id),
+                    );
+                    // Generation and its limits are given with load sign
+                    generator.p_gen = -pg;
+                    generator.q_gen = -qg;
+                    generator.v_setpoint = voltage;
+                    let limits = |start: usize| match (
+                        number(line, start, start + 7),
+                        number(line, start + 8, start + 15),
+                    ) {
+                        (Some(a), Some(b)) => (-a.max(b), -a.min(b)),
+                        _ => (-9999.0, 9999.0),
+                    };
+                    (generator.p_min, generator.p_max) = limits(65);
+                    (generator.q_min, generator.q_max) = limits(81);
+                    network.generators.push(generator);
+                }
+            }
+
+            Block::Line => {
+                // Node 1 (1-8), node 2 (10-17), order code (19), status (21),
+                // R (23-28), X (30-35), B in uS (37-44), current limit (46-51), name (53-64)
+                let from_bus = bus_of(&bus_ids, columns(line, 0, 8), line)?;
+                let to_bus = bus_of(&bus_ids, columns(line, 9, 17), line)?;
+                let status: u8 = columns(line, 20, 21).parse().unwrap_or(0);
+                let kv = network.buses[from_bus - 1].nom_voltage;
+                let z_base = if kv > 0.0 { kv * kv / s_base } else { 1.0 };
+                let x = number(line, 29, 35).unwrap_or(0.0) / z_base;
+                let b = number(line, 36, 44).unwrap_or(0.0) * 1e-6 * z_base;
+                let rating = number(line, 45, 51).unwrap_or(0.0) * kv * 3f32.sqrt() / 1000.0;
+
+                network.branches.push(Branch {
+                    branch_type: BranchType::Line,
+                    id: network.branches.len(),
+                    from_bus,
+                    to_bus,
+                    ckt: columns(line, 18, 19).to_string(),
+                    branch_name: columns(line, 52, 64).to_string(),
+                    // 0-2 in operation, 7-9 out of operation
+                    branch_status: status < 7,
+                    resistance: number(line, 22, 28).unwrap_or(0.0) / z_base,
+                    // Bus bar couplers have no impedance
+                    reactance: if x.abs() < 1e-4 { 1e-4 } else { x },
+                    from_shunt_conductance: 0.0,
+                    from_shunt_susceptance: b / 2.0,
+                    to_shunt_conductance: 0.0,
+                    to_shunt_susceptance: b / 2.0,
+                    tap_ratio: 1.0,
+                    phase_shift: 0.0,
+                    impedance_table: None,
+                    operating_limit: rating,
+                    contingency_limit: rating,
+                    flow: 0.0,
+                });
+            }
+
+            Block::Transformer => {
+                // Node 1 (1-8, non-regulated winding), node 2 (10-17), order code (19),
+                // status (21), rated U1 (23-27), rated U2 (29-33), rated S (35-39),
+                // R (41-46), X (48-53), B in uS (55-62), G in uS (64-69),
+                // current limit (71-76), name (78-89)
+                let node1 = columns(line, 0, 8).to_string();
+                let node2 = columns(line, 9, 17).to_string();
+                let from_bus = bus_of(&bus_ids, &node1, line)?;
+                let to_bus = bus_of(&bus_ids, &node2, line)?;
+                let status: u8 = columns(line, 20, 21).parse().unwrap_or(0);
+                let base_kv1 = network.buses[from_bus - 1].nom_voltage;
+                let base_kv2 = network.buses[to_bus - 1].nom_voltage;
+                let rated_kv1 = number(line, 22, 27).unwrap_or(base_kv1);
+                let rated_kv2 = number(line, 28, 33).unwrap_or(base_kv2);
+                let z_base = if rated_kv1 > 0.0 {
+                    rated_kv1 * rated_kv1 / s_base
+                } else {
+                    1.0
+                };
+                let rating = match number(line, 70, 76) {
+                    Some(amps) if amps > 0.0 => amps * rated_kv1 * 3f32.sqrt() / 1000.0,
+                    _ => number(line, 34, 39).unwrap_or(0.0),
+                };
+
+                let branch = network.branches.len();
+                network.branches.push(Branch {
+                    branch_type: BranchType::TwoWinding,
+                    id: branch,
+                    from_bus,
+                    to_bus,
+                    ckt: columns(line, 18, 19).to_string(),
+                    branch_name: columns(line, 77, 89).to_string(),
+                    branch_status: status < 7,
+                    resistance: number(line, 40, 46).unwrap_or(0.0) / z_base,
+                    reactance: number(line, 47, 53).unwrap_or(0.0) / z_base,
+                    from_shunt_conductance: number(line, 63, 69).unwrap_or(0.0) * 1e-6 * z_base,
+                    from_shunt_susceptance: number(line, 54, 62).unwrap_or(0.0) * 1e-6 * z_base,
+                    to_shunt_conductance: 0.0,
+                    to_shunt_susceptance: 0.0,
+                    tap_ratio: 1.0,
+                    phase_shift: 0.0,
+                    impedance_table: None,
+                    operating_limit: rating,
+                    contingency_limit: rating,
+                    flow: 0.0,
+                });
+                let transformer = Transformer {
+                    branch,
+                    rated_kv1,
+                    rated_kv2,
+                    base_kv1,
+                    base_kv2,
+                };
+                set_tap(&mut network.branches[branch], &transformer, 1.0, 0.0);
+                let order = columns(line, 18, 19).to_string();
+                transformers.insert((node1, node2, order), transformer);
+            }
+
+            Block::Regulation => {
+                // Node 1, node 2, order code as for the transformer; phase
+                // regulation du (21-25), taps (27-28), tap (30-32), U (34-38);
+                // angle regulation du (40-44), theta (46-50), taps (52-53),
+                // tap (55-57), P (59-63), type ASYM / SYMM (65-68)
+                let key = (
+                    columns(line, 0, 8).to_string(),
+                    columns(line, 9, 17).to_string(),
+                    columns(line, 18, 19).to_string(),
+                );
+                let transformer = transformers
+                    .get(&key)
+                    .ok_or_else(|| format!("Regulation for unknown transformer: {}", line))?;
+
+                // Voltage step on the regulated winding (node 2)
+                let magnitude = match (number(line, 20, 25), number(line, 29, 32)) {
+                    (Some(du), Some(tap)) => 1.0 + tap * du / 100.0,
+                    _ => 1.0,
+                };
+                // Complex step of the angle regulation
+                let (re, im) = match (number(line, 39, 44), number(line, 54, 57)) {
+                    (Some(du), Some(tap)) => {
+                        let step = tap * du / 100.0;
+                        if columns(line, 64, 68) == "SYMM" {
+                            let angle = 2.0 * (step / 2.0).atan();
+                            (angle.cos(), angle.sin())
+                        } else {
+                            let theta = number(line, 45, 50).unwrap_or(90.0).to_radians();
+                            (1.0 + step * theta.cos(), step * theta.sin())
+                        }
+                    }
+                    _ => (1.0, 0.0),
+                };
+                let ratio = magnitude * re.hypot(im);
+                let shift = im.atan2(re).to_degrees();
+                set_tap(
+                    &mut network.branches[transformer.branch],
+                    transformer,
+                    ratio,
+                    shift,
+                );
+            }
+
+            Block::Other => {}
+        }
+    }
+
+    if network.buses.is_empty() {
+        return Err("No UCTE node data found".to_string());
+    }
+    network.number_circuits();
+    network.rebuild_bus_map();
+    Ok(network)
+}
+
+/// Sets the off-nominal ratio and phase shift of a transformer whose regulated
+/// winding (node 2) is stepped by `ratio` at `shift` degrees.
+fn set_tap(branch: &mut Branch, transformer: &Transformer, ratio: f32, shift: f32) {
+    let winding1 = if transformer.base_kv1 > 0.0 {
+        transformer.rated_kv1 / transformer.base_kv1
+    } else {
+        1.0
+    };
+    let winding2 = if transformer.base_kv2 > 0.0 {
+        transformer.rated_kv2 * ratio / transformer.base_kv2
+    } else {
+        ratio
+    };
+    branch.tap_ratio = winding1 / winding2;
+    // Shifting the node 2 side is a negative shift seen from node 1
+    branch.phase_shift = if shift == 0.0 { 0.0 } else { -shift };
+}
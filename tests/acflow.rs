@@ -0,0 +1,200 @@
+use mantis::case::{BusType, DcControl, DcConverter, DcLine, Load};
+use mantis::export::format_raw_v33;
+use mantis::parse::parse_raw_str;
+use mantis::rawx::{format_rawx, parse_rawx_str};
+
+mod common;
+use common::{close, two_bus};
+
+#[test]
+fn two_bus_case_matches_the_closed_form_solution() {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 50.0, 0.0));
+    let iterations = network.ac_load_flow().unwrap();
+    assert!(iterations <= 5, "{} iterations", iterations);
+
+    // P = V1 V2 sin(θ1 - θ2) / X and Q2 = 0 give V2 = cos θ2 and sin 2θ2 = -0.1
+    let theta = -0.5 * 0.1f32.asin();
+    assert!(close(network.buses[1].angle, theta.to_degrees(), 1e-3));
+    assert!(close(network.buses[1].voltage, theta.cos(), 1e-5));
+    assert!(close(network.branches[0].flow, 50.0, 1e-3));
+    assert!(close(network.generators[0].p_gen, 50.0, 1e-3));
+
+    // The line's reactive loss comes from the slack
+    let q_loss = 100.0 * theta.sin().powi(2) / 0.1;
+    assert!(close(network.generators[0].q_gen, q_loss, 1e-2));
+}
+
+#[test]
+fn zip_loads_are_drawn_at_the_solved_voltage() {
+    let mut load = Load::new(1, 2, "1".to_string(), 20.0, 5.0);
+    load.real_current = 15.0;
+    load.imag_current = 5.0;
+    load.real_admittance = 10.0;
+    load.imag_admittance = 10.0;
+    let mut network = two_bus(BusType::PQ, load.clone());
+    network.branches[0].resistance = 0.02;
+
+    let iterations = network.ac_load_flow().unwrap();
+    assert!(iterations <= 6, "{} iterations", iterations);
+
+    let v = network.buses[1].voltage;
+    assert!(v < 0.99);
+    let (p, q) = load.power_at(v);
+    assert!(p < 45.0 && q < 20.0);
+
+    // The slack supplies the ZIP load at the solved voltage plus I²Z losses
+    let theta = network.buses[1].angle.to_radians();
+    let (dr, di) = (1.0 - v * theta.cos(), -v * theta.sin());
+    let current2 = (dr * dr + di * di) / (0.02f32 * 0.02 + 0.1 * 0.1);
+    let slack = &network.generators[0];
+    assert!(close(slack.p_gen, p + 100.0 * current2 * 0.02, 1e-2));
+    assert!(close(slack.q_gen, q + 100.0 * current2 * 0.1, 1e-2));
+    assert!(close(network.branches[0].flow, slack.p_gen, 1e-3));
+}
+
+#[test]
+fn ac_load_flow_needs_a_slack_bus() {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 50.0, 0.0));
+    network.buses[0].bus_type = BusType::PV;
+    assert!(network.ac_load_flow().unwrap_err().contains("slack"));
+}
+
+#[test]
+fn raw_yq_is_negative_for_an_inductive_load() {
+    let raw = include_str!("data/five_bus_v33.raw").replacen(
+        "4, '1', 1, 2, 1, 0, 5, 0, 0, 0, 0, 1, 1, 0",
+        "4, '1', 1, 2, 1, 0, 5, 0, 0, 0, -3, 1, 1, 0",
+        1,
+    );
+    let network = parse_raw_str(&raw).unwrap();
+    let load = network.loads.iter().find(|l| l.bus_id == 4).unwrap();
+    assert_eq!(load.imag_admittance, 3.0);
+    assert!(
+        format_raw_v33(&network)
+            .unwrap()
+            .contains("4, '1', 1, 2, 1, 0, 5, 0, 0, 0, -3, 1, 1, 0")
+    );
+
+    let rawx = format_rawx(&network).unwrap();
+    let reread = parse_rawx_str(&rawx).unwrap();
+    assert_eq!(reread.loads, network.loads);
+    assert_eq!(
+        parse_raw_str(&format_raw_v33(&reread).unwrap())
+            .unwrap()
+            .loads,
+        network.loads
+    );
+}
+
+/// One end of a 500 kV, two-bridge LCC link on a 345 kV bus.
+fn converter(bus_id: usize, angle_min: f32, angle_max: f32) -> DcConverter {
+    DcConverter {
+        bus_id,
+        bridges: 2,
+        angle_max,
+        angle_min,
+        commutating_resistance: 0.0,
+        commutating_reactance: 10.0,
+        base_kv: 345.0,
+        transformer_ratio: 0.556,
+        tap: 1.0,
+        tap_max: 1.1,
+        tap_min: 0.9,
+        tap_step: 0.00625,
+    }
+}
+
+fn dc_line() -> DcLine {
+    DcLine {
+        name: "DC1".to_string(),
+        control: DcControl::Power,
+        resistance: 10.0,
+        setpoint: 100.0,
+        scheduled_voltage: 500.0,
+        switch_voltage: 400.0,
+        compounding_resistance: 5.0,
+        current_margin: 0.1,
+        rectifier: converter(1, 10.0, 20.0),
+        inverter: converter(2, 15.0, 25.0),
+    }
+}
+
+#[test]
+fn dc_line_transfer_holds_the_compounding_point() {
+    let line = dc_line();
+    let (drawn, delivered) = line.transfer();
+    assert!(close(drawn, 100.0, 1e-3));
+
+    // 5 I² + 500 I = 100 with the inverter 5 ohms below the scheduled 500 kV
+    let current = ((500.0f32 * 500.0 + 2000.0).sqrt() - 500.0) / 10.0;
+    assert!(close(delivered, (500.0 - 5.0 * current) * current, 1e-3));
+    assert!(close(drawn - delivered, 10.0 * current * current, 1e-3));
+
+    // Power ordered at the inverter
+    let mut line = dc_line();
+    line.setpoint = -100.0;
+    let (drawn, delivered) = line.transfer();
+    assert!(close(delivered, 100.0, 1e-3));
+    assert!(drawn > delivered);
+}
+
+#[test]
+fn converters_at_out_of_service_buses_block_the_line() {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 150.0, 0.0));
+    network.dc_lines.push(dc_line());
+    assert_eq!(network.hvdc_injections().len(), 2);
+
+    network.buses[1].bus_type = BusType::OUT;
+    network.buses[1].bus_status = false;
+    assert!(network.hvdc_injections().values().all(|&mw| mw == 0.0));
+}
+
+#[test]
+fn ac_load_flow_solves_the_converter_equations() {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 150.0, 20.0));
+    network.dc_lines.push(dc_line());
+    network.ac_load_flow().unwrap();
+
+    let (v1, v2) = (network.buses[0].voltage, network.buses[1].voltage);
+    let solution = network.dc_lines[0].ac_solution(v1, v2).unwrap();
+    let (rect, inv) = (&solution.rectifier, &solution.inverter);
+
+    // Power at the rectifier, the compounding point at 500 kV
+    assert!(close(rect.p, 100.0, 1e-3));
+    assert!(close(inv.dc_voltage, 500.0 - 5.0 * solution.current, 1e-2));
+    assert!(close(rect.p + inv.p, 10.0 * solution.current.powi(2), 1e-3));
+
+    // Angles within limits on stepped taps, both ends drawing MVAR
+    assert!((10.0..=20.0).contains(&rect.angle), "alpha {}", rect.angle);
+    assert!((15.0..=25.0).contains(&inv.angle), "gamma {}", inv.angle);
+    for c in [rect, inv] {
+        let steps = (c.tap - 0.9) / 0.00625;
+        assert!((steps - steps.round()).abs() < 1e-3, "tap {}", c.tap);
+        assert!(c.q > 0.0);
+    }
+    // The solved taps are written back
+    assert_eq!(network.dc_lines[0].rectifier.tap, rect.tap);
+    assert_eq!(network.dc_lines[0].inverter.tap, inv.tap);
+
+    // The slack covers the load and DC loss over the lossless AC line
+    let slack = &network.generators[0];
+    assert!(close(slack.p_gen, 150.0 + rect.p + inv.p, 1e-2));
+    let theta = network.buses[1].angle.to_radians();
+    let (dr, di) = (v1 - v2 * theta.cos(), -v2 * theta.sin());
+    let line_q = 100.0 * (dr * dr + di * di) / 0.1;
+    assert!(close(slack.q_gen, 20.0 + rect.q + inv.q + line_q, 1e-2));
+}
+
+#[test]
+fn rectifier_at_minimum_alpha_passes_current_control_to_the_inverter() {
+    let mut line = dc_line();
+    line.rectifier.tap_min = 1.0;
+    line.rectifier.tap_max = 1.0;
+    // Too little AC voltage for 500 kV at 10 degrees
+    let solution = line.ac_solution(0.9, 1.0).unwrap();
+    assert!(close(solution.rectifier.angle, 10.0, 1e-3));
+    assert!(solution.inverter.dc_voltage < 495.0);
+    // The inverter holds the current order less the 10% margin
+    let order = dc_line().transfer().0 / (500.0 + 5.0 * 0.2);
+    assert!(close(solution.current, 0.9 * order, 1e-3));
+}
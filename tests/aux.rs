@@ -0,0 +1,41 @@
+use mantis::case::BusType;
+use mantis::powerworld::{apply_aux, parse_aux_str, read_aux_case};
+
+const AUX: &str = include_str!("data/three_bus.aux");
+
+#[test]
+fn aux_case_maps_onto_the_network() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/three_bus.aux");
+    let (network, _) = read_aux_case(path).unwrap();
+
+    assert_eq!(network.case_name, "three_bus");
+    assert_eq!(network.buses.len(), 3);
+    assert_eq!(network.buses[0].bus_type, BusType::Slack);
+    assert_eq!(network.buses[2].area, 2);
+    assert_eq!(network.branches.len(), 3);
+    assert!(!network.branches[2].branch_status);
+    assert_eq!(network.branches[0].operating_limit, 150.0);
+    assert_eq!(network.generators[0].p_gen, 80.0);
+    assert_eq!(network.loads[1].real_load, 30.0);
+
+    // Two in-service shunts at bus 2 are summed; the open one at bus 3 is not
+    assert_eq!(network.buses[1].imag_shunt, 35.0);
+    assert_eq!(network.buses[2].imag_shunt, 0.0);
+}
+
+#[test]
+fn shunt_records_replace_the_bus_shunt() {
+    let blocks = parse_aux_str(AUX).unwrap();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/three_bus.aux");
+    let (mut network, _) = read_aux_case(path).unwrap();
+    network.buses[0].imag_shunt = 12.0;
+    network.buses[2].imag_shunt = 25.0;
+
+    // Applying the same file again leaves the shunts unchanged
+    apply_aux(&mut network, &blocks).unwrap();
+    assert_eq!(network.buses[1].imag_shunt, 35.0);
+    // Bus 3 has only an open shunt record, so its shunt is cleared
+    assert_eq!(network.buses[2].imag_shunt, 0.0);
+    // Bus 1 has no shunt records and keeps its own
+    assert_eq!(network.buses[0].imag_shunt, 12.0);
+}
@@ -0,0 +1,118 @@
+use mantis::capability::{parse_capability_csv, parse_gcp_str};
+use mantis::case::{BusType, CapabilityPoint, Generator, Load, Network};
+
+mod common;
+use common::{close, two_bus};
+
+fn point(p: f32, q_min: f32, q_max: f32) -> CapabilityPoint {
+    CapabilityPoint { p, q_min, q_max }
+}
+
+/// A slack bus at 1.04 pu and a PV bus whose generator dispatches 50 MW
+/// against a load of 50 MW and 60 MVAR, holding 1.05 pu within fixed limits
+/// of ±100 MVAR.
+fn pv_case() -> Network {
+    let mut network = two_bus(BusType::PV, Load::new(1, 2, "1".to_string(), 50.0, 60.0));
+    network.generators[0].v_setpoint = 1.04;
+    let mut machine = Generator::new(2, 2, "1".to_string());
+    machine.p_gen = 50.0;
+    machine.v_setpoint = 1.05;
+    (machine.q_min, machine.q_max) = (-100.0, 100.0);
+    network.generators.push(machine);
+    network
+}
+
+#[test]
+fn limits_follow_the_curve_at_the_dispatched_p() {
+    let mut machine = pv_case().generators[1].clone();
+    assert_eq!(machine.q_limits(), (-100.0, 100.0));
+
+    machine.capability = vec![point(0.0, -40.0, 30.0), point(100.0, -20.0, -10.0)];
+    assert_eq!(machine.q_limits(), (-30.0, 10.0));
+    machine.p_gen = -5.0;
+    assert_eq!(machine.q_limits(), (-40.0, 30.0));
+    machine.p_gen = 120.0;
+    assert_eq!(machine.q_limits(), (-20.0, -10.0));
+    machine.p_gen = 25.0;
+    let (q_min, q_max) = machine.q_limits();
+    assert!(close(q_min, -35.0, 1e-5));
+    assert!(close(q_max, 20.0, 1e-5));
+}
+
+#[test]
+fn ac_load_flow_holds_the_curve_limit() {
+    // Within its fixed limits the machine holds 1.05 pu
+    let mut network = pv_case();
+    network.ac_load_flow().unwrap();
+    assert!(close(network.buses[1].voltage, 1.05, 1e-5));
+    let q = network.generators[1].q_gen;
+    assert!(q > 60.0 && q < 100.0, "{}", q);
+
+    // At 50 MW the curve allows 10 MVAR, so the bus is held there and sags
+    let mut network = pv_case();
+    network.generators[1].capability = vec![point(0.0, -40.0, 30.0), point(100.0, -20.0, -10.0)];
+    network.ac_load_flow().unwrap();
+    assert!(close(network.generators[1].q_gen, 10.0, 1e-3));
+    assert!(network.buses[1].voltage < 1.04);
+    let held = network.buses[1].voltage;
+
+    // Dispatched at 20 MW the curve allows more
+    let mut network = pv_case();
+    network.generators[1].capability = vec![point(0.0, -40.0, 30.0), point(100.0, -20.0, -10.0)];
+    network.generators[1].p_gen = 20.0;
+    network.ac_load_flow().unwrap();
+    assert!(close(network.generators[1].q_gen, 22.0, 1e-3));
+    assert!(network.buses[1].voltage > held);
+}
+
+#[test]
+fn csv_curves_are_sorted_by_mw() {
+    let mut network = pv_case();
+    let csv = "bus,id,p,q_min,q_max\n\
+               2,1,100,-20,-10\n\
+               2,1,0,-40,30\n\
+               1,1,0,-50,50\n";
+    assert_eq!(parse_capability_csv(&mut network, csv).unwrap(), 2);
+    assert_eq!(
+        network.generators[1].capability,
+        vec![point(0.0, -40.0, 30.0), point(100.0, -20.0, -10.0)]
+    );
+    assert_eq!(
+        network.generators[0].capability,
+        vec![point(0.0, -50.0, 50.0)]
+    );
+}
+
+#[test]
+fn a_bad_csv_row_leaves_every_curve_alone() {
+    let mut network = pv_case();
+    let before = network.clone();
+
+    let csv = "bus,id,p,q_min,q_max\n2,1,0,-40,30\n3,1,0,-40,30\n";
+    let err = parse_capability_csv(&mut network, csv).unwrap_err();
+    assert!(err.contains("Line 3"), "{}", err);
+    assert_eq!(network, before);
+
+    let csv = "bus,id,p,q_min,q_max\n2,1,0,-40,30\n1,1,0,40,30\n";
+    let err = parse_capability_csv(&mut network, csv).unwrap_err();
+    assert!(err.contains("Line 3") && err.contains("Qmin"), "{}", err);
+    assert_eq!(network, before);
+}
+
+#[test]
+fn gcp_records_give_qmax_before_qmin() {
+    let mut network = pv_case();
+    let gcp = "2, '1', 100, -10, -20, 0, 30, -40\n0\n";
+    assert_eq!(parse_gcp_str(&mut network, gcp).unwrap(), 1);
+    assert_eq!(
+        network.generators[1].capability,
+        vec![point(0.0, -40.0, 30.0), point(100.0, -20.0, -10.0)]
+    );
+
+    let mut network = pv_case();
+    let before = network.clone();
+    let gcp = "2, '1', 0, 30, -40\n2, '9', 0, 30, -40\n0\n";
+    let err = parse_gcp_str(&mut network, gcp).unwrap_err();
+    assert!(err.contains("Line 2"), "{}", err);
+    assert_eq!(network, before);
+}
@@ -0,0 +1,50 @@
+use mantis::case::{BranchType, BusType};
+use mantis::cdf::parse_cdf_str;
+
+#[test]
+fn cdf_case_maps_onto_the_network() {
+    let network = parse_cdf_str(include_str!("data/four_bus.cdf")).unwrap();
+
+    assert_eq!(network.case_name, "FOUR BUS CDF TEST");
+    assert_eq!(network.s_base, 100.0);
+
+    assert_eq!(network.buses.len(), 4);
+    assert_eq!(network.buses[0].bus_name, "NORTH    230");
+    assert_eq!(network.buses[0].bus_type, BusType::Slack);
+    assert_eq!(network.buses[1].bus_type, BusType::PV);
+    assert_eq!(network.buses[3].area, 2);
+    assert_eq!(network.buses[3].nom_voltage, 115.0);
+    assert_eq!(network.buses[2].angle, -12.72);
+    // Shunts are converted from per unit to MVAR at 1 pu
+    assert!((network.buses[2].imag_shunt - 19.0).abs() < 1e-4);
+
+    // A load per bus with demand, a generator per PV, swing or generating bus
+    assert_eq!(network.loads.len(), 3);
+    assert_eq!(network.loads[0].bus_id, 2);
+    assert_eq!(network.loads[0].real_load, 21.7);
+    assert_eq!(network.generators.len(), 2);
+    let generator = &network.generators[1];
+    assert_eq!(generator.gen_bus_id, 2);
+    assert_eq!(generator.p_gen, 40.0);
+    assert_eq!(generator.v_setpoint, 1.045);
+    assert_eq!(generator.q_max, 50.0);
+    assert_eq!(generator.q_min, -40.0);
+
+    assert_eq!(network.branches.len(), 3);
+    let line = &network.branches[0];
+    assert_eq!(line.branch_type, BranchType::Line);
+    assert_eq!(line.reactance, 0.05917);
+    assert_eq!(line.from_shunt_susceptance, 0.0264);
+    assert_eq!(line.operating_limit, 120.0);
+    assert_eq!(line.contingency_limit, 150.0);
+    let transformer = &network.branches[2];
+    assert_eq!(transformer.branch_type, BranchType::TwoWinding);
+    assert_eq!(transformer.tap_ratio, 0.978);
+}
+
+#[test]
+fn bad_records_are_rejected() {
+    let content = include_str!("data/four_bus.cdf").replace("   2    3  1", "   X    3  1");
+    assert!(parse_cdf_str(&content).is_err());
+    assert!(parse_cdf_str("").is_err());
+}
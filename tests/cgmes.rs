@@ -0,0 +1,67 @@
+use mantis::case::{BranchType, BusType};
+use mantis::cgmes::parse_cgmes_str;
+
+mod common;
+use common::near;
+
+#[test]
+fn cgmes_profiles_assemble_into_a_network() {
+    let network = parse_cgmes_str(&[
+        include_str!("data/cgmes/eq.xml"),
+        include_str!("data/cgmes/tp.xml"),
+        include_str!("data/cgmes/ssh.xml"),
+        include_str!("data/cgmes/sv.xml"),
+    ])
+    .unwrap();
+
+    assert_eq!(network.case_name, "THREE NODE CGMES TEST");
+
+    // Topological nodes become buses, with SV voltages as the initial state
+    assert_eq!(network.buses.len(), 3);
+    let names: Vec<&str> = network.buses.iter().map(|b| b.bus_name.as_str()).collect();
+    assert_eq!(names, ["BUS A", "BUS B", "BUS C"]);
+    assert_eq!(network.buses[0].bus_type, BusType::Slack);
+    assert_eq!(network.buses[2].nom_voltage, 110.0);
+    assert!(near(network.buses[0].voltage, 1.025));
+    assert!(near(network.buses[1].voltage, 0.99));
+    assert_eq!(network.buses[1].angle, -3.5);
+    // Two 0.1 mS sections at 110 kV
+    assert!(near(network.buses[2].imag_shunt, 2.42));
+
+    // Line impedances in per unit on 100 MVA and 400 kV
+    let line = &network.branches[0];
+    assert_eq!(line.branch_type, BranchType::Line);
+    assert_eq!((line.from_bus, line.to_bus), (1, 2));
+    assert!(near(line.resistance, 0.001));
+    assert!(near(line.reactance, 0.01));
+    assert!(near(line.from_shunt_susceptance, 0.08));
+    // 1000 A at 400 kV
+    assert!(near(line.operating_limit, 692.82));
+
+    let transformer = &network.branches[1];
+    assert_eq!(transformer.branch_type, BranchType::TwoWinding);
+    assert_eq!((transformer.from_bus, transformer.to_bus), (2, 3));
+    assert!(near(transformer.reactance, 0.02));
+    assert!(near(transformer.tap_ratio, 1.025));
+    assert_eq!(transformer.operating_limit, 250.0);
+
+    // SSH injections use the load sign convention
+    let generator = &network.generators[0];
+    assert_eq!(generator.gen_bus_id, 1);
+    assert_eq!(generator.p_gen, 300.0);
+    assert_eq!(generator.q_gen, 50.0);
+    assert_eq!(generator.q_max, 300.0);
+    assert_eq!(generator.p_max, 500.0);
+
+    let load = &network.loads[0];
+    assert_eq!(load.bus_id, 3);
+    assert_eq!((load.real_load, load.imag_load), (200.0, 40.0));
+}
+
+#[test]
+fn equipment_without_nodes_is_left_out() {
+    let network = parse_cgmes_str(&[include_str!("data/cgmes/eq.xml")]).unwrap();
+    // Without TP there are no topological nodes, and the EQ file has no connectivity nodes
+    assert!(network.buses.is_empty());
+    assert!(network.branches.is_empty());
+}
@@ -0,0 +1,35 @@
+//! Helpers shared by the integration tests. Each test crate uses its own
+//! subset of them.
+#![allow(dead_code)]
+
+use mantis::case::{Branch, BranchType, Bus, BusType, Generator, Load, Network};
+
+/// Whether `a` is within `tol` of `b`.
+pub fn close(a: f32, b: f32, tol: f32) -> bool {
+    (a - b).abs() < tol
+}
+
+/// Whether `a` matches `b` to a relative 1e-4, for values read back from a
+/// converted case.
+pub fn near(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4 * b.abs().max(1.0)
+}
+
+/// A slack bus with one generator feeding `load` at bus 2 over a lossless
+/// 0.1 pu line, with bus 2 of type `kind`. Tests add the device under study.
+pub fn two_bus(kind: BusType, load: Load) -> Network {
+    let mut network = Network::new("TWO BUS".to_string(), 100.0, 60.0);
+    network
+        .buses
+        .push(Bus::new(1, "SLACK".to_string(), BusType::Slack));
+    network.buses.push(Bus::new(2, "BUS 2".to_string(), kind));
+    network
+        .branches
+        .push(Branch::new(1, 1, 2, BranchType::Line, 0.0, 0.1));
+    network
+        .generators
+        .push(Generator::new(1, 1, "1".to_string()));
+    network.loads.push(load);
+    network.rebuild_bus_map();
+    network
+}
@@ -0,0 +1,118 @@
+use mantis::case::Network;
+use mantis::contingency::{
+    ChangeMode, ChangeUnit, ContingencyAction, PairCategory, branches_above_kv, format_con,
+    parse_con_str, screened_pairs,
+};
+use mantis::parse::parse_raw_str;
+
+fn load_case() -> Network {
+    parse_raw_str(include_str!("data/five_bus_v33.raw")).expect("fixture should parse")
+}
+
+fn apply(network: &mut Network, con: &str) -> Result<(), String> {
+    let contingencies = parse_con_str(con)?;
+    network.apply_contingency(&contingencies[0])
+}
+
+fn bus_load(network: &Network, bus_id: usize) -> f32 {
+    network
+        .loads
+        .iter()
+        .filter(|l| l.bus_id == bus_id && l.load_status)
+        .map(|l| l.real_load)
+        .sum()
+}
+
+#[test]
+fn con_roundtrip_is_stable() {
+    let con = "CONTINGENCY 'LINE 1-2'\n\
+               OPEN BRANCH FROM BUS 1 TO BUS 2 CIRCUIT 1\n\
+               END\n\
+               CONTINGENCY GEN3\n\
+               REMOVE MACHINE 1 FROM BUS 3\n\
+               SET BUS 2 LOAD TO 120 MW\n\
+               CHANGE MACHINE 1 AT BUS 1 DISPATCH BY 10 PERCENT\n\
+               END\n\
+               END\n";
+    let parsed = parse_con_str(con).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parse_con_str(&format_con(&parsed)).unwrap(), parsed);
+}
+
+#[test]
+fn load_change_keeps_shares() {
+    let mut network = load_case();
+    apply(
+        &mut network,
+        "CONTINGENCY C\nSET BUS 2 LOAD TO 150 MW\nEND\n",
+    )
+    .unwrap();
+    assert_eq!(bus_load(&network, 2), 150.0);
+    assert_eq!(network.loads[0].real_load, 90.0);
+    assert_eq!(network.loads[0].imag_load, 30.0);
+
+    apply(
+        &mut network,
+        "CONTINGENCY C\nCHANGE BUS 2 LOAD BY -50 PERCENT\nEND\n",
+    )
+    .unwrap();
+    assert_eq!(bus_load(&network, 2), 75.0);
+}
+
+#[test]
+fn load_change_on_zero_load_fails() {
+    let mut network = load_case();
+    let result = apply(
+        &mut network,
+        "CONTINGENCY C\nSET BUS 4 LOAD TO 20 MW\nEND\n",
+    );
+    assert!(result.is_err());
+    assert_eq!(bus_load(&network, 4), 0.0);
+
+    // Leaving a zero load at zero is not an error
+    apply(
+        &mut network,
+        "CONTINGENCY C\nCHANGE BUS 4 LOAD BY 10 PERCENT\nEND\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn load_change_on_out_of_service_load_fails() {
+    let mut network = load_case();
+    let result = apply(
+        &mut network,
+        "CONTINGENCY C\nSET BUS 5 LOAD TO 20 MW\nEND\n",
+    );
+    assert!(result.is_err());
+    assert_eq!(network.loads[3].real_load, 30.0);
+}
+
+#[test]
+fn screened_n11_pairs_keep_both_orders_and_adjustments() {
+    let network = load_case();
+    let outages = branches_above_kv(&network, 200.0);
+    let adjustments = vec![ContingencyAction::DispatchChange {
+        bus_id: 3,
+        machine_id: "1".to_string(),
+        mode: ChangeMode::By,
+        value: 10.0,
+        unit: ChangeUnit::Mw,
+    }];
+
+    let pairs = screened_pairs(&network, &outages, PairCategory::N11, 0.0, &adjustments);
+    let n = outages.len();
+    assert_eq!(pairs.len(), n * (n - 1));
+    for pair in &pairs {
+        assert_eq!(pair.adjustments, adjustments);
+        assert!(
+            pairs
+                .iter()
+                .any(|p| p.first == pair.second && p.second == pair.first)
+        );
+    }
+
+    let n2 = screened_pairs(&network, &outages, PairCategory::N2, 0.0, &adjustments);
+    assert_eq!(n2.len(), n * (n - 1) / 2);
+    assert!(n2.iter().all(|p| p.adjustments.is_empty()));
+}
@@ -0,0 +1,51 @@
+function mpc = case4
+%CASE4  Four bus test case with a transformer and generator costs.
+
+%% MATPOWER Case Format : Version 2
+mpc.version = '2';
+
+%%-----  Power Flow Data  -----%%
+%% system MVA base
+mpc.baseMVA = 100;
+
+%% bus data
+%	bus_i	type	Pd	Qd	Gs	Bs	area	Vm	Va	baseKV	zone	Vmax	Vmin
+mpc.bus = [
+	1	3	0	0	0	0	1	1.02	0	230	1	1.1	0.9;
+	2	1	120	40	0	15	1	0.99	-3.5	230	1	1.05	0.95;
+	3	2	0	0	0	0	1	1.01	-1.2	230	1	1.1	0.9;
+	4	1	50	10	0	0	2	0.98	-5	115	1	1.1	0.9;
+];
+
+%% generator data
+%	bus	Pg	Qg	Qmax	Qmin	Vg	mBase	status	Pmax	Pmin
+mpc.gen = [
+	1	100	20	150	-100	1.02	100	1	250	10;
+	3	70	10	80	-40	1.01	100	1	120	0;
+	3	0	0	30	-30	1.01	100	0	50	0;
+];
+
+%% branch data
+%	fbus	tbus	r	x	b	rateA	rateB	rateC	ratio	angle	status	angmin	angmax
+mpc.branch = [
+	1	2	0.01	0.08	0.04	200	250	0	0	0	1	-360	360;
+	1	3	0.02	0.1	0.03	150	180	0	0	0	1	-360	360;
+	2	3	0.015	0.09	0.02	150	180	0	0	0	0	-360	360;
+	2	4	0.002	0.06	0	100	120	0	0.975	-2	1	-360	360;
+];
+
+%%-----  OPF Data  -----%%
+%% generator cost data
+mpc.gencost = [
+	2	0	0	3	0.02	15	100;
+	2	0	0	3	0.03	18	50;
+	1	100	50	2	0	0	50	900;
+];
+
+%% bus names
+mpc.bus_name = {
+	'NORTH';
+	'EAST';
+	'SOUTH';
+	'SPUR';
+};
@@ -0,0 +1,224 @@
+use mantis::case::{
+    Branch, BranchType, Bus, BusType, DcBranch, DcBus, Generator, Load, Network, VscAcControl,
+    VscConverter, VscDcControl,
+};
+use mantis::export::format_raw_v33;
+use mantis::parse::parse_raw_str;
+use mantis::rawx::format_rawx;
+
+mod common;
+use common::close;
+
+fn vsc(
+    ac_bus_id: usize,
+    dc_bus_id: usize,
+    dc_control: VscDcControl,
+    dc_setpoint: f32,
+) -> VscConverter {
+    VscConverter {
+        name: format!("VSC{}", dc_bus_id),
+        ac_bus_id,
+        dc_bus_id,
+        status: true,
+        dc_control,
+        dc_setpoint,
+        ac_control: VscAcControl::ReactivePower,
+        ac_setpoint: 0.0,
+        loss_a: 100.0,
+        loss_b: 0.5,
+        loss_min: 50.0,
+        s_max: 0.0,
+        i_max: 0.0,
+        q_max: 100.0,
+        q_min: -100.0,
+        p_ac: 0.0,
+        q_ac: 0.0,
+    }
+}
+
+fn dc_branch(from_bus: usize, to_bus: usize, resistance: f32) -> DcBranch {
+    DcBranch {
+        from_bus,
+        to_bus,
+        name: format!("DC{}-{}", from_bus, to_bus),
+        status: true,
+        resistance,
+        flow: 0.0,
+    }
+}
+
+/// Three 345 kV AC buses in a ring, the first the slack, with a 40 MW load
+/// at each of the others, and a converter at each on a meshed 400 kV DC grid:
+/// the first holds the DC voltage, the second delivers 100 MW and the third
+/// draws 150 MW.
+fn three_terminal() -> Network {
+    let mut network = Network::new("MTDC".to_string(), 100.0, 60.0);
+    for (bus_id, bus_type) in [(1, BusType::Slack), (2, BusType::PQ), (3, BusType::PQ)] {
+        let mut bus = Bus::new(bus_id, format!("BUS{}", bus_id), bus_type);
+        bus.nom_voltage = 345.0;
+        network.buses.push(bus);
+        network.dc_buses.push(DcBus {
+            dc_bus_id: bus_id,
+            name: format!("DC{}", bus_id),
+            voltage: 0.0,
+        });
+    }
+    for (from, to) in [(1, 2), (2, 3), (1, 3)] {
+        let id = network.branches.len();
+        network
+            .branches
+            .push(Branch::new(id, from, to, BranchType::Line, 0.01, 0.1));
+        network
+            .dc_branches
+            .push(dc_branch(from, to, 5.0 * to as f32));
+    }
+    network
+        .generators
+        .push(Generator::new(1, 1, "1".to_string()));
+    for bus_id in [2, 3] {
+        network
+            .loads
+            .push(Load::new(bus_id, bus_id, "1".to_string(), 40.0, 10.0));
+    }
+    network.vsc_converters = vec![
+        vsc(1, 1, VscDcControl::Voltage, 400.0),
+        vsc(2, 2, VscDcControl::Power, 100.0),
+        vsc(3, 3, VscDcControl::Power, -150.0),
+    ];
+    network.rebuild_bus_map();
+    network
+}
+
+/// DC current in kA drawn into the grid at each DC bus by its branches.
+fn branch_currents(network: &Network) -> Vec<f32> {
+    let voltage = |bus_id: usize| network.dc_buses[bus_id - 1].voltage;
+    let mut currents = vec![0.0; network.dc_buses.len()];
+    for br in network.dc_branches.iter().filter(|br| br.status) {
+        let current = (voltage(br.from_bus) - voltage(br.to_bus)) / br.resistance;
+        currents[br.from_bus - 1] += current;
+        currents[br.to_bus - 1] -= current;
+    }
+    currents
+}
+
+#[test]
+fn meshed_dc_grid_balances_current_and_losses() {
+    let mut network = three_terminal();
+    assert!(network.solve_dc_grid());
+
+    assert_eq!(network.dc_buses[0].voltage, 400.0);
+    let currents = branch_currents(&network);
+    for (k, conv) in network.vsc_converters.iter().enumerate().skip(1) {
+        // A power-controlled converter feeds its branches its setpoint plus losses
+        let v = network.dc_buses[k].voltage;
+        let drawn = conv.dc_setpoint + conv.loss(conv.dc_setpoint / v);
+        assert!(close(currents[k] * v, -drawn, 1e-2), "bus {}", k + 1);
+        assert_eq!(conv.p_ac, conv.dc_setpoint);
+    }
+    // The rectifier's bus is the highest, the inverter's the lowest
+    assert!(network.dc_buses[2].voltage > 400.0 && network.dc_buses[1].voltage < 400.0);
+
+    // The slack converter covers the branch and converter losses
+    let branch_loss: f32 = network
+        .dc_branches
+        .iter()
+        .map(|br| {
+            let dv =
+                network.dc_buses[br.from_bus - 1].voltage - network.dc_buses[br.to_bus - 1].voltage;
+            dv * dv / br.resistance
+        })
+        .sum();
+    let fed = currents[0] * 400.0;
+    let slack = &network.vsc_converters[0];
+    assert!(close(slack.p_ac, -fed - slack.loss(fed / 400.0), 1e-3));
+    let converter_loss: f32 = network
+        .vsc_converters
+        .iter()
+        .enumerate()
+        .map(|(k, c)| c.loss(currents[k]))
+        .sum();
+    let p_ac: f32 = network.vsc_converters.iter().map(|c| c.p_ac).sum();
+    assert!(close(-p_ac, branch_loss + converter_loss, 1e-2));
+}
+
+#[test]
+fn converters_off_an_energized_dc_bus_carry_nothing() {
+    let mut network = three_terminal();
+    network.dc_branches.truncate(1);
+    network.dc_branches[0].status = false;
+    network.vsc_converters.truncate(2);
+    assert!(network.solve_dc_grid());
+    // No loss_min is drawn through a converter whose DC side is open
+    assert!(network.vsc_converters.iter().all(|c| c.p_ac == 0.0));
+
+    // Nor through one at an out-of-service AC bus
+    let mut network = three_terminal();
+    network.buses[2].bus_status = false;
+    assert!(network.solve_dc_grid());
+    assert_eq!(network.vsc_converters[2].p_ac, 0.0);
+    let currents = branch_currents(&network);
+    assert!(close(currents[2], 0.0, 1e-4));
+}
+
+#[test]
+fn ac_load_flow_couples_the_dc_grid_through_converter_currents() {
+    let mut network = three_terminal();
+    network.vsc_converters[0].ac_setpoint = 20.0;
+    network.vsc_converters[2].ac_control = VscAcControl::PowerFactor;
+    network.vsc_converters[2].ac_setpoint = -0.95;
+    network.ac_load_flow().unwrap();
+
+    // Scheduled MVAR and power factor at the dispatched P
+    let convs = &network.vsc_converters;
+    assert!(close(convs[0].q_ac, 20.0, 1e-4));
+    let q = -150.0 * (1.0 - 0.95f32 * 0.95).sqrt() / 0.95;
+    assert!(close(convs[2].q_ac, q, 1e-3));
+
+    // Losses are taken at the AC current of the solved bus voltage
+    let currents = branch_currents(&network);
+    let fed = currents[0] * 400.0;
+    let current =
+        convs[0].p_ac.hypot(convs[0].q_ac) / (3f32.sqrt() * network.buses[0].voltage * 345.0);
+    assert!(close(convs[0].p_ac, -fed - convs[0].loss(current), 1e-3));
+}
+
+#[test]
+fn vsc_voltage_control_holds_its_bus_until_a_limit() {
+    let mut network = three_terminal();
+    network.vsc_converters[1].ac_control = VscAcControl::Voltage;
+    network.vsc_converters[1].ac_setpoint = 1.02;
+    network.ac_load_flow().unwrap();
+    assert!(close(network.buses[1].voltage, 1.02, 1e-5));
+    let q = network.vsc_converters[1].q_ac;
+    assert!(q > 0.0 && q < 100.0, "{}", q);
+
+    // The MVA rating leaves √(120² - 100²) MVAR beside the dispatched 100 MW
+    network.vsc_converters[1].s_max = 120.0;
+    network.vsc_converters[1].ac_setpoint = 1.1;
+    network.ac_load_flow().unwrap();
+    let q_max = (120.0f32 * 120.0 - 100.0 * 100.0).sqrt();
+    assert!(close(network.vsc_converters[1].q_ac, q_max, 1e-3));
+    assert!(network.buses[1].voltage < 1.1);
+}
+
+#[test]
+fn multi_terminal_dc_records_fail_the_read() {
+    let raw = include_str!("data/native/six_bus.raw").replacen(
+        "0 / END OF MULTI-TERMINAL DC DATA",
+        "'MT1', 2, 0, 0, 0\n0 / END OF MULTI-TERMINAL DC DATA",
+        1,
+    );
+    assert!(raw.contains("'MT1'"));
+    assert!(
+        parse_raw_str(&raw)
+            .unwrap_err()
+            .contains("multi-terminal DC")
+    );
+}
+
+#[test]
+fn meshed_dc_grids_fail_the_raw_writers() {
+    let network = three_terminal();
+    assert!(format_raw_v33(&network).unwrap_err().contains("DC grid"));
+    assert!(format_rawx(&network).unwrap_err().contains("DC grid"));
+}
@@ -0,0 +1,96 @@
+use mantis::case::{BranchType, BusType};
+use mantis::epc::parse_epc_str;
+use mantis::export::format_raw_v33;
+use mantis::parse::parse_raw_str;
+
+mod common;
+use common::near;
+
+const EPC: &str = include_str!("data/four_bus.epc");
+
+#[test]
+fn epc_case_maps_onto_the_network() {
+    let network = parse_epc_str(EPC).unwrap();
+
+    assert_eq!(network.case_name, "FOUR BUS EPC TEST");
+    assert_eq!(network.s_base, 100.0);
+
+    // Four buses plus the star point of the three-winding transformer
+    assert_eq!(network.buses.len(), 5);
+    let north = &network.buses[0];
+    assert_eq!(north.bus_type, BusType::Slack);
+    assert_eq!((north.v_max_operating, north.v_min_operating), (1.1, 0.9));
+    assert_eq!(
+        (north.v_max_contingency, north.v_min_contingency),
+        (1.15, 0.85)
+    );
+    assert_eq!(network.buses[2].bus_type, BusType::PV);
+    assert_eq!((network.buses[2].area, network.buses[2].zone), (2, 3));
+    assert_eq!(network.buses[2].owner, 2);
+
+    // Lines are in per unit on the system base, continued over two lines
+    let line = &network.branches[0];
+    assert_eq!((line.from_bus, line.to_bus, line.ckt.as_str()), (1, 2, "1"));
+    assert_eq!(line.branch_name, "N-S");
+    assert!(near(line.from_shunt_susceptance, 0.01));
+    assert_eq!(
+        (line.operating_limit, line.contingency_limit),
+        (300.0, 350.0)
+    );
+    assert!(!network.branches[1].branch_status);
+
+    // Transformer impedance converted from its 50 MVA base, tap from the primary
+    let transformer = &network.branches[2];
+    assert_eq!(transformer.branch_type, BranchType::TwoWinding);
+    assert!(near(transformer.resistance, 0.004));
+    assert!(near(transformer.reactance, 0.1));
+    assert!(near(transformer.tap_ratio, 1.025));
+    assert!(near(transformer.from_shunt_susceptance, -0.005));
+
+    // The three-winding unit becomes three branches to a new star bus
+    let star = &network.buses[4];
+    assert_eq!(star.bus_id, 5);
+    assert_eq!(star.nom_voltage, 230.0);
+    let legs: Vec<_> = network.branches.iter().filter(|b| b.to_bus == 5).collect();
+    assert_eq!(legs.len(), 3);
+    let reactances: Vec<f32> = legs.iter().map(|b| b.reactance).collect();
+    assert!(near(reactances[0], 0.07));
+    assert!(near(reactances[1], 0.03));
+    assert!(near(reactances[2], 0.13));
+
+    // Generators take the scheduled voltage of their bus
+    let generator = &network.generators[0];
+    assert_eq!(generator.v_setpoint, 1.04);
+    assert_eq!((generator.p_gen, generator.p_max), (150.0, 300.0));
+    assert_eq!((generator.q_min, generator.q_max), (-50.0, 100.0));
+
+    // Loads keep their constant current and impedance parts
+    let load = &network.loads[1];
+    assert_eq!((load.real_load, load.imag_load), (80.0, 20.0));
+    assert_eq!((load.real_current, load.imag_current), (5.0, 1.0));
+    assert_eq!((load.real_admittance, load.imag_admittance), (2.0, 0.5));
+
+    // Only the in-service shunt is counted, converted to MVAR
+    assert!(near(network.buses[2].imag_shunt, 30.0));
+
+    assert!(network.passthrough["area"].contains("NORTH AREA"));
+    assert!(network.passthrough["zone"].contains("ZONE THREE"));
+}
+
+#[test]
+fn epc_case_round_trips_through_raw() {
+    let mut network = parse_epc_str(EPC).unwrap();
+    // RAW v33 branch records have no name field
+    for branch in &mut network.branches {
+        if branch.branch_type == BranchType::Line {
+            branch.branch_name.clear();
+        }
+    }
+    let reread =
+        parse_raw_str(&format_raw_v33(&network).unwrap()).expect("written case should parse");
+
+    assert_eq!(reread.buses, network.buses);
+    assert_eq!(reread.loads, network.loads);
+    assert_eq!(reread.generators, network.generators);
+    assert_eq!(reread.branches, network.branches);
+}
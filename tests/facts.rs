@@ -0,0 +1,150 @@
+use mantis::case::{BusType, FactsDevice, Load, Network, SeriesControl, SeriesLink};
+use mantis::parse::parse_raw_str;
+
+mod common;
+use common::{close, two_bus};
+
+/// A slack bus feeding 50 MW and 20 MVAR at bus 2, with a FACTS device at
+/// bus 2.
+fn facts_case(device: FactsDevice) -> Network {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 50.0, 20.0));
+    network.facts_devices.push(device);
+    network
+}
+
+fn shunt(q_max: f32) -> FactsDevice {
+    FactsDevice {
+        name: "SVC".to_string(),
+        bus_id: 2,
+        status: true,
+        v_setpoint: 1.0,
+        q_max,
+        q_min: -q_max,
+        series: None,
+        q_shunt: 0.0,
+    }
+}
+
+/// A device at bus 1 whose series link runs to bus 2 beside the line.
+fn series(control: SeriesControl, set: (f32, f32)) -> FactsDevice {
+    FactsDevice {
+        name: "TCSC".to_string(),
+        bus_id: 1,
+        status: true,
+        v_setpoint: 1.0,
+        q_max: 0.0,
+        q_min: 0.0,
+        series: Some(SeriesLink {
+            to_bus: 2,
+            control,
+            p_setpoint: 0.0,
+            q_setpoint: 0.0,
+            setpoint_real: set.0,
+            setpoint_imag: set.1,
+            rating: 0.0,
+            reactance: 0.1,
+            flow: 0.0,
+        }),
+        q_shunt: 0.0,
+    }
+}
+
+#[test]
+fn shunt_link_holds_its_bus_within_current_limits() {
+    let mut network = facts_case(shunt(50.0));
+    network.ac_load_flow().unwrap();
+    assert!(close(network.buses[1].voltage, 1.0, 1e-5));
+    let q = network.facts_devices[0].q_shunt;
+    assert!(q > 20.0 && q < 50.0, "{}", q);
+    // The slack supplies none of the load's MVAR beyond the line's share
+    let slack_q = network.generators[0].q_gen;
+    let q_loss = 100.0 * (network.buses[1].angle.to_radians() / 2.0).sin().powi(2) * 4.0 / 0.1;
+    assert!(close(slack_q + q, 20.0 + q_loss, 1e-2));
+
+    // Past its limit the link is held there, scaled by the bus voltage
+    let mut network = facts_case(shunt(5.0));
+    network.ac_load_flow().unwrap();
+    let v = network.buses[1].voltage;
+    assert!(v < 1.0);
+    assert!(close(network.facts_devices[0].q_shunt, 5.0 * v, 1e-4));
+}
+
+#[test]
+fn series_impedance_shares_the_flow_with_the_line() {
+    let mut network = facts_case(series(SeriesControl::Impedance, (0.0, 0.1)));
+    network.ac_load_flow().unwrap();
+    let link = network.facts_devices[0].series.as_ref().unwrap();
+    assert!(close(link.flow, 25.0, 1e-3));
+    assert!(close(network.branches[0].flow, 25.0, 1e-3));
+}
+
+#[test]
+fn series_voltage_advances_the_link() {
+    // 0.05 pu in quadrature, leading the from bus
+    let mut network = facts_case(series(SeriesControl::Voltage, (0.05, 90.0)));
+    network.ac_load_flow().unwrap();
+    let link = network.facts_devices[0].series.as_ref().unwrap().clone();
+    let line = network.branches[0].flow;
+    assert!(link.flow > 40.0, "{}", link.flow);
+    assert!(close(link.flow + line, 50.0, 1e-3));
+
+    // The DC load flow shifts the link by the same angle
+    assert!(network.dc_approximation());
+    let link = network.facts_devices[0].series.as_ref().unwrap();
+    let shift = 0.05f32.atan();
+    assert!(close(
+        link.flow - network.branches[0].flow,
+        shift / 0.1 * 100.0,
+        1e-3
+    ));
+    assert!(close(link.flow + network.branches[0].flow, 50.0, 1e-3));
+}
+
+#[test]
+fn flow_control_is_scaled_back_to_the_rating() {
+    let mut device = series(SeriesControl::Flow, (0.0, 0.0));
+    let link = device.series.as_mut().unwrap();
+    (link.p_setpoint, link.q_setpoint, link.rating) = (40.0, 30.0, 25.0);
+    let mut network = facts_case(device);
+    network.ac_load_flow().unwrap();
+
+    // 50 MVA asked, 25 MVA at unity voltage allowed
+    let v = network.buses[1].voltage;
+    let link = network.facts_devices[0].series.as_ref().unwrap();
+    assert!(close(link.flow, 40.0 * 25.0 * v / 50.0, 1e-3));
+    assert!(close(network.branches[0].flow, 50.0 - link.flow, 1e-3));
+}
+
+#[test]
+fn raw_series_voltage_modes_are_read_in_polar_form() {
+    let raw = include_str!("data/native/six_bus.raw");
+    let record =
+        "'UPFC1', 2, 4, 1, 20, 5, 1.0, 50, 50, 0.9, 1.1, 1, 0, 0.05, 100, 1, 0, 0, 0, 0, ''";
+    assert!(raw.contains(record));
+    let with = |fields: &str| raw.replacen(record, fields, 1);
+
+    let network = parse_raw_str(&with(
+        "'SSSC', 2, 4, 4, 0, 0, 1.0, 0, 0, 0.9, 1.1, 1, 0, 0.08, 100, 1, 0.05, 90, 0, 0, ''",
+    ))
+    .unwrap();
+    let link = network.facts_devices[0].series.as_ref().unwrap();
+    assert_eq!(link.control, SeriesControl::Voltage);
+    assert_eq!(link.reactance, 0.08);
+    let (d, q) = link.series_voltage();
+    assert!(close(d, 0.0, 1e-6) && close(q, 0.05, 1e-6));
+
+    let network = parse_raw_str(&with(
+        "'IPFC', 2, 4, 7, 0, 0, 1.0, 0, 0, 0.9, 1.1, 1, 0, 0.05, 100, 1, 0.03, 0.04, 0, 0, ''",
+    ))
+    .unwrap();
+    let link = network.facts_devices[0].series.as_ref().unwrap();
+    assert!(close(link.setpoint_real, 0.05, 1e-6));
+    let (d, q) = link.series_voltage();
+    assert!(close(d, 0.03, 1e-6) && close(q, 0.04, 1e-6));
+
+    let error = parse_raw_str(&with(
+        "'SSSC', 2, 4, 4, 0, 0, 1.0, 0, 0, 0.9, 1.1, 1, 0, 0.05, 100, 1, 0.05, 90, 1, 0, ''",
+    ))
+    .unwrap_err();
+    assert!(error.contains("line current"));
+}
@@ -0,0 +1,93 @@
+use mantis::case::{BranchType, BusType, ImpedanceTable, Load, Network, TableLink};
+use mantis::export::format_raw_v33;
+use mantis::parse::parse_raw;
+
+mod common;
+use common::{close, two_bus};
+
+fn table(points: Vec<(f32, f32, f32)>) -> ImpedanceTable {
+    ImpedanceTable { number: 1, points }
+}
+
+/// A slack bus feeding 50 MW and 20 MVAR at bus 2 through a transformer of
+/// 0.02 + j0.1 pu, linked to `points` by its ratio.
+fn transformer_case(points: Vec<(f32, f32, f32)>) -> Network {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 50.0, 20.0));
+    let br = &mut network.branches[0];
+    br.branch_type = BranchType::TwoWinding;
+    br.resistance = 0.02;
+    br.tap_ratio = 1.05;
+    br.impedance_table = Some(TableLink {
+        table: 1,
+        by_angle: false,
+    });
+    network.impedance_tables.push(table(points));
+    network
+}
+
+#[test]
+fn factor_interpolates_both_parts_and_holds_the_ends() {
+    let t = table(vec![(0.9, 1.2, 0.1), (1.1, 0.8, -0.1)]);
+    assert_eq!(t.factor(0.8), (1.2, 0.1));
+    assert_eq!(t.factor(1.2), (0.8, -0.1));
+    let (re, im) = t.factor(1.05);
+    assert!(close(re, 0.9, 1e-6));
+    assert!(close(im, -0.05, 1e-6));
+    assert_eq!(table(Vec::new()).factor(1.0), (1.0, 0.0));
+}
+
+#[test]
+fn complex_factor_scales_resistance_and_reactance() {
+    let network = transformer_case(vec![(1.0, 2.0, 0.0), (1.1, 2.0, 0.5)]);
+    // At ratio 1.05 the factor is 2 + j0.25
+    let (r, x) = network.effective_impedances()[0];
+    assert!(close(r, 0.02 * 2.0 - 0.1 * 0.25, 1e-6));
+    assert!(close(x, 0.02 * 0.25 + 0.1 * 2.0, 1e-6));
+    assert_eq!(network.effective_reactances(), vec![x]);
+}
+
+#[test]
+fn ac_load_flow_uses_the_corrected_resistance() {
+    let mut corrected = transformer_case(vec![(1.0, 2.0, 0.2)]);
+    corrected.ac_load_flow().unwrap();
+
+    // The same transformer with the corrected impedance written in
+    let mut plain = transformer_case(Vec::new());
+    plain.impedance_tables.clear();
+    plain.branches[0].resistance = 0.02 * 2.0 - 0.1 * 0.2;
+    plain.branches[0].reactance = 0.02 * 0.2 + 0.1 * 2.0;
+    plain.ac_load_flow().unwrap();
+
+    let p = corrected.generators[0].p_gen;
+    assert!(p > 50.0, "{}", p);
+    assert!(close(p, plain.generators[0].p_gen, 1e-3));
+    assert!(close(
+        corrected.branches[0].flow,
+        plain.branches[0].flow,
+        1e-3
+    ));
+}
+
+#[test]
+fn v35_tables_keep_the_imaginary_part() {
+    let raw = include_str!("data/five_bus_v35.raw").replace(
+        "BEGIN IMPEDANCE CORRECTION DATA\n",
+        "BEGIN IMPEDANCE CORRECTION DATA\n\
+         1, 0.9, 1.1, 0.2, 1.0, 1.0, 0.0,\n\
+         1.1, 0.9, -0.1\n\
+         0, 0, 0\n",
+    );
+    let network = parse_raw(&raw).unwrap();
+    assert_eq!(
+        network.impedance_tables,
+        vec![table(vec![
+            (0.9, 1.1, 0.2),
+            (1.0, 1.0, 0.0),
+            (1.1, 0.9, -0.1)
+        ])]
+    );
+
+    // v33 has no field for the imaginary part
+    let err = format_raw_v33(&network).unwrap_err();
+    assert!(err.contains("complex factors"), "{}", err);
+}
@@ -0,0 +1,125 @@
+use mantis::case::{BusType, LineGroup, Network};
+use mantis::contingency::{PairCategory, branches_above_kv, parse_con_str, screened_pairs};
+use mantis::monitor::{MonitoredElement, RatingSet, ViolationFilter};
+use mantis::parse::parse_raw_str;
+
+/// Six buses with the multi-section line 1-6-3 '&1' as branches 5 and 6.
+fn load_case() -> Network {
+    parse_raw_str(include_str!("data/native/six_bus.raw")).expect("fixture should parse")
+}
+
+fn open_group(network: &mut Network) {
+    let con = "CONTINGENCY '&1'\nOPEN BRANCH FROM BUS 3 TO BUS 1 CIRCUIT &1\nEND\n";
+    let contingencies = parse_con_str(con).unwrap();
+    network.apply_contingency(&contingencies[0]).unwrap();
+}
+
+#[test]
+fn groups_are_read_with_their_sections() {
+    let network = load_case();
+    assert_eq!(
+        network.line_groups,
+        vec![LineGroup {
+            from_bus: 1,
+            to_bus: 3,
+            id: "&1".to_string(),
+            metered_from: true,
+            dummy_buses: vec![6],
+        }]
+    );
+    assert_eq!(
+        network.group_sections(&network.line_groups[0]),
+        Some(vec![5, 6])
+    );
+}
+
+#[test]
+fn sections_match_the_group_id() {
+    // A parallel circuit between the first pair of buses is not a section
+    let raw = include_str!("data/native/six_bus.raw").replace(
+        "1, 6, '&1',",
+        "1, 6, '2', 0.01, 0.1, 0.02, 150, 180, 0, 0, 0, 0, 0, 1, 1, 0, 1, 1, 0, 1, 0, 1, 0, 1\n\
+         1, 6, '&1',",
+    );
+    let network = parse_raw_str(&raw).unwrap();
+    assert_eq!(network.branches[5].ckt, "2");
+    assert_eq!(
+        network.group_sections(&network.line_groups[0]),
+        Some(vec![6, 7])
+    );
+
+    // Nor is a section whose circuit id differs
+    let raw = include_str!("data/native/six_bus.raw").replace("6, 3, '&1',", "6, 3, '1',");
+    let network = parse_raw_str(&raw).unwrap();
+    assert_eq!(network.group_sections(&network.line_groups[0]), None);
+}
+
+#[test]
+fn opening_the_group_takes_every_section_out() {
+    let mut network = load_case();
+    open_group(&mut network);
+    assert!(!network.branches[5].branch_status);
+    assert!(!network.branches[6].branch_status);
+    assert!(network.branches[1].branch_status);
+
+    // The dummy bus keeps its type and is left out of the solve
+    let dummy = &network.buses[5];
+    assert_eq!(dummy.bus_type, BusType::PQ);
+    assert!(dummy.bus_status);
+    assert!(network.isolated_dummy_buses().contains(&6));
+    assert!(network.dc_approximation());
+    assert_eq!(network.buses[5].voltage, 0.0);
+    assert_eq!(network.branches[5].flow, 0.0);
+    network.ac_load_flow().unwrap();
+    assert_eq!(network.buses[5].voltage, 0.0);
+
+    let violations = network.check_violations(RatingSet::Contingency, &ViolationFilter::default());
+    assert!(
+        violations
+            .iter()
+            .all(|v| v.element != MonitoredElement::Bus(6))
+    );
+}
+
+#[test]
+fn group_is_one_outage_and_one_violation() {
+    let mut network = load_case();
+    let outages = branches_above_kv(&network, 0.0);
+    let names: Vec<&str> = outages.iter().map(|c| c.name.as_str()).collect();
+    assert!(names.contains(&"BR_1_3_&1"));
+    assert!(!names.iter().any(|name| name.starts_with("BR_1_6")));
+    assert!(!names.iter().any(|name| name.starts_with("BR_6_3")));
+
+    assert!(network.dc_approximation());
+    for br in &mut network.branches[5..7] {
+        br.operating_limit = 1.0;
+    }
+    let violations = network.check_violations(RatingSet::Operating, &ViolationFilter::default());
+    let group: Vec<_> = violations
+        .iter()
+        .filter(|v| {
+            matches!(
+                &v.element,
+                MonitoredElement::LineGroup { from_bus: 1, to_bus: 3, id } if id == "&1"
+            )
+        })
+        .collect();
+    assert_eq!(group.len(), 1);
+    assert!(violations.iter().all(|v| !matches!(
+        v.element,
+        MonitoredElement::Branch { from_bus: 6, .. } | MonitoredElement::Branch { to_bus: 6, .. }
+    )));
+}
+
+#[test]
+fn screening_treats_the_sections_as_one_branch() {
+    let network = load_case();
+    // The group and its first section open the same circuit; together they
+    // do not island the dummy bus, so a loose threshold screens the pair out
+    let con = "CONTINGENCY GROUP\nOPEN BRANCH FROM BUS 1 TO BUS 3 CIRCUIT &1\nEND\n\
+               CONTINGENCY SECTION\nOPEN BRANCH FROM BUS 1 TO BUS 6 CIRCUIT &1\nEND\n\
+               END\n";
+    let outages = parse_con_str(con).unwrap();
+    let pairs = screened_pairs(&network, &outages, PairCategory::N2, 1000.0, &[]);
+    assert!(pairs.is_empty());
+}
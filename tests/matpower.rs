@@ -0,0 +1,63 @@
+use mantis::case::{BranchType, BusType, CostCurve};
+use mantis::matpower::{format_matpower, parse_matpower_str};
+
+#[test]
+fn matpower_case_maps_onto_the_network() {
+    let network = parse_matpower_str(include_str!("data/case4.m")).unwrap();
+
+    assert_eq!(network.case_name, "case4");
+    assert_eq!(network.s_base, 100.0);
+    assert_eq!(network.buses.len(), 4);
+    assert_eq!(network.buses[0].bus_type, BusType::Slack);
+    assert_eq!(network.buses[1].bus_name, "EAST");
+    assert_eq!(network.buses[1].imag_shunt, 15.0);
+    assert_eq!(network.buses[1].v_min_operating, 0.95);
+
+    // One load per bus with demand
+    assert_eq!(network.loads.len(), 2);
+    assert_eq!(network.loads[0].bus_id, 2);
+    assert_eq!(network.loads[0].real_load, 120.0);
+
+    assert_eq!(network.generators.len(), 3);
+    assert_eq!(network.generators[2].gen_bus_id, 3);
+    assert!(!network.generators[2].gen_status);
+    assert_eq!(network.generators[0].p_max, 250.0);
+
+    let cost = network.generators[0].cost.as_ref().unwrap();
+    assert_eq!(cost.curve, CostCurve::Polynomial(vec![0.02, 15.0, 100.0]));
+    let cost = network.generators[2].cost.as_ref().unwrap();
+    assert_eq!(cost.startup, 100.0);
+    assert_eq!(
+        cost.curve,
+        CostCurve::PiecewiseLinear(vec![(0.0, 0.0), (50.0, 900.0)])
+    );
+
+    assert_eq!(network.branches.len(), 4);
+    assert_eq!(network.branches[0].branch_type, BranchType::Line);
+    assert_eq!(network.branches[0].from_shunt_susceptance, 0.02);
+    assert!(!network.branches[2].branch_status);
+    let transformer = &network.branches[3];
+    assert_eq!(transformer.branch_type, BranchType::TwoWinding);
+    assert_eq!(transformer.tap_ratio, 0.975);
+    assert_eq!(transformer.phase_shift, -2.0);
+}
+
+#[test]
+fn matpower_roundtrip_reproduces_case() {
+    let network = parse_matpower_str(include_str!("data/case4.m")).unwrap();
+    let reread = parse_matpower_str(&format_matpower(&network, "case4")).unwrap();
+
+    assert_eq!(reread.buses, network.buses);
+    assert_eq!(reread.loads, network.loads);
+    assert_eq!(reread.generators, network.generators);
+    assert_eq!(reread.branches, network.branches);
+}
+
+#[test]
+fn short_rows_are_rejected() {
+    let content = include_str!("data/case4.m").replace(
+        "\t4\t1\t50\t10\t0\t0\t2\t0.98\t-5\t115\t1\t1.1\t0.9;",
+        "\t4\t1\t50;",
+    );
+    assert!(parse_matpower_str(&content).is_err());
+}
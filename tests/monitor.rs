@@ -0,0 +1,126 @@
+use mantis::case::{Branch, BranchType, Bus, BusType, Load, Network};
+use mantis::monitor::{LimitType, MonitoredElement, RatingSet, Violation, ViolationFilter};
+
+mod common;
+use common::{close, two_bus};
+
+/// The two-bus case at 230 kV with a 69 kV spur from bus 2 to bus 3, solved
+/// by the DC approximation: branch 1 carries 70 MW against 50 MW and branch 2
+/// carries 20 MW against 25 MW. Bus 2 sits at 0.85 pu and bus 3 at 1.10 pu.
+fn solved_case() -> Network {
+    let mut network = two_bus(BusType::PQ, Load::new(1, 2, "1".to_string(), 50.0, 0.0));
+    network
+        .buses
+        .push(Bus::new(3, "SPUR".to_string(), BusType::PQ));
+    network
+        .branches
+        .push(Branch::new(2, 2, 3, BranchType::Line, 0.0, 0.1));
+    network
+        .loads
+        .push(Load::new(2, 3, "1".to_string(), 20.0, 0.0));
+    network.rebuild_bus_map();
+    for (bus, kv) in network.buses.iter_mut().zip([230.0, 230.0, 69.0]) {
+        bus.nom_voltage = kv;
+    }
+    network.branches[0].operating_limit = 50.0;
+    network.branches[1].operating_limit = 25.0;
+    assert!(network.dc_approximation());
+    network.buses[1].voltage = 0.85;
+    network.buses[2].voltage = 1.10;
+    network
+}
+
+fn elements(violations: &[Violation]) -> Vec<MonitoredElement> {
+    violations.iter().map(|v| v.element.clone()).collect()
+}
+
+fn branch(id: usize, from_bus: usize, to_bus: usize) -> MonitoredElement {
+    MonitoredElement::Branch {
+        id,
+        from_bus,
+        to_bus,
+    }
+}
+
+#[test]
+fn violations_report_value_limit_and_percent() {
+    let network = solved_case();
+    let violations = network.check_violations(RatingSet::Operating, &ViolationFilter::default());
+    assert_eq!(violations.len(), 3, "{:?}", violations);
+
+    let low = &violations[0];
+    assert_eq!(low.element, MonitoredElement::Bus(2));
+    assert_eq!(low.limit_type, LimitType::LowVoltage);
+    assert_eq!((low.value, low.limit), (0.85, 0.9));
+    assert!(close(low.percent, 94.444, 1e-3), "{}", low.percent);
+
+    let high = &violations[1];
+    assert_eq!(high.element, MonitoredElement::Bus(3));
+    assert_eq!(high.limit_type, LimitType::HighVoltage);
+    assert_eq!((high.value, high.limit), (1.10, 1.05));
+    assert!(close(high.percent, 104.762, 1e-3), "{}", high.percent);
+
+    let flow = &violations[2];
+    assert_eq!(flow.element, branch(1, 1, 2));
+    assert_eq!(flow.limit_type, LimitType::BranchFlow);
+    assert!(close(flow.value, 70.0, 1e-3), "{}", flow.value);
+    assert_eq!(flow.limit, 50.0);
+    assert!(close(flow.percent, 140.0, 1e-3), "{}", flow.percent);
+}
+
+#[test]
+fn threshold_sets_the_reported_loading() {
+    let network = solved_case();
+
+    // Branch 2 is at 80% of its rating
+    let filter = ViolationFilter {
+        threshold: 80.0,
+        ..ViolationFilter::default()
+    };
+    let violations = network.check_violations(RatingSet::Operating, &filter);
+    assert_eq!(
+        elements(&violations)[2..],
+        [branch(1, 1, 2), branch(2, 2, 3)]
+    );
+    assert!(close(violations[3].percent, 80.0, 1e-3));
+
+    // Above 140% no branch is reported, and voltages are unaffected
+    let filter = ViolationFilter {
+        threshold: 150.0,
+        ..ViolationFilter::default()
+    };
+    let violations = network.check_violations(RatingSet::Operating, &filter);
+    assert_eq!(
+        elements(&violations),
+        [MonitoredElement::Bus(2), MonitoredElement::Bus(3)]
+    );
+}
+
+#[test]
+fn kv_filters_drop_buses_and_branches_outside_the_range() {
+    let network = solved_case();
+
+    // The 69 kV bus is dropped; the spur stays because bus 2 is at 230 kV
+    let filter = ViolationFilter {
+        min_kv: Some(100.0),
+        threshold: 80.0,
+        ..ViolationFilter::default()
+    };
+    let violations = network.check_violations(RatingSet::Operating, &filter);
+    assert_eq!(
+        elements(&violations),
+        [MonitoredElement::Bus(2), branch(1, 1, 2), branch(2, 2, 3)]
+    );
+
+    // Only the 69 kV bus and the branch that reaches it are left
+    let filter = ViolationFilter {
+        max_kv: Some(100.0),
+        threshold: 80.0,
+        ..ViolationFilter::default()
+    };
+    let violations = network.check_violations(RatingSet::Operating, &filter);
+    assert_eq!(
+        elements(&violations),
+        [MonitoredElement::Bus(3), branch(2, 2, 3)]
+    );
+}
@@ -0,0 +1,164 @@
+use mantis::case::{CapabilityPoint, CostCurve, GenCost, Network};
+use mantis::native::{Metadata, from_bytes, from_json, json_schema, read_native, to_json};
+
+const FOLDER: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/native");
+
+/// The six bus case as saved by the build that wrote schema `version`.
+fn fixture(version: u32, extension: &str) -> String {
+    format!("{}/v{}.{}", FOLDER, version, extension)
+}
+
+#[test]
+fn every_schema_version_migrates_from_json_and_binary() {
+    for version in 0..=11 {
+        let json = read_native(&fixture(version, "json")).unwrap();
+        let binary = read_native(&fixture(version, "bin")).unwrap();
+        assert_eq!(json.schema_version, version);
+        assert_eq!(binary.schema_version, version);
+        assert_eq!(json.network, binary.network, "schema {}", version);
+
+        let network = &json.network;
+        assert_eq!(network.case_name, "SIX BUS NATIVE");
+        assert_eq!(network.buses.len(), 6);
+        assert_eq!(network.branches.len(), 8);
+        assert_eq!(network.generators.len(), 2);
+        assert!(network.buses.iter().all(|b| b.area >= 1));
+        assert!(network.generators.iter().all(|g| g.capability.is_empty()));
+
+        // Identifiers: filled in for schemas 0 and 1, read from schema 2 on
+        let ckts: Vec<&str> = network.branches.iter().map(|b| b.ckt.as_str()).collect();
+        let section = if version < 2 { "1" } else { "&1" };
+        assert_eq!(ckts[5..7], [section, section]);
+        assert!(network.generators.iter().all(|g| g.machine_id == "1"));
+        assert_eq!(network.loads[1].load_ident, "2");
+        assert_eq!(network.loads.len(), if version < 2 { 3 } else { 4 });
+        assert_eq!(network.loads.iter().all(|l| l.load_status), version < 2);
+
+        // ZIP load parts from schema 3
+        let zip = network.loads.iter().find(|l| l.bus_id == 4).unwrap();
+        let expected = if version < 3 { (0.0, 0.0) } else { (2.0, 3.0) };
+        assert_eq!((zip.real_current, zip.imag_admittance), expected);
+
+        // Components each schema added, empty before it
+        assert_eq!(network.dc_lines.len(), (version >= 4) as usize);
+        assert_eq!(network.dc_buses.len(), if version >= 5 { 2 } else { 0 });
+        assert_eq!(
+            network.vsc_converters.len(),
+            if version >= 5 { 2 } else { 0 }
+        );
+        assert_eq!(network.facts_devices.len(), (version >= 6) as usize);
+        assert_eq!(network.impedance_tables.len(), (version >= 7) as usize);
+        let linked = network
+            .branches
+            .iter()
+            .filter(|b| b.impedance_table.is_some());
+        assert_eq!(linked.count(), (version >= 7) as usize);
+        assert_eq!(network.line_groups.len(), (version >= 8) as usize);
+    }
+
+    let dc = &read_native(&fixture(4, "bin")).unwrap().network.dc_lines[0];
+    assert_eq!(dc.name, "DC1");
+    assert_eq!((dc.rectifier.bus_id, dc.inverter.bus_id), (1, 4));
+    let facts = &read_native(&fixture(6, "json"))
+        .unwrap()
+        .network
+        .facts_devices[0];
+    assert_eq!(facts.series.as_ref().unwrap().to_bus, 4);
+    let group = &read_native(&fixture(8, "json"))
+        .unwrap()
+        .network
+        .line_groups[0];
+    assert_eq!(group.dummy_buses, [6]);
+}
+
+#[test]
+fn corrupted_cases_fail_their_checksum() {
+    for version in 1..=11 {
+        let content = std::fs::read_to_string(fixture(version, "json")).unwrap();
+        let edited = content.replacen("\"s_base\": 100.0", "\"s_base\": 101.0", 1);
+        assert_ne!(edited, content);
+        let error = from_json(&edited).unwrap_err();
+        assert!(
+            error.contains("Checksum mismatch"),
+            "schema {}: {}",
+            version,
+            error
+        );
+
+        let mut bytes = std::fs::read(fixture(version, "bin")).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let error = from_bytes(&bytes).unwrap_err();
+        assert!(
+            error.contains("Checksum mismatch"),
+            "schema {}: {}",
+            version,
+            error
+        );
+    }
+}
+
+/// The migrated six bus case with every optional part filled in.
+fn full_network() -> Network {
+    let mut network = read_native(&fixture(8, "json")).unwrap().network;
+    network.generators[0].cost = Some(GenCost {
+        startup: 100.0,
+        shutdown: 0.0,
+        curve: CostCurve::PiecewiseLinear(vec![(0.0, 0.0), (100.0, 2500.0)]),
+    });
+    network.generators[1].cost = Some(GenCost {
+        startup: 0.0,
+        shutdown: 0.0,
+        curve: CostCurve::Polynomial(vec![0.01, 20.0, 0.0]),
+    });
+    network.generators[0].capability = vec![
+        CapabilityPoint {
+            p: 0.0,
+            q_min: -60.0,
+            q_max: 60.0,
+        },
+        CapabilityPoint {
+            p: 100.0,
+            q_min: -30.0,
+            q_max: 40.0,
+        },
+    ];
+    network
+        .passthrough
+        .insert("area".to_string(), "[]".to_string());
+    network
+}
+
+#[test]
+fn json_schema_matches_the_serialized_case() {
+    let schema = json_schema();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+
+    let metadata = Metadata::now("schema test");
+    let json = to_json(&full_network(), &metadata, true).unwrap();
+    let mut instance: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let errors: Vec<String> = validator
+        .iter_errors(&instance)
+        .map(|e| format!("{} at {}", e, e.instance_path))
+        .collect();
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    // Round trip through the current schema
+    let mut case = from_json(&json).unwrap();
+    case.network.rebuild_bus_map();
+    assert_eq!(case.schema_version, mantis::native::SCHEMA_VERSION);
+    assert_eq!(case.network, full_network());
+
+    // The schema is closed: unknown fields and missing ones are rejected
+    instance["network"]["buses"][0]["extra"] = serde_json::json!(1);
+    assert!(!validator.is_valid(&instance));
+    instance["network"]["buses"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("extra");
+    assert!(validator.is_valid(&instance));
+    instance["network"]["generators"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("p_gen");
+    assert!(!validator.is_valid(&instance));
+}
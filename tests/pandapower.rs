@@ -0,0 +1,138 @@
+use mantis::case::{BranchType, BusType, Network};
+use mantis::pandapower::{format_pandapower, parse_pandapower_str};
+
+mod common;
+use common::near;
+
+const NET: &str = include_str!("data/seven_bus_pandapower.json");
+
+fn bus(network: &Network, bus_id: usize) -> &mantis::case::Bus {
+    network.buses.iter().find(|b| b.bus_id == bus_id).unwrap()
+}
+
+#[test]
+fn pandapower_elements_map_onto_the_network() {
+    let network = parse_pandapower_str(NET).unwrap();
+
+    // Bus 4 is switched onto bus 1, and the three-winding transformer adds
+    // star point bus 7
+    let ids: Vec<usize> = network.buses.iter().map(|b| b.bus_id).collect();
+    assert_eq!(ids, [0, 1, 2, 3, 5, 6, 7]);
+    assert_eq!(bus(&network, 0).bus_type, BusType::Slack);
+    assert_eq!(bus(&network, 0).voltage, 1.02);
+    assert_eq!(network.loads[1].bus_id, 1);
+
+    // The second line ends at the merged bus and its switch is open
+    let line = &network.branches[1];
+    assert_eq!((line.from_bus, line.to_bus), (0, 1));
+    assert!(!line.branch_status);
+    assert!(network.branches[0].branch_status);
+
+    // Two tap steps of 1.25 % on the high voltage side
+    let trafo = &network.branches[2];
+    assert_eq!(trafo.branch_type, BranchType::TwoWinding);
+    assert!(near(trafo.tap_ratio, 1.025));
+
+    // Three windings to the star point; each pair adds up to its
+    // short-circuit impedance on the smaller rating of the pair
+    let legs: Vec<_> = network.branches.iter().filter(|b| b.to_bus == 7).collect();
+    let froms: Vec<usize> = legs.iter().map(|b| b.from_bus).collect();
+    assert_eq!(froms, [1, 2, 3]);
+    assert_eq!(bus(&network, 7).nom_voltage, 110.0);
+    let pair = |a: usize, b: usize| legs[a].reactance + legs[b].reactance;
+    assert!(near(
+        pair(0, 1),
+        ((0.1f32 * 100.0 / 30.0).powi(2) - 0.01f32.powi(2)).sqrt()
+    ));
+    assert!(near(pair(1, 2), (0.09f32 - 0.0001).sqrt()));
+    assert!(near(pair(2, 0), (0.36f32 - 0.0004).sqrt()));
+    assert!(near(legs[1].tap_ratio, 1.0));
+    assert!(legs[0].from_shunt_susceptance < 0.0);
+
+    // The impedance is in per unit on its own 10 MVA
+    let impedance = network
+        .branches
+        .iter()
+        .find(|b| b.branch_name == "Z1")
+        .unwrap();
+    assert!(near(impedance.resistance, 0.1));
+    assert!(near(impedance.reactance, 0.5));
+
+    // The ward is a constant power plus constant impedance load
+    let ward = network
+        .loads
+        .iter()
+        .find(|l| l.load_name.ends_with("W1"))
+        .unwrap();
+    assert_eq!((ward.real_load, ward.imag_load), (5.0, 2.0));
+    assert_eq!((ward.real_admittance, ward.imag_admittance), (1.0, 0.5));
+
+    // The DC line is a pair of generators, less 2 % and 0.5 MW of losses
+    let ends: Vec<_> = network
+        .generators
+        .iter()
+        .filter(|g| g.gen_name.ends_with("HVDC"))
+        .collect();
+    assert_eq!((ends[0].gen_bus_id, ends[0].p_gen), (0, -20.0));
+    assert_eq!(ends[1].gen_bus_id, 6);
+    assert!(near(ends[1].p_gen, 19.1));
+    assert_eq!(bus(&network, 6).bus_type, BusType::PV);
+}
+
+#[test]
+fn unsupported_pandapower_elements_are_rejected() {
+    let with_xward = NET.replace(
+        r#"\"index\": [], \"data\": []"#,
+        r#"\"index\": [0], \"data\": [[\"X1\", 1, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, true]]"#,
+    );
+    assert_ne!(with_xward, NET);
+    let error = parse_pandapower_str(&with_xward).unwrap_err();
+    assert!(error.contains("xward"), "{}", error);
+
+    let with_impedance = NET.replace(r#"\"S1\", 0.0"#, r#"\"S1\", 0.5"#);
+    assert_ne!(with_impedance, NET);
+    assert!(parse_pandapower_str(&with_impedance).is_err());
+}
+
+#[test]
+fn pandapower_export_round_trips() {
+    let network = parse_pandapower_str(NET).unwrap();
+    let reread = parse_pandapower_str(&format_pandapower(&network)).unwrap();
+
+    let ids = |n: &Network| {
+        n.buses
+            .iter()
+            .map(|b| (b.bus_id, b.bus_type))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&reread), ids(&network));
+    // Impedances come back as lines, so compare branches by their ends
+    let sorted = |n: &Network| {
+        let mut branches = n.branches.clone();
+        branches.sort_by_key(|b| (b.from_bus, b.to_bus, b.branch_name.clone()));
+        branches
+    };
+    let (first, second) = (sorted(&network), sorted(&reread));
+    assert_eq!(first.len(), second.len());
+    for (a, b) in second.iter().zip(&first) {
+        assert_eq!((a.from_bus, a.to_bus), (b.from_bus, b.to_bus));
+        assert_eq!(a.branch_status, b.branch_status);
+        assert!(near(a.resistance, b.resistance), "{:?} {:?}", a, b);
+        assert!(near(a.reactance, b.reactance), "{:?} {:?}", a, b);
+        assert!(near(a.tap_ratio, b.tap_ratio), "{:?} {:?}", a, b);
+        assert!(near(a.from_shunt_susceptance, b.from_shunt_susceptance));
+    }
+    let total = |n: &Network| -> f32 { n.loads.iter().map(|l| l.power_at(1.0).0).sum() };
+    assert!(near(total(&reread), total(&network)));
+    // Controlled generators are written before fixed ones
+    let generation = |n: &Network| {
+        let mut p: Vec<(usize, f32)> = n
+            .generators
+            .iter()
+            .map(|g| (g.gen_bus_id, g.p_gen))
+            .collect();
+        p.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        p
+    };
+    assert_eq!(generation(&reread), generation(&network));
+}
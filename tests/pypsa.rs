@@ -0,0 +1,87 @@
+use mantis::case::{BranchType, BusType, Network};
+use mantis::pypsa::{read_pypsa, write_pypsa};
+
+mod common;
+use common::near;
+
+const FOLDER: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/pypsa");
+
+#[test]
+fn pypsa_folder_maps_onto_the_network() {
+    let network = read_pypsa(FOLDER).unwrap();
+
+    assert_eq!(network.case_name, "PYPSA TEST");
+
+    // Named buses are numbered in file order
+    let names: Vec<(usize, &str)> = network
+        .buses
+        .iter()
+        .map(|b| (b.bus_id, b.bus_name.as_str()))
+        .collect();
+    assert_eq!(names, [(1, "North"), (2, "South"), (3, "Town")]);
+    assert_eq!(network.buses[0].bus_type, BusType::Slack);
+    assert_eq!(network.buses[2].bus_type, BusType::PV);
+    assert_eq!(network.buses[2].v_max_operating, 1.05);
+
+    // Line ohms and siemens on the 380 kV base
+    let line = &network.branches[0];
+    assert!(near(line.resistance, 0.002));
+    assert!(near(line.reactance, 0.02));
+    assert!(near(line.from_shunt_susceptance, 0.1444));
+    assert_eq!(line.operating_limit, 1000.0);
+    assert_eq!(network.branches[1].branch_name, "NS, second");
+    assert!(!network.branches[1].branch_status);
+
+    // Transformer per unit on its own 200 MVA
+    let transformer = &network.branches[2];
+    assert_eq!(transformer.branch_type, BranchType::TwoWinding);
+    assert!(near(transformer.reactance, 0.05));
+    assert!(near(transformer.tap_ratio, 1.05));
+
+    let generator = &network.generators[1];
+    assert_eq!(generator.v_setpoint, 1.01);
+    assert!(near(generator.p_max, 90.0));
+    assert!(near(generator.p_min, 10.0));
+    assert_eq!(network.loads.len(), 3);
+    assert!(!network.loads[2].load_status);
+
+    // Shunt siemens become MVAR at the nominal voltage
+    assert!(near(network.buses[2].imag_shunt, 12.1));
+}
+
+#[test]
+fn pypsa_export_round_trips() {
+    let network = read_pypsa(FOLDER).unwrap();
+    let dir = std::env::temp_dir().join(format!("mantis-pypsa-{}", std::process::id()));
+    let dir = dir.to_str().unwrap();
+    write_pypsa(dir, &network).unwrap();
+    let reread = read_pypsa(dir).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+
+    // Bus ids are written as bus names
+    let buses = |n: &Network| {
+        n.buses
+            .iter()
+            .map(|b| (b.bus_id, b.bus_type, b.nom_voltage, b.imag_shunt))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(buses(&reread), buses(&network));
+    assert_eq!(reread.branches.len(), network.branches.len());
+    for (a, b) in reread.branches.iter().zip(&network.branches) {
+        assert_eq!((a.from_bus, a.to_bus), (b.from_bus, b.to_bus));
+        assert_eq!(a.branch_type, b.branch_type);
+        assert_eq!(a.branch_status, b.branch_status);
+        assert!(near(a.resistance, b.resistance));
+        assert!(near(a.reactance, b.reactance));
+        assert!(near(a.from_shunt_susceptance, b.from_shunt_susceptance));
+        assert!(near(a.tap_ratio, b.tap_ratio));
+    }
+    assert_eq!(reread.loads, network.loads);
+    for (a, b) in reread.generators.iter().zip(&network.generators) {
+        assert_eq!(
+            (a.gen_bus_id, a.p_gen, a.v_setpoint),
+            (b.gen_bus_id, b.p_gen, b.v_setpoint)
+        );
+        assert!(near(a.p_max, b.p_max));
+    }
+}
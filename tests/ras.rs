@@ -0,0 +1,84 @@
+use mantis::case::Network;
+use mantis::contingency::{ChangeMode, ChangeUnit, Contingency, ContingencyAction};
+use mantis::parse::parse_raw_str;
+use mantis::ras::{RasCondition, RemedialAction};
+
+fn load_case() -> Network {
+    parse_raw_str(include_str!("data/five_bus_v33.raw")).expect("fixture should parse")
+}
+
+fn line_outage(from_bus: usize, to_bus: usize) -> Contingency {
+    let mut contingency = Contingency::new(format!("LINE {}-{}", from_bus, to_bus));
+    contingency.actions.push(ContingencyAction::OpenBranch {
+        from_bus,
+        to_bus,
+        ckt: "1".to_string(),
+    });
+    contingency
+}
+
+fn flow_scheme(
+    name: &str,
+    from_bus: usize,
+    to_bus: usize,
+    action: ContingencyAction,
+) -> RemedialAction {
+    let mut scheme = RemedialAction::new(
+        name.to_string(),
+        RasCondition::FlowAbove {
+            from_bus,
+            to_bus,
+            ckt: "1".to_string(),
+            limit: 0.0,
+        },
+    );
+    scheme.actions.push(action);
+    scheme
+}
+
+#[test]
+fn schemes_with_the_same_name_fire_independently() {
+    let network = load_case();
+    let schemes = vec![
+        flow_scheme(
+            "SPS",
+            1,
+            3,
+            ContingencyAction::TripGenerator {
+                bus_id: 3,
+                machine_id: "1".to_string(),
+            },
+        ),
+        flow_scheme(
+            "SPS",
+            2,
+            3,
+            ContingencyAction::LoadChange {
+                bus_id: 2,
+                mode: ChangeMode::By,
+                value: -50.0,
+                unit: ChangeUnit::Percent,
+            },
+        ),
+    ];
+
+    let result = network.run_contingency_with_ras(&line_outage(1, 2), &schemes);
+    assert_eq!(result.fired, vec!["SPS", "SPS"]);
+    assert!(result.post_ras.is_some());
+}
+
+#[test]
+fn voltage_conditions_are_rejected() {
+    let network = load_case();
+    let schemes = vec![RemedialAction::new(
+        "UVLS".to_string(),
+        RasCondition::VoltageBelow {
+            bus_id: 2,
+            limit: 0.95,
+        },
+    )];
+
+    let result = network.run_contingency_with_ras(&line_outage(1, 2), &schemes);
+    assert!(result.fired.is_empty());
+    assert!(result.pre_ras.to_string().contains("UVLS"));
+}
@@ -0,0 +1,53 @@
+use mantis::case::{ImpedanceTable, Network};
+use mantis::export::format_raw_v33;
+use mantis::native::read_native;
+use mantis::parse::parse_raw_str;
+
+fn load_case() -> Network {
+    read_native("case.json")
+        .expect("case.json should parse")
+        .network
+}
+
+#[test]
+fn raw_roundtrip_reproduces_case() {
+    let network = load_case();
+    let reread =
+        parse_raw_str(&format_raw_v33(&network).unwrap()).expect("written case should parse");
+
+    assert_eq!(reread.case_name, network.case_name);
+    assert_eq!(reread.buses, network.buses);
+    assert_eq!(reread.loads, network.loads);
+    assert_eq!(reread.generators, network.generators);
+    assert_eq!(reread.branches, network.branches);
+    assert_eq!(reread, network);
+}
+
+#[test]
+fn raw_roundtrip_is_stable() {
+    let network = load_case();
+    let first = format_raw_v33(&network).unwrap();
+    let second =
+        format_raw_v33(&parse_raw_str(&first).expect("written case should parse")).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn impedance_tables_past_eleven_points_are_rejected() {
+    let mut network = load_case();
+    let points = (0..12).map(|k| (0.9 + 0.02 * k as f32, 1.0, 0.0));
+    network.impedance_tables.push(ImpedanceTable {
+        number: 7,
+        points: points.clone().take(11).collect(),
+    });
+    let reread = parse_raw_str(&format_raw_v33(&network).unwrap()).unwrap();
+    assert_eq!(reread.impedance_tables, network.impedance_tables);
+
+    network.impedance_tables.last_mut().unwrap().points = points.collect();
+    let err = format_raw_v33(&network).unwrap_err();
+    assert!(
+        err.contains("table 7") && err.contains("12 points"),
+        "{}",
+        err
+    );
+}
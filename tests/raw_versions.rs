@@ -0,0 +1,75 @@
+use mantis::case::Network;
+use mantis::parse::{parse_raw, raw_revision};
+
+fn v33_case() -> Network {
+    parse_raw(include_str!("data/five_bus_v33.raw")).expect("v33 fixture should parse")
+}
+
+#[test]
+fn revision_is_read_from_the_header() {
+    assert_eq!(raw_revision(include_str!("data/five_bus_v33.raw")), 33);
+    assert_eq!(raw_revision(include_str!("data/five_bus_v35.raw")), 35);
+    assert_eq!(raw_revision("0, 100, 0, 0, 1, 60 / NO REV"), 33);
+}
+
+#[test]
+fn v35_reads_the_same_network_as_v33() {
+    let v33 = v33_case();
+    let v35 = parse_raw(include_str!("data/five_bus_v35.raw")).expect("v35 fixture should parse");
+
+    assert_eq!(v33.buses.len(), 5);
+    assert_eq!(v33.loads.len(), 4);
+    assert_eq!(v33.generators.len(), 2);
+    assert_eq!(v33.branches.len(), 6);
+    assert_eq!(v35.s_base, v33.s_base);
+    assert_eq!(v35.buses, v33.buses);
+    assert_eq!(v35.loads, v33.loads);
+    assert_eq!(v35.generators, v33.generators);
+    assert_eq!(v35.branches, v33.branches);
+    assert_eq!(v35, v33);
+}
+
+#[test]
+fn unsupported_revisions_are_rejected() {
+    let content = include_str!("data/five_bus_v33.raw").replacen(", 33,", ", 29,", 1);
+    assert!(parse_raw(&content).is_err());
+}
+
+#[test]
+fn v33_reads_bus_voltage_limits() {
+    let network = v33_case();
+    let east = &network.buses[1];
+    assert_eq!(east.v_max_operating, 1.05);
+    assert_eq!(east.v_min_operating, 0.95);
+    assert_eq!(east.v_max_contingency, 1.1);
+    assert_eq!(east.v_min_contingency, 0.9);
+}
+
+#[test]
+fn v30_keeps_default_limits_and_reads_bus_shunts() {
+    let v30 = parse_raw(include_str!("data/five_bus_v30.raw")).expect("v30 fixture should parse");
+    let v33 = v33_case();
+
+    for bus in &v30.buses {
+        assert_eq!(bus.v_max_operating, 1.1);
+        assert_eq!(bus.v_min_operating, 0.9);
+        assert_eq!(bus.v_max_contingency, 1.1);
+        assert_eq!(bus.v_min_contingency, 0.9);
+    }
+    assert_eq!(v30.buses[1].angle, -2.0);
+    assert_eq!(v30.buses[3].imag_shunt, 10.0);
+    assert_eq!(v30.loads, v33.loads);
+    assert_eq!(v30.generators, v33.generators);
+    assert_eq!(v30.branches, v33.branches);
+}
+
+#[test]
+fn unknown_bus_type_is_an_error() {
+    let content = include_str!("data/five_bus_v33.raw").replacen(
+        "230, 1, 1, 1, 1, 1, -2",
+        "230, 7, 1, 1, 1, 1, -2",
+        1,
+    );
+    let error = parse_raw(&content).unwrap_err();
+    assert!(error.contains("bus type"));
+}
@@ -0,0 +1,80 @@
+use mantis::case::Network;
+use mantis::export::format_raw_v33;
+use mantis::parse::parse_raw_str;
+use mantis::rawx::{format_rawx, parse_rawx_str};
+
+fn load_case() -> Network {
+    parse_raw_str(include_str!("data/five_bus_v33.raw")).expect("fixture should parse")
+}
+
+#[test]
+fn rawx_roundtrip_reproduces_raw_case() {
+    let network = load_case();
+    let reread =
+        parse_rawx_str(&format_rawx(&network).unwrap()).expect("written RAWX should parse");
+
+    assert_eq!(reread.buses, network.buses);
+    assert_eq!(reread.loads, network.loads);
+    assert_eq!(reread.generators, network.generators);
+    assert_eq!(reread.branches, network.branches);
+
+    // And back to RAW
+    let raw = parse_raw_str(&format_raw_v33(&reread).unwrap()).expect("written RAW should parse");
+    assert_eq!(raw.branches, network.branches);
+    assert_eq!(format_rawx(&raw).unwrap(), format_rawx(&network).unwrap());
+}
+
+#[test]
+fn tap_uses_both_windings() {
+    // WINDV2 of 1.05 pu on the winding 2 side
+    let raw = include_str!("data/five_bus_v33.raw").replacen("\n1.0, 0\n", "\n1.05, 0\n", 1);
+    let network = parse_raw_str(&raw).unwrap();
+    let tap = network.branches[5].tap_ratio;
+    assert!((tap - 1.0 / 1.05).abs() < 1e-6);
+
+    // RAWX with the windings in kV (CW=2): 236.9 kV on 230 kV over 115 kV on 115 kV
+    let rawx = format_rawx(&load_case()).unwrap();
+    let mut doc: serde_json::Value = serde_json::from_str(&rawx).unwrap();
+    let transformer = &mut doc["network"]["transformer"];
+    let fields: Vec<String> = transformer["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f.as_str().unwrap().to_string())
+        .collect();
+    let row = transformer["data"][0].as_array_mut().unwrap();
+    let at = |name: &str| fields.iter().position(|f| f == name).unwrap();
+    row[at("cw")] = 2.into();
+    row[at("windv1")] = 236.9.into();
+    row[at("windv2")] = 115.0.into();
+    let network = parse_rawx_str(&doc.to_string()).unwrap();
+    assert!((network.branches[5].tap_ratio - 1.03).abs() < 1e-6);
+}
+
+#[test]
+fn hvdc_facts_tables_and_sections_survive_rawx() {
+    let network = parse_raw_str(include_str!("data/native/six_bus.raw")).unwrap();
+    assert_eq!(network.dc_lines.len(), 1);
+    assert_eq!(network.vsc_converters.len(), 2);
+    assert_eq!(network.facts_devices.len(), 1);
+    assert_eq!(network.impedance_tables.len(), 1);
+    assert_eq!(network.line_groups.len(), 1);
+
+    let rawx = format_rawx(&network).unwrap();
+    let reread = parse_rawx_str(&rawx).unwrap();
+    assert_eq!(reread.dc_lines, network.dc_lines);
+    assert_eq!(reread.dc_buses, network.dc_buses);
+    assert_eq!(reread.dc_branches, network.dc_branches);
+    assert_eq!(reread.vsc_converters, network.vsc_converters);
+    assert_eq!(reread.facts_devices, network.facts_devices);
+    assert_eq!(reread.impedance_tables, network.impedance_tables);
+    assert_eq!(reread.line_groups, network.line_groups);
+    assert_eq!(reread.branches, network.branches);
+    assert!(reread.passthrough.is_empty());
+
+    // And back to RAW
+    let raw = parse_raw_str(&format_raw_v33(&reread).unwrap()).unwrap();
+    assert_eq!(raw.dc_lines, network.dc_lines);
+    assert_eq!(raw.facts_devices, network.facts_devices);
+    assert_eq!(format_rawx(&raw).unwrap(), rawx);
+}
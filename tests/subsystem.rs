@@ -0,0 +1,116 @@
+use mantis::case::Network;
+use mantis::contingency::{Contingency, ContingencyAction, ContingencyPair, PairCategory};
+use mantis::monitor::{MonitoredElement, ViolationFilter};
+use mantis::parse::parse_raw_str;
+use mantis::ras::RemedialAction;
+use mantis::subsystem::parse_sub_str;
+
+fn load_case() -> Network {
+    parse_raw_str(include_str!("data/five_bus_v33.raw")).expect("fixture should parse")
+}
+
+fn line_outage(from_bus: usize, to_bus: usize) -> Contingency {
+    let mut contingency = Contingency::new(format!("LINE {}-{}", from_bus, to_bus));
+    contingency.actions.push(ContingencyAction::OpenBranch {
+        from_bus,
+        to_bus,
+        ckt: "1".to_string(),
+    });
+    contingency
+}
+
+/// Filter that reports every loaded branch touching area 2.
+fn area_filter() -> ViolationFilter {
+    ViolationFilter {
+        areas: vec![2],
+        threshold: 0.0,
+        ..ViolationFilter::default()
+    }
+}
+
+fn in_area_2(network: &Network, element: &MonitoredElement) -> bool {
+    let area = |bus_id: &usize| {
+        network
+            .buses
+            .iter()
+            .find(|b| b.bus_id == *bus_id)
+            .unwrap()
+            .area
+    };
+    match element {
+        MonitoredElement::Branch {
+            from_bus, to_bus, ..
+        } => area(from_bus) == 2 || area(to_bus) == 2,
+        MonitoredElement::Bus(bus_id) => area(bus_id) == 2,
+        MonitoredElement::LineGroup { .. } => false,
+    }
+}
+
+#[test]
+fn kv_ranges_stay_separate_selections() {
+    let subsystems =
+        parse_sub_str("SUBSYSTEM 'HV'\n KVRANGE 100 120\n KVRANGE 200 240\nEND\nEND\n").unwrap();
+    let hv = &subsystems[0];
+    assert_eq!(hv.selections.len(), 2);
+
+    let network = load_case();
+    let ids = hv.bus_ids(&network);
+    assert_eq!(ids.len(), 5);
+    assert_eq!(parse_sub_str(&format!("{}\nEND\n", hv)).unwrap()[0], *hv);
+}
+
+#[test]
+fn pair_results_use_the_filter() {
+    let network = load_case();
+    let pair = ContingencyPair::new(line_outage(1, 2), line_outage(1, 3), PairCategory::N11);
+    let filter = area_filter();
+
+    let result = network.run_pair_filtered(&pair, &filter);
+    let violations = &result.result.violations;
+    assert!(!violations.is_empty());
+    assert!(violations.iter().all(|v| in_area_2(&network, &v.element)));
+    let intermediate = result.intermediate.unwrap();
+    assert!(
+        intermediate
+            .violations
+            .iter()
+            .all(|v| in_area_2(&network, &v.element))
+    );
+}
+
+#[test]
+fn ras_results_use_the_filter() {
+    let network = load_case();
+    let schemes: Vec<RemedialAction> = Vec::new();
+    let result =
+        network.run_contingency_with_ras_filtered(&line_outage(1, 2), &schemes, &area_filter());
+    let violations = &result.pre_ras.violations;
+    assert!(!violations.is_empty());
+    assert!(violations.iter().all(|v| in_area_2(&network, &v.element)));
+}
+
+#[test]
+fn ranges_are_kept_as_ranges() {
+    let sub = "SUBSYSTEM 'S'\n BUSES 2 4\n BUS 5\n JOIN 'G'\n  AREAS 1 2\n  OWNERS 1 4000000000\n END\nEND\nEND\n";
+    let subsystems = parse_sub_str(sub).unwrap();
+    let s = &subsystems[0];
+    assert_eq!(s.selections[0].buses, vec![(2, 4), (5, 5)]);
+    assert_eq!(s.selections[1].owners, vec![(1, 4_000_000_000)]);
+
+    let network = load_case();
+    let mut ids: Vec<usize> = s.bus_ids(&network).into_iter().collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+    assert_eq!(parse_sub_str(&format!("{}\nEND\n", s)).unwrap()[0], *s);
+}
+
+#[test]
+fn backwards_ranges_and_empty_groups_are_rejected() {
+    for statement in ["BUSES 10 5", "AREAS 3 1", "ZONES 2 1", "OWNERS 9 2"] {
+        let sub = format!("SUBSYSTEM 'S'\n {}\nEND\nEND\n", statement);
+        let err = parse_sub_str(&sub).unwrap_err();
+        assert!(err.starts_with("Line 2:"), "{}", err);
+    }
+    let err = parse_sub_str("SUBSYSTEM 'S'\n JOIN 'G'\n END\nEND\nEND\n").unwrap_err();
+    assert!(err.starts_with("Line 3:"), "{}", err);
+}
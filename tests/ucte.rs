@@ -0,0 +1,54 @@
+use mantis::case::{BranchType, BusType};
+use mantis::ucte::parse_ucte_str;
+
+mod common;
+use common::near;
+
+#[test]
+fn ucte_case_maps_onto_the_network() {
+    let network = parse_ucte_str(include_str!("data/three_node.uct")).unwrap();
+
+    assert_eq!(network.case_name, "THREE NODE UCTE TEST");
+
+    // Node codes name the buses, numbered in file order
+    let names: Vec<&str> = network.buses.iter().map(|b| b.bus_name.as_str()).collect();
+    assert_eq!(names, ["FAAAAA11", "FBBBBB11", "FCCCCC51"]);
+    assert_eq!(network.buses[0].bus_type, BusType::Slack);
+    assert_eq!(network.buses[0].nom_voltage, 380.0);
+    assert_eq!(network.buses[2].nom_voltage, 110.0);
+    assert!(near(network.buses[0].voltage, 400.0 / 380.0));
+
+    // Generation and its limits are given with load sign
+    let generator = &network.generators[0];
+    assert_eq!((generator.p_gen, generator.q_gen), (500.0, 50.0));
+    assert_eq!((generator.p_min, generator.p_max), (100.0, 900.0));
+    assert_eq!((generator.q_min, generator.q_max), (-300.0, 300.0));
+    assert_eq!(network.loads.len(), 2);
+    assert_eq!(network.loads[1].bus_id, 3);
+
+    let z_base = 380.0 * 380.0 / 100.0;
+    let line = &network.branches[0];
+    assert_eq!(line.branch_type, BranchType::Line);
+    assert!(near(line.resistance, 1.6 / z_base));
+    assert!(near(line.reactance, 16.0 / z_base));
+    assert!(near(line.from_shunt_susceptance, 100e-6 * z_base / 2.0));
+    assert!(near(line.operating_limit, 380.0 * 3f32.sqrt()));
+    assert_eq!(network.branches[1].ckt, "2");
+    assert!(!network.branches[1].branch_status);
+
+    // Regulation at tap 2 of 1.25 % steps on the 400 kV winding
+    let transformer = &network.branches[2];
+    assert_eq!(transformer.branch_type, BranchType::TwoWinding);
+    assert_eq!((transformer.from_bus, transformer.to_bus), (3, 2));
+    assert!(near(transformer.reactance, 5.0 / 121.0));
+    assert!(near(transformer.tap_ratio, 1.0 / (400.0 * 1.025 / 380.0)));
+    assert_eq!(transformer.phase_shift, 0.0);
+}
+
+#[test]
+fn unknown_nodes_are_rejected() {
+    let content =
+        include_str!("data/three_node.uct").replace("FAAAAA11 FBBBBB11 1", "FXXXXX11 FBBBBB11 1");
+    assert!(parse_ucte_str(&content).is_err());
+    assert!(parse_ucte_str("##C\nempty\n").is_err());
+}